@@ -4,23 +4,51 @@
 
 #![warn(missing_docs, clippy::unwrap_used, clippy::expect_used)]
 
+use std::collections::hash_map::Entry;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
+use std::fmt;
 use std::io;
+use std::mem;
 use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
 use std::net::SocketAddr;
+use std::net::SocketAddrV4;
+use std::net::SocketAddrV6;
+use std::slice;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::ExchangeType;
+use isakmp::v1::definitions::IdentificationType;
 use isakmp::v1::definitions::NotifyMessageType;
+use isakmp::v1::generator::Identity;
+use isakmp::v1::generator::Lifetime;
 use isakmp::v1::generator::MessageBuilder;
 use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::DOI;
+use isakmp::v1::generator::PROPOSAL_NO;
+use isakmp::v1::generator::SITUATION;
+use isakmp::v1::parser::definitions::NotificationPayload;
+use isakmp::v1::parser::errors::IsakmpParseError;
+use isakmp::v1::parser::parse_packet;
+use serde::Deserialize;
+use serde::Serialize;
 use thiserror::Error;
 use tokio::net::UdpSocket;
 use tokio::select;
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::time::interval;
 use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
@@ -28,18 +56,492 @@ use tracing::instrument;
 use tracing::trace;
 use tracing::warn;
 
+use crate::recv::AbortOnDrop;
+use crate::recv::Framing;
 use crate::recv::ReceiveError;
-use crate::utils::gen_transforms::gen_v1_transforms;
+use crate::transport::IkeTransport;
+use crate::utils::bind::bind_with_retries;
+use crate::utils::dedup::dedup_transforms;
+use crate::utils::formatting::format_attribute;
+use crate::utils::formatting::format_raw_transform;
+use crate::utils::formatting::group_prime_bits;
+use crate::utils::formatting::MIN_GROUP_PRIME_BITS;
+use crate::utils::gen_transforms::chunk_transforms;
+use crate::utils::gen_transforms::gen_v1_transform_list;
+use crate::utils::gen_transforms::split_oversized;
+use crate::utils::gen_transforms::truncate_transforms;
+use crate::utils::gen_transforms::TransformFilter;
+use crate::utils::grading::grade_custom_group;
+use crate::utils::grading::grade_transforms;
+use crate::utils::grading::Grade;
+use crate::utils::jitter::jittered_interval;
+use crate::utils::matrix::build_matrix;
+use crate::utils::matrix::Matrix;
+use crate::utils::payload_to_transforms::payload_to_raw_transforms;
 use crate::utils::payload_to_transforms::payload_to_transforms;
+use crate::utils::payload_to_transforms::InvalidTransform;
+use crate::utils::payload_to_transforms::RawTransform;
+use crate::utils::record::message_transforms;
+use crate::utils::record::set_initiator_cookie;
+use crate::utils::record::ReplayError;
+use crate::utils::vendor_id::describe_vendor_id;
 
+pub mod many;
+#[cfg(feature = "socks5")]
+pub mod proxy;
 mod recv;
+pub mod socket;
+#[cfg(feature = "test-util")]
+pub mod testing;
+pub mod transport;
 pub mod utils;
 
+/// The port used for NAT-traversal.
+///
+/// IKE messages sent to and received from this port are prefixed with a non-ESP marker.
+pub const NAT_T_PORT: u16 = 4500;
+
+/// The default of [ScanOptions::receive_capacity]
+pub const DEFAULT_RECEIVE_CAPACITY: usize = 1024;
+
+/// Vendor IDs announcing support for NAT-traversal (RFC 3947 and its drafts)
+///
+/// These are the MD5 hashes of e.g. `RFC 3947` or `draft-ietf-ipsec-nat-t-ike-02`.
+pub const NAT_T_VENDOR_IDS: [[u8; 16]; 5] = [
+    // RFC 3947
+    [
+        0x4a, 0x13, 0x1c, 0x81, 0x07, 0x03, 0x58, 0x45, 0x5c, 0x57, 0x28, 0xf2, 0x0e, 0x95, 0x45,
+        0x2f,
+    ],
+    // draft-ietf-ipsec-nat-t-ike-00
+    [
+        0x44, 0x85, 0x15, 0x2d, 0x18, 0xb6, 0xbb, 0xcd, 0x0b, 0xe8, 0xa8, 0x46, 0x95, 0x79, 0xdd,
+        0xcc,
+    ],
+    // draft-ietf-ipsec-nat-t-ike-02
+    [
+        0xcd, 0x60, 0x46, 0x43, 0x35, 0xdf, 0x21, 0xf8, 0x7c, 0xfd, 0xb2, 0xfc, 0x68, 0xb6, 0xa4,
+        0x48,
+    ],
+    // draft-ietf-ipsec-nat-t-ike-02\n
+    [
+        0x90, 0xcb, 0x80, 0x91, 0x3e, 0xbb, 0x69, 0x6e, 0x08, 0x63, 0x81, 0xb5, 0xec, 0x42, 0x7b,
+        0x1f,
+    ],
+    // draft-ietf-ipsec-nat-t-ike-03
+    [
+        0x7d, 0x94, 0x19, 0xa6, 0x53, 0x10, 0xca, 0x6f, 0x2c, 0x17, 0x9d, 0x92, 0x15, 0x52, 0x9d,
+        0x56,
+    ],
+];
+
+/// The exchange types that are probed, see [ScanOptions::probe_exchange_types]
+pub const PROBED_EXCHANGE_TYPES: [ExchangeType; 5] = [
+    ExchangeType::Base,
+    ExchangeType::IdentityProtection,
+    ExchangeType::AuthenticationOnly,
+    ExchangeType::Aggressive,
+    ExchangeType::Informational,
+];
+
+/// The identity of the initiator in the aggressive mode probe, see [PROBED_EXCHANGE_TYPES]
+const PROBE_IDENTITY: &[u8] = b"ikebuster";
+
+/// Duration for which rejected proposals are kept, in case an acceptance arrives late
+const CLOSED_COOKIE_TTL: Duration = Duration::from_secs(30);
+
 /// The results of the scan
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ScanResult {
+    /// The scanned target
+    pub target: Option<SocketAddr>,
     /// All transforms that were accepted by the target server
     pub valid_transforms: Vec<Transform>,
+    /// Transforms that were found during enumeration, but weren't accepted again when sent
+    /// on their own
+    ///
+    /// Only populated if [ScanOptions::verify_findings] is set
+    pub unverified_transforms: Vec<Transform>,
+    /// All transforms that were rejected by the target server
+    pub rejected_transforms: Vec<Transform>,
+    /// Transforms of proposals the server never responded to, which were given up
+    ///
+    /// See [ScanOptions::response_timeout] and [ScanOptions::rate_limit]. This also includes
+    /// the proposals of a message the server accepted with an unknown proposal number or
+    /// only with transforms that weren't offered, and proposals answered with an error
+    /// notification only.
+    pub unanswered_transforms: Vec<Transform>,
+    /// All transforms that were planned to be tested
+    pub tested_transforms: Vec<Transform>,
+    /// Whether the server accepted any transform authenticated by a pre-shared key
+    ///
+    /// Pre-shared keys can be cracked offline if the server also supports aggressive mode
+    pub psk_accepted: bool,
+    /// The grade of the weakest accepted transform or custom group, `None` if neither
+    /// was accepted
+    ///
+    /// See [utils::grading] for the rules
+    pub grade: Option<Grade>,
+    /// The sizes in bits of the primes of the custom groups the server accepted
+    ///
+    /// Custom groups with a prime below [MIN_GROUP_PRIME_BITS](utils::formatting::MIN_GROUP_PRIME_BITS)
+    /// are weak.
+    pub custom_group_prime_bits: Vec<usize>,
+    /// Noteworthy behavior of the server that was observed while scanning
+    pub quirks: Vec<ServerQuirk>,
+    /// Whether all transforms were tested
+    ///
+    /// This is `false` if any of the [ScanResult::incomplete_reasons] applies. The transforms
+    /// excluded by [ScanOptions::transform_filter] don't count.
+    pub completed: bool,
+    /// Why not all transforms were tested, empty if the scan [completed](ScanResult::completed)
+    pub incomplete_reasons: Vec<IncompleteReason>,
+    /// Whether the server responded to any message
+    ///
+    /// If this is `false`, the server may not be running IKE or its traffic may be filtered
+    pub responded: bool,
+    /// All sent messages in the order they were sent
+    ///
+    /// Only populated if [ScanOptions::record] is set
+    pub sent_messages: Vec<Vec<u8>>,
+    /// The raw proposal payloads the server accepted, but which couldn't be converted
+    /// to transforms
+    pub unparsed_acceptances: Vec<Vec<u8>>,
+    /// The raw values of the transforms of [ScanResult::unparsed_acceptances]
+    ///
+    /// This reveals accepted values that are unknown, e.g. a cipher of the private use range
+    pub unknown_transforms: Vec<RawTransform>,
+    /// The exchange types the server answered without an error notification
+    ///
+    /// Only populated if [ScanOptions::probe_exchange_types] is set
+    pub supported_exchange_types: Vec<ExchangeType>,
+    /// The transforms the server chose out of the sent proposals, in the order of the responses
+    ///
+    /// If a proposal offers multiple transforms, the chosen one reveals the preference of
+    /// the server
+    pub choices: Vec<TransformChoice>,
+    /// The accepted proposals in the order of the responses
+    ///
+    /// Unlike [ScanResult::valid_transforms], this retains the security association and
+    /// proposal each transform was accepted in
+    pub accepted_proposals: Vec<AcceptedProposal>,
+    /// Whether the server sent NAT discovery payloads, indicating support for NAT-traversal
+    pub nat_discovery: bool,
+    /// Whether the server announced support for NAT-traversal by one of the [NAT_T_VENDOR_IDS]
+    pub nat_t_vendor_id: bool,
+    /// The vendor IDs the server sent, in the order they were first received
+    ///
+    /// Known vendor IDs are replaced by the name of the implementation, unknown ones are
+    /// hex encoded, see [utils::vendor_id::describe_vendor_id]
+    pub vendor_ids: Vec<String>,
+    /// The types of the notifications the server sent, in the order they were first received
+    ///
+    /// NO-PROPOSAL-CHOSEN is omitted, as it is the regular rejection of a proposal.
+    pub notifications: Vec<NotifyMessageType>,
+    /// Summary of the times between sending a proposal and receiving its response
+    ///
+    /// Late responses to proposals that were already sent again are not measured, as they
+    /// can't be attributed to a transmission. `None` if no response was measured.
+    pub round_trip_times: Option<RoundTripTimes>,
+    /// The responder cookie of the first response accepting a proposal
+    ///
+    /// This identifies the exchange the server created in packet captures.
+    /// `None` if no proposal was accepted.
+    pub responder_cookie: Option<u64>,
+}
+
+/// Summary of the round-trip times of the proposals, see [ScanResult::round_trip_times]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundTripTimes {
+    /// The shortest round-trip time
+    pub min: Duration,
+    /// The longest round-trip time
+    pub max: Duration,
+    /// The average round-trip time
+    pub avg: Duration,
+    /// Number of measured responses
+    pub count: usize,
+}
+
+impl RoundTripTimes {
+    /// Summarize the measured round-trip times, `None` if there are none
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        Some(Self {
+            min: *samples.iter().min()?,
+            max: *samples.iter().max()?,
+            avg: samples.iter().sum::<Duration>() / samples.len() as u32,
+            count: samples.len(),
+        })
+    }
+}
+
+impl ScanResult {
+    /// Compute the transforms that were added or removed in comparison to a `previous` result
+    pub fn diff(&self, previous: &ScanResult) -> ScanDiff {
+        let current: BTreeSet<&Transform> = self.valid_transforms.iter().collect();
+        let previous: BTreeSet<&Transform> = previous.valid_transforms.iter().collect();
+
+        ScanDiff {
+            added: current.difference(&previous).copied().cloned().collect(),
+            removed: previous.difference(&current).copied().cloned().collect(),
+        }
+    }
+
+    /// Group the accepted transforms by their authentication method
+    pub fn by_authentication_method(&self) -> BTreeMap<AuthenticationMethod, Vec<Transform>> {
+        let mut grouped: BTreeMap<AuthenticationMethod, Vec<Transform>> = BTreeMap::new();
+        for transform in &self.valid_transforms {
+            grouped
+                .entry(transform.authentication_method)
+                .or_default()
+                .push(transform.clone());
+        }
+        grouped
+    }
+
+    /// Retrieve the status of every tested transform
+    pub fn transform_statuses(&self) -> Vec<TestedTransform> {
+        let accepted: BTreeSet<&Transform> = self.valid_transforms.iter().collect();
+        let rejected: BTreeSet<&Transform> = self.rejected_transforms.iter().collect();
+        let unanswered: BTreeSet<&Transform> = self.unanswered_transforms.iter().collect();
+
+        self.tested_transforms
+            .iter()
+            .map(|transform| TestedTransform {
+                transform: transform.clone(),
+                status: if accepted.contains(transform) {
+                    TransformStatus::Accepted
+                } else if rejected.contains(transform) {
+                    TransformStatus::Rejected
+                } else if unanswered.contains(transform) {
+                    TransformStatus::Unanswered
+                } else {
+                    TransformStatus::Indeterminate
+                },
+            })
+            .collect()
+    }
+
+    /// Merge the results of multiple scans of the same host, e.g. of different ports
+    ///
+    /// The accepted transforms and quirks are combined, while keeping track of the targets
+    /// they were found on
+    pub fn merge(results: &[ScanResult]) -> MergedHostResult {
+        let mut valid_transforms: BTreeMap<&Transform, BTreeSet<SocketAddr>> = BTreeMap::new();
+        let mut quirks: BTreeMap<&ServerQuirk, BTreeSet<SocketAddr>> = BTreeMap::new();
+
+        for result in results {
+            let source = result.target.into_iter();
+            for transform in &result.valid_transforms {
+                valid_transforms
+                    .entry(transform)
+                    .or_default()
+                    .extend(source.clone());
+            }
+            for quirk in &result.quirks {
+                quirks.entry(quirk).or_default().extend(source.clone());
+            }
+        }
+
+        MergedHostResult {
+            targets: results.iter().filter_map(|x| x.target).collect(),
+            valid_transforms: valid_transforms
+                .into_iter()
+                .map(|(transform, found_on)| MergedTransform {
+                    transform: transform.clone(),
+                    found_on: found_on.into_iter().collect(),
+                })
+                .collect(),
+            quirks: quirks
+                .into_iter()
+                .map(|(quirk, found_on)| MergedQuirk {
+                    quirk: quirk.clone(),
+                    found_on: found_on.into_iter().collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// The distinct lifetimes of the security associations the server responded with
+    pub fn lifetimes(&self) -> BTreeSet<Lifetime> {
+        self.choices
+            .iter()
+            .filter_map(|choice| choice.lifetime)
+            .collect()
+    }
+
+    /// Build a matrix of the attributes `x` and `y` showing which combinations were accepted
+    ///
+    /// Returns `None` if one of the attribute types is not part of a [Transform]
+    pub fn matrix(&self, x: AttributeType, y: AttributeType) -> Option<Matrix> {
+        build_matrix(&self.valid_transforms, &self.rejected_transforms, x, y)
+    }
+}
+
+/// The reason why a scan didn't test all transforms, see [ScanResult::incomplete_reasons]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum IncompleteReason {
+    /// Only a sample of the transforms was tested, see [ScanOptions::max_transforms]
+    Sampled,
+    /// Proposals the server didn't respond to or answered with an error were given up,
+    /// see [ScanResult::unanswered_transforms]
+    Unanswered,
+    /// The scan stopped after the first accepted transform,
+    /// see [ScanOptions::stop_on_first_accept]
+    StoppedOnAccept,
+    /// The scan was cancelled, see [ScanOptions::cancel]
+    Cancelled,
+}
+
+/// The outcome of testing a transform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransformStatus {
+    /// The server accepted the transform
+    Accepted,
+    /// The server rejected the transform
+    Rejected,
+    /// The server never responded to the proposal containing the transform
+    Unanswered,
+    /// The server didn't answer conclusively for the transform
+    Indeterminate,
+}
+
+/// A tested transform together with its outcome
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestedTransform {
+    /// The tested transform
+    pub transform: Transform,
+    /// The outcome of the test
+    pub status: TransformStatus,
+}
+
+/// A transform the server chose out of a proposal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransformChoice {
+    /// The chosen transform
+    pub transform: Transform,
+    /// The transform number the server responded with
+    pub transform_no: u8,
+    /// Position of the transform in the sent proposal
+    pub index: usize,
+    /// Number of transforms in the sent proposal
+    pub offered: usize,
+    /// The lifetime of the security association the server responded with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifetime: Option<Lifetime>,
+    /// Time between sending the proposal and receiving the response,
+    /// see [ScanResult::round_trip_times]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rtt: Option<Duration>,
+}
+
+/// A proposal accepted by the server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AcceptedProposal {
+    /// The initiator cookie of the exchange
+    pub initiator_cookie: u64,
+    /// Position of the security association in the response
+    pub sa_index: usize,
+    /// Number of the proposal
+    pub proposal_no: u8,
+    /// The accepted transforms of the proposal
+    pub transforms: Vec<Transform>,
+}
+
+/// The combined results of multiple scans of the same host
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedHostResult {
+    /// The targets whose results were merged
+    pub targets: Vec<SocketAddr>,
+    /// All transforms accepted by any of the targets
+    pub valid_transforms: Vec<MergedTransform>,
+    /// All quirks observed on any of the targets
+    pub quirks: Vec<MergedQuirk>,
+}
+
+/// A transform of a [MergedHostResult]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedTransform {
+    /// The accepted transform
+    pub transform: Transform,
+    /// The targets that accepted the transform
+    pub found_on: Vec<SocketAddr>,
+}
+
+/// A quirk of a [MergedHostResult]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedQuirk {
+    /// The observed quirk
+    pub quirk: ServerQuirk,
+    /// The targets the quirk was observed on
+    pub found_on: Vec<SocketAddr>,
+}
+
+/// Noteworthy behavior of a server that is observed while scanning
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ServerQuirk {
+    /// The server accepted every transform it was offered and never rejected a proposal
+    AcceptsAll,
+    /// The server returned more than one transform in an accepted proposal
+    MultipleTransformsReturned,
+    /// The server responded with an IKEv2 message to an IKEv1 proposal
+    RespondedAsIkeV2,
+    /// The server answered with a security association of another domain of interpretation
+    /// or situation than proposed
+    UnexpectedSituation {
+        /// The domain of interpretation of the answer
+        doi: u32,
+        /// The situation of the answer
+        situation: Vec<u8>,
+    },
+    /// The server stopped responding for a while, see [ScanOptions::rate_limit]
+    RateLimited {
+        /// The backoff after which the server responded again the first time
+        backoff_ms: u64,
+    },
+    /// The server accepted a proposal with a number that wasn't offered
+    UnknownProposalNumber {
+        /// The number of the accepted proposal
+        proposal_no: u8,
+    },
+}
+
+impl fmt::Display for ServerQuirk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerQuirk::AcceptsAll => write!(f, "Accepts every offered transform"),
+            ServerQuirk::MultipleTransformsReturned => {
+                write!(f, "Returns multiple transforms in an accepted proposal")
+            }
+            ServerQuirk::RespondedAsIkeV2 => write!(f, "Responds to IKEv1 with IKEv2"),
+            ServerQuirk::UnexpectedSituation { doi, situation } => write!(
+                f,
+                "Answers with another situation than proposed (DOI {doi}, situation {situation:02x?})"
+            ),
+            ServerQuirk::RateLimited { backoff_ms } => {
+                write!(f, "Rate limits responses, recovered after {backoff_ms} ms")
+            }
+            ServerQuirk::UnknownProposalNumber { proposal_no } => {
+                write!(f, "Accepts proposals with a number that wasn't offered ({proposal_no})")
+            }
+        }
+    }
+}
+
+/// The difference between two [ScanResult]s
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanDiff {
+    /// Transforms that are accepted now, but weren't accepted previously
+    pub added: Vec<Transform>,
+    /// Transforms that were accepted previously, but aren't accepted anymore
+    pub removed: Vec<Transform>,
+}
+
+impl ScanDiff {
+    /// Check whether the accepted transforms are unchanged
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
 }
 
 /// Options to "configure" the scanner
@@ -47,45 +549,453 @@ pub struct ScanResult {
 pub struct ScanOptions {
     /// Target IP
     pub ip: IpAddr,
+    /// Scope ID of an IPv6 target, e.g. the index of the interface a link-local address is
+    /// reached over, `0` if unset
+    ///
+    /// See [socket::scope_id] to resolve the zone of an address like `fe80::1%eth0`
+    pub scope_id: u32,
     /// Target port
     pub port: u16,
+    /// Local port the messages are sent from, `0` for an ephemeral port
+    ///
+    /// Some servers only answer messages sent from port 500. Scans running at the same
+    /// time (see [many::ScanManyOptions::concurrency]) need distinct ports. This is
+    /// ignored if the scan is relayed through a proxy.
+    pub source_port: u16,
+    /// Scan using NAT-traversal (RFC 3947)
+    ///
+    /// Messages are sent to [NAT_T_PORT] instead of [ScanOptions::port] and are prefixed with
+    /// the non-ESP marker. This also changes the local port to [NAT_T_PORT], unless
+    /// [ScanOptions::source_port] is `0`.
+    pub nat_t: bool,
     /// Interval between each sent message
     pub interval: u64,
+    /// Randomize each interval by up to this duration in either direction
+    ///
+    /// This makes the timing of the scan less predictable for intrusion prevention systems
+    /// and rate limiters, see [utils::jitter::jittered_interval]. Zero disables the jitter.
+    pub jitter: Duration,
+    /// Time to wait for outstanding responses after the last message was sent or received
+    ///
+    /// Proposals which are still unanswered afterward are given up, so a server dropping
+    /// some of the messages doesn't stall the scan.
+    pub response_timeout: Duration,
+    /// Number of received messages that may be queued until the scan handles them,
+    /// `0` is treated as `1`
+    ///
+    /// This bounds the memory used when a host floods the scanner. While the queue is full,
+    /// datagrams wait in the receive buffer of the socket, which drops them once it is
+    /// full as well. Responses lost this way are handled like unanswered ones.
+    /// See [DEFAULT_RECEIVE_CAPACITY].
+    pub receive_capacity: usize,
+    /// Number of times a proposal is sent again if it isn't answered within
+    /// [ScanOptions::retransmit_interval]
+    ///
+    /// Every retransmission uses a fresh initiator cookie, late responses to the
+    /// previous cookie are ignored.
+    pub max_retries: usize,
+    /// Time after which an unanswered proposal is sent again, see [ScanOptions::max_retries]
+    pub retransmit_interval: Duration,
     /// Number of transforms to send in a single proposal
     pub transform_no: usize,
     /// The sleep to set when a valid transform is found.
     ///
-    /// This may be important as some servers timeout requests when requests aren't fully closed
+    /// This may be important as some servers timeout requests when requests aren't fully closed.
+    /// It is skipped if [ScanOptions::rate_limit] is set.
     pub sleep_on_transform_found: Duration,
+    /// Detect rate limiting by the server and back off until it responds again
+    ///
+    /// This replaces the fixed [ScanOptions::sleep_on_transform_found]
+    pub rate_limit: Option<RateLimitOptions>,
+    /// Number of times binding the local port is retried if it is in use
+    ///
+    /// See [utils::bind::bind_with_retries]
+    pub bind_retries: u32,
+    /// Name of the network interface the scan should be pinned to (e.g. `eth1`)
+    ///
+    /// This is only supported on linux
+    pub interface: Option<String>,
+    /// TTL (IPv4) or hop limit (IPv6) of the sent packets
+    ///
+    /// A low value confirms the server is on the local network segment. If the scan is
+    /// relayed through a proxy, this limits the packets sent to the proxy.
+    pub ttl: Option<u32>,
+    /// Set the DF (don't fragment) bit on the sent packets
+    ///
+    /// Messages exceeding the path MTU fail with [ScanError::MessageTooLarge] instead of
+    /// being fragmented and possibly dropped silently. This is only supported on linux.
+    pub dont_fragment: bool,
+    /// Re-send every found transform in its own proposal after the enumeration and only keep
+    /// the ones that are accepted again
+    ///
+    /// This eliminates false positives at the cost of additional traffic
+    pub verify_findings: bool,
+    /// Stop the scan as soon as any transform was accepted
+    ///
+    /// This answers whether a server accepts any of the transforms without enumerating all
+    /// of them. The remaining transforms are dropped, so [ScanResult::completed] is unset.
+    /// With [ScanOptions::verify_findings], the found transforms are still verified.
+    pub stop_on_first_accept: bool,
+    /// Maximum number of transforms to test
+    ///
+    /// Weak transforms are preferred when limiting the transforms, allowing a quick
+    /// sampling scan
+    pub max_transforms: Option<usize>,
+    /// The transforms to include in the scan
+    ///
+    /// This allows e.g. to only test weak transforms, see [TransformFilter::only_weak]
+    pub transform_filter: TransformFilter,
+    /// Test exactly these transforms instead of generating them
+    ///
+    /// [ScanOptions::transform_filter] is ignored, [ScanOptions::max_transforms] still
+    /// limits the transforms.
+    pub transforms: Option<Vec<Transform>>,
+    /// The order in which the attributes of each transform are sent
+    ///
+    /// Attributes that are missing are sent afterward in the default order,
+    /// see [isakmp::v1::generator::DEFAULT_ATTRIBUTE_ORDER]
+    pub attribute_order: Vec<AttributeType>,
+    /// Maximum size of a sent message in bytes
+    ///
+    /// Proposals that would exceed the size are split, as IKEv1 has no fragmentation and
+    /// messages larger than the path MTU are likely dropped
+    pub max_message_size: Option<usize>,
+    /// Keep all sent messages in [ScanResult::sent_messages], so they can be replayed
+    pub record: bool,
+    /// Send these previously recorded messages instead of generating the transforms
+    ///
+    /// The initiator cookie of each message is replaced by a random one, the remaining
+    /// bytes are sent unmodified. Proposals that have to be split are built anew.
+    pub replay: Option<Vec<Vec<u8>>>,
+    /// Send the first message of each of the [PROBED_EXCHANGE_TYPES] before scanning to
+    /// detect the supported exchange types
+    pub probe_exchange_types: bool,
+    /// Channel to report the progress of the scan to
+    ///
+    /// Use [utils::progress::watch_progress] to track the aggregated [utils::progress::ScanProgress]
+    pub progress: Option<UnboundedSender<ScanEvent>>,
+    /// Stop the scan early when the token is cancelled
+    ///
+    /// The scan returns the transforms found so far, with [ScanResult::completed] unset.
+    /// Proposals that are still awaiting a response are dropped.
+    pub cancel: Option<CancellationToken>,
+    /// Relay the scan through a SOCKS5 proxy
+    ///
+    /// The proxy has to support `UDP ASSOCIATE`, which many proxies don't.
+    #[cfg(feature = "socks5")]
+    pub proxy: Option<proxy::ProxyConfig>,
+}
+
+impl ScanOptions {
+    /// The address the messages are sent to, see [ScanOptions::nat_t]
+    pub fn target(&self) -> SocketAddr {
+        let port = if self.nat_t { NAT_T_PORT } else { self.port };
+        match self.ip {
+            IpAddr::V4(ip) => SocketAddr::V4(SocketAddrV4::new(ip, port)),
+            IpAddr::V6(ip) => SocketAddr::V6(SocketAddrV6::new(ip, port, 0, self.scope_id)),
+        }
+    }
+
+    /// The local port the messages are sent from, see [ScanOptions::nat_t]
+    pub fn local_port(&self) -> u16 {
+        if self.nat_t && self.source_port != 0 {
+            NAT_T_PORT
+        } else {
+            self.source_port
+        }
+    }
+}
+
+/// Detection of rate limiting, see [ScanOptions::rate_limit]
+///
+/// The server is considered to rate limit the scan if it doesn't respond to a number of
+/// consecutively sent proposals. The unanswered proposals are sent again after backing off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitOptions {
+    /// Number of proposals that may be sent without receiving any response
+    pub max_unanswered: usize,
+    /// The backoff when the server stops responding, doubled while it doesn't recover
+    pub backoff: Duration,
+    /// The maximum backoff, proposals still unanswered afterward are given up
+    pub max_backoff: Duration,
+}
+
+impl Default for RateLimitOptions {
+    fn default() -> Self {
+        Self {
+            max_unanswered: 8,
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(64),
+        }
+    }
+}
+
+/// Events that are reported while scanning, see [ScanOptions::progress]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanEvent {
+    /// A proposal was sent
+    ProposalSent {
+        /// The transforms of the proposal
+        transforms: Vec<Transform>,
+        /// Number of proposals that are still to be sent
+        remaining: usize,
+    },
+    /// The server accepted a transform
+    TransformAccepted(Transform),
+    /// The server rejected a proposal
+    ProposalRejected(Vec<Transform>),
+    /// A quirk of the server was detected
+    QuirkDetected(ServerQuirk),
+}
+
+/// Wait until the scan is cancelled, see [ScanOptions::cancel]
+///
+/// Never completes if there is no token
+async fn cancelled(cancel: &Option<CancellationToken>) {
+    match cancel {
+        Some(cancel) => cancel.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Add the types of the notifications to the `recorded` ones, see [ScanResult::notifications]
+///
+/// Each type is logged when it is first received, errors as warning
+fn record_notifications(
+    recorded: &mut Vec<NotifyMessageType>,
+    notifications: &[NotificationPayload],
+) {
+    for notification in notifications {
+        let notify_message_type = notification.notify_message_type;
+        if notify_message_type == NotifyMessageType::NoProposalChosen
+            || recorded.contains(&notify_message_type)
+        {
+            continue;
+        }
+
+        if notify_message_type.is_error() {
+            warn!("Server sent error notification {notify_message_type}");
+        } else {
+            info!("Server sent status notification {notify_message_type}");
+        }
+        recorded.push(notify_message_type);
+    }
+}
+
+/// Report an event to [ScanOptions::progress]
+fn emit(opts: &ScanOptions, event: ScanEvent) {
+    if let Some(progress) = &opts.progress {
+        // The receiver may have lost interest, which must not affect the scan
+        let _res = progress.send(event);
+    }
+}
+
+/// Check whether the authentication method relies on a pre-shared key
+pub fn is_pre_shared_key(authentication_method: AuthenticationMethod) -> bool {
+    matches!(
+        authentication_method,
+        // XAUTH is used with a pre-shared key for the initial authentication
+        AuthenticationMethod::PreSharedKey | AuthenticationMethod::XAUTH
+    )
+}
+
+/// Retrieve all transforms a scan with the given options would test
+pub fn planned_transforms(opts: &ScanOptions) -> Vec<Transform> {
+    plan_transforms(opts).0
+}
+
+/// Retrieve the transforms to test and whether they were limited by [ScanOptions::max_transforms]
+fn plan_transforms(opts: &ScanOptions) -> (Vec<Transform>, bool) {
+    let mut transforms = match &opts.transforms {
+        Some(transforms) => transforms.clone(),
+        None => gen_v1_transform_list(&opts.transform_filter),
+    };
+    dedup_transforms(&mut transforms);
+    let truncated = opts
+        .max_transforms
+        .is_some_and(|max_transforms| truncate_transforms(&mut transforms, max_transforms));
+    (transforms, truncated)
+}
+
+/// Split the transforms to test into the proposals to send
+///
+/// Returns whether the transforms were limited by [ScanOptions::max_transforms]
+fn plan_proposals(opts: &ScanOptions, framing: Framing) -> (VecDeque<Vec<Transform>>, bool) {
+    let (transforms, truncated) = plan_transforms(opts);
+    if truncated {
+        info!("Sampling {} transforms", transforms.len());
+    }
+    let mut todo = chunk_transforms(transforms, opts.transform_no);
+    if let Some(max_message_size) = opts.max_message_size {
+        let proposals = todo.len();
+        todo = split_oversized(todo, max_message_size, |transforms| {
+            message_builder(opts, transforms).build().0.len() + framing.overhead()
+        });
+        if todo.len() > proposals {
+            warn!("Split proposals exceeding {max_message_size} bytes, consider lowering the number of transforms per proposal");
+        }
+    }
+    (todo, truncated)
+}
+
+/// Retrieve the transforms of each proposal a scan with the given options would send
+///
+/// The transforms are split according to [ScanOptions::transform_no] and
+/// [ScanOptions::max_message_size]. Probes of [ScanOptions::probe_exchange_types] are not
+/// considered.
+pub fn planned_proposals(opts: &ScanOptions) -> Vec<Vec<Transform>> {
+    let framing = Framing {
+        non_esp_marker: opts.target().port() == NAT_T_PORT,
+    };
+    plan_proposals(opts, framing).0.into()
+}
+
+/// Build the messages a scan with the given options would send, without sending anything
+///
+/// Each message is returned with the transforms of its proposal. The initiator cookies are
+/// random, so they differ from the ones of an actual scan. Probes of
+/// [ScanOptions::probe_exchange_types] and [ScanOptions::replay] are not considered.
+pub fn planned_messages(opts: &ScanOptions) -> Vec<(Vec<Transform>, Vec<u8>)> {
+    let framing = Framing {
+        non_esp_marker: opts.target().port() == NAT_T_PORT,
+    };
+    plan_proposals(opts, framing)
+        .0
+        .into_iter()
+        .map(|transforms| {
+            let (msg, _) = message_builder(opts, &transforms).build();
+            (transforms, framing.wrap(msg))
+        })
+        .collect()
 }
 
 /// Scan the provided ip address
 #[instrument(skip_all)]
 pub async fn scan(opts: ScanOptions) -> Result<ScanResult, ScanError> {
     // Initialize udp socket
-    let addr = SocketAddr::new(opts.ip, opts.port);
+    let addr = opts.target();
+
+    #[cfg(feature = "socks5")]
+    let association = match &opts.proxy {
+        None => None,
+        Some(proxy) => {
+            info!("Associating with the UDP relay of {}", proxy.addr);
+            Some(
+                proxy::udp_associate(proxy)
+                    .await
+                    .map_err(ScanError::Proxy)?,
+            )
+        }
+    };
+    // Datagrams are sent to the relay of the proxy from an arbitrary port, as the relay
+    // uses its own port anyway
+    #[cfg(feature = "socks5")]
+    let (peer, local_port) = match &association {
+        None => (addr, opts.local_port()),
+        Some(association) => (association.relay, 0),
+    };
+    #[cfg(not(feature = "socks5"))]
+    let (peer, local_port) = (addr, opts.local_port());
 
     info!("Binding and starting to scan {addr}");
-    let socket = Arc::new(match addr.ip() {
-        IpAddr::V4(_) => UdpSocket::bind("0.0.0.0:500")
-            .await
-            .map_err(ScanError::CouldNotBind)?,
-        IpAddr::V6(_) => UdpSocket::bind("[::]:500")
-            .await
-            .map_err(ScanError::CouldNotBind)?,
-    });
-    socket.connect(&addr).await.map_err(ScanError::Receive)?;
-
-    let (tx, mut rx) = mpsc::unbounded_channel();
+    let local_ip = match peer.ip() {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    let socket = bind_with_retries(
+        || UdpSocket::bind(SocketAddr::new(local_ip, local_port)),
+        opts.bind_retries,
+    )
+    .await
+    .map_err(ScanError::CouldNotBind)?;
+    if let Some(interface) = &opts.interface {
+        socket::bind_to_interface(&socket, interface)
+            .map_err(|err| ScanError::CouldNotBindInterface(interface.clone(), err))?;
+    }
+    if let Some(ttl) = opts.ttl {
+        socket::set_ttl(&socket, ttl).map_err(ScanError::CouldNotSetTtl)?;
+    }
+    if opts.dont_fragment {
+        socket::set_dont_fragment(&socket).map_err(ScanError::CouldNotSetDontFragment)?;
+    }
+    socket.connect(&peer).await.map_err(ScanError::Receive)?;
+
+    #[cfg(feature = "socks5")]
+    if let Some(association) = association {
+        let transport = proxy::Socks5Transport::new(socket, association, addr);
+        return scan_with_transport(opts, Arc::new(transport)).await;
+    }
+    scan_with_transport(opts, Arc::new(socket)).await
+}
+
+/// Scan the provided ip address, blocking the current thread until the scan is finished
+///
+/// This allows scanning without an async runtime, the scan runs on a runtime of its own.
+/// It panics if called from within an async context. See [scan] for the details.
+pub fn scan_blocking(opts: ScanOptions) -> Result<ScanResult, ScanError> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(ScanError::Runtime)?
+        .block_on(scan(opts))
+}
+
+/// Scan the target of the options, exchanging the messages over the given transport
+///
+/// The socket options ([ScanOptions::bind_retries], [ScanOptions::interface],
+/// [ScanOptions::ttl], [ScanOptions::dont_fragment]) are not applied, as the transport is already set up.
+#[instrument(skip_all)]
+pub async fn scan_with_transport<T: IkeTransport>(
+    opts: ScanOptions,
+    transport: Arc<T>,
+) -> Result<ScanResult, ScanError> {
+    let addr = opts.target();
+
+    let (tx, mut rx) = mpsc::channel(opts.receive_capacity.max(1));
     let mut interval = interval(Duration::from_millis(opts.interval));
 
-    tokio::spawn(recv::handle_receive(socket.clone(), tx));
+    let framing = Framing {
+        non_esp_marker: addr.port() == NAT_T_PORT,
+    };
+    let _recv_task = AbortOnDrop(tokio::spawn(recv::handle_receive(
+        transport.clone(),
+        tx,
+        framing,
+    )));
+
+    // Recorded messages that are replayed instead of building them, by their transforms
+    let mut replay: BTreeMap<Vec<Transform>, Vec<u8>> = BTreeMap::new();
 
     // list of a list of transforms which should be sent in the future
-    let mut todo: VecDeque<Vec<_>> = gen_v1_transforms(opts.transform_no);
+    let (mut todo, truncated): (VecDeque<Vec<_>>, _) = match &opts.replay {
+        Some(messages) => {
+            let mut todo = VecDeque::new();
+            for msg in messages {
+                let transforms = message_transforms(msg).map_err(ScanError::InvalidReplay)?;
+                replay.insert(transforms.clone(), msg.clone());
+                todo.push_back(transforms);
+            }
+            info!("Replaying {} recorded messages", todo.len());
+            (todo, false)
+        }
+        None => plan_proposals(&opts, framing),
+    };
+
+    // Why not all transforms are tested, see ScanResult::incomplete_reasons
+    let mut incomplete: BTreeSet<IncompleteReason> = BTreeSet::new();
+    if truncated {
+        incomplete.insert(IncompleteReason::Sampled);
+    }
+
+    // All transforms that are tested
+    let tested_transforms: Vec<Transform> = todo.iter().flatten().cloned().collect();
+
+    // Lookup of cookie to the transforms that were sent in the corresponding message,
+    // grouped by the number of the proposal they were sent in
+    let mut open: HashMap<u64, HashMap<u8, Vec<Transform>>> = HashMap::new();
 
-    // Lookup of cookie to the transforms that were sent in the corresponding message
-    let mut open: HashMap<u64, Vec<Transform>> = HashMap::new();
+    // Recently rejected proposals by cookie, with the time they were rejected at
+    let mut closed: HashMap<u64, (Instant, HashMap<u8, Vec<Transform>>)> = HashMap::new();
 
     // Number of cookies lost in attempts to remove them from the tracked list, used as a fallback
     let mut lost_cookies = 0;
@@ -93,38 +1003,309 @@ pub async fn scan(opts: ScanOptions) -> Result<ScanResult, ScanError> {
     // The valid transforms that were found
     let mut found: Vec<Transform> = vec![];
 
+    // The transforms found during enumeration, set when they are being verified
+    let mut verifying: Option<Vec<Transform>> = None;
+
+    // The raw proposals that were accepted, but couldn't be converted to transforms
+    let mut unparsed_acceptances = vec![];
+
+    // The raw values of the transforms of the unparsed acceptances
+    let mut unknown_transforms = vec![];
+
+    // The sent messages, if they should be recorded
+    let mut sent_messages = vec![];
+
+    // The transforms the server chose out of the proposals
+    let mut choices = vec![];
+
+    // The accepted proposals with the security association they were returned in
+    let mut accepted_proposals = vec![];
+
+    // Observed noteworthy behavior of the server
+    let mut quirks: BTreeSet<ServerQuirk> = BTreeSet::new();
+
+    // Whether the server has responded at all
+    let mut responded = false;
+
+    // Whether the server sent NAT discovery payloads
+    let mut nat_discovery = false;
+    // Whether the server sent one of the NAT_T_VENDOR_IDS
+    let mut nat_t_vendor_id = false;
+    // The described vendor IDs the server sent
+    let mut vendor_ids: Vec<String> = vec![];
+    // The types of the notifications the server sent, see ScanResult::notifications
+    let mut notifications: Vec<NotifyMessageType> = vec![];
+    // The measured round-trip times, see ScanResult::round_trip_times
+    let mut round_trip_times: Vec<Duration> = vec![];
+    // The responder cookie of the first accepting response, see ScanResult::responder_cookie
+    let mut responder_cookie: Option<u64> = None;
+
+    // The transforms of all rejected proposals
+    let mut rejected: Vec<Transform> = vec![];
+
+    // The transforms of all proposals that were given up without a response
+    let mut unanswered_transforms: Vec<Transform> = vec![];
+
+    // The sizes of the primes of accepted custom groups, see ScanResult::custom_group_prime_bits
+    let mut custom_group_prime_bits: BTreeSet<usize> = BTreeSet::new();
+
+    // The exchange types that are still to be probed, using the first tested transform
+    let probe_transform = tested_transforms.first().cloned();
+    let mut probes_todo: VecDeque<ExchangeType> = match probe_transform {
+        Some(_) if opts.probe_exchange_types => VecDeque::from(PROBED_EXCHANGE_TYPES),
+        _ => VecDeque::new(),
+    };
+
+    // Lookup of cookie to the exchange type that was probed with the corresponding message
+    let mut probes: HashMap<u64, ExchangeType> = HashMap::new();
+
+    // The exchange types the server answered
+    let mut supported_exchange_types: BTreeSet<ExchangeType> = BTreeSet::new();
+
     // If sleep is active, the sending part will pause
     let mut do_sleep = false;
 
+    // Cookies of the sent proposals which weren't answered yet, in the order they were sent
+    let mut unanswered: Vec<u64> = vec![];
+
+    // Cookies of unanswered proposals that were sent again or given up, their responses are ignored
+    let mut abandoned: HashSet<u64> = HashSet::new();
+
+    // The current backoff, if the server is considered to rate limit the scan
+    let mut backoff: Option<Duration> = None;
+
+    // The sending part is paused until then while backing off
+    let mut paused_until: Option<Instant> = None;
+
+    // Whether the server didn't respond while backing off
+    let mut rate_limited = false;
+
+    // The last time a message was sent or received, see [ScanOptions::response_timeout]
+    let mut last_activity = Instant::now();
+
+    // The time the open proposals were sent at and the number of times they were sent before
+    let mut sent_at: HashMap<u64, (Instant, usize)> = HashMap::new();
+
+    // Unanswered proposals which are to be sent again, with the number of times they were sent
+    let mut retransmit: VecDeque<(Vec<Transform>, usize)> = VecDeque::new();
+
+    // Whether the scan was cancelled, see [ScanOptions::cancel]
+    let mut is_cancelled = false;
+    // Whether the scan stopped after the first accepted transform, see [ScanOptions::stop_on_first_accept]
+    let mut stopped_on_accept = false;
+
     loop {
+        if opts.stop_on_first_accept
+            && !stopped_on_accept
+            && !found.is_empty()
+            && verifying.is_none()
+        {
+            info!("Found an accepted transform, dropping the remaining transforms");
+            stopped_on_accept = true;
+            incomplete.insert(IncompleteReason::StoppedOnAccept);
+            todo.clear();
+            retransmit.clear();
+            abandoned.extend(open.drain().map(|(cookie, _)| cookie));
+            unanswered.clear();
+            sent_at.clear();
+            lost_cookies = 0;
+        }
+
         select! {
+            // Drop the remaining work, so the partial result is returned
+            _ = cancelled(&opts.cancel), if !is_cancelled => {
+                info!("Scan was cancelled, returning the transforms found so far");
+                is_cancelled = true;
+                incomplete.insert(IncompleteReason::Cancelled);
+                todo.clear();
+                retransmit.clear();
+                probes_todo.clear();
+                abandoned.extend(open.drain().map(|(cookie, _)| cookie));
+                unanswered.clear();
+                sent_at.clear();
+                lost_cookies = 0;
+                // There is nothing left to back off for
+                paused_until = None;
+                backoff = None;
+                rate_limited = false;
+            }
+
             // Handle received isakmp messages or errors from receiving side
             msg_res = rx.recv() => {
                 if let Some(res) = msg_res {
                     match res {
                         Ok(msg) => {
                             trace!("Received message: {msg:?}");
+                            responded = true;
+                            last_activity = Instant::now();
+                            // Proposals that were sent again have no entry, as it can't be told
+                            // which transmission is answered
+                            let rtt = sent_at.remove(&msg.header.initiator_cookie).map(|(at, _)| at.elapsed());
+                            round_trip_times.extend(rtt);
+                            nat_discovery |= !msg.nat_discovery_payloads.is_empty();
+                            nat_t_vendor_id |= msg.vendor_ids.iter().any(|x| NAT_T_VENDOR_IDS.iter().any(|id| x.vendor_id == id));
+                            for vendor_id in &msg.vendor_ids {
+                                let vendor_id = describe_vendor_id(&vendor_id.vendor_id);
+                                if !vendor_ids.contains(&vendor_id) {
+                                    vendor_ids.push(vendor_id);
+                                }
+                            }
+                            unanswered.retain(|cookie| *cookie != msg.header.initiator_cookie);
+                            if let Some(backoff) = backoff.take().filter(|_| mem::take(&mut rate_limited)) {
+                                info!("Server responds again after backing off {} ms", backoff.as_millis());
+                                if !quirks.iter().any(|x| matches!(x, ServerQuirk::RateLimited { .. })) {
+                                    let quirk = ServerQuirk::RateLimited { backoff_ms: backoff.as_millis() as u64 };
+                                    quirks.insert(quirk.clone());
+                                    emit(&opts, ScanEvent::QuirkDetected(quirk));
+                                }
+                            }
+
+                            // Responses to proposals that were already sent again are superseded
+                            if abandoned.remove(&msg.header.initiator_cookie) {
+                                debug!("Ignoring late response for initiator cookie {}", msg.header.initiator_cookie);
+                                continue;
+                            }
+
+                            // Responses to probes are not part of the enumeration of transforms
+                            if let Some(exchange_type) = probes.remove(&msg.header.initiator_cookie) {
+                                if msg.notification_payloads.iter().any(|x| x.notify_message_type.is_error()) {
+                                    debug!("Exchange type {exchange_type:?} is not supported");
+                                } else {
+                                    debug!("Exchange type {exchange_type:?} is supported");
+                                    do_sleep |= !msg.security_associations.is_empty();
+                                    supported_exchange_types.insert(exchange_type);
+                                }
 
                             // Retrieving a security association means we got at least one transform right
-                            if !msg.security_associations.is_empty() {
-                                for sa in &msg.security_associations {
+                            } else if !msg.security_associations.is_empty() {
+                                record_notifications(&mut notifications, &msg.notification_payloads);
+                                debug!(
+                                    "Responder cookie for initiator cookie {}: {}",
+                                    msg.header.initiator_cookie, msg.header.responder_cookie
+                                );
+                                responder_cookie.get_or_insert(msg.header.responder_cookie);
+
+                                // Due to reordering, an acceptance may arrive after a rejection
+                                // for the same cookie, which has to be handled nevertheless
+                                if let Entry::Vacant(entry) = open.entry(msg.header.initiator_cookie) {
+                                    if let Some((_, proposals)) = closed.remove(&msg.header.initiator_cookie) {
+                                        debug!("Late acceptance for initiator cookie {}", msg.header.initiator_cookie);
+                                        entry.insert(proposals);
+                                    }
+                                }
+
+                                // Numbers of the proposals the server has chosen
+                                let mut chosen_proposals = vec![];
+                                // Whether a proposal number was unknown and couldn't be attributed
+                                let mut unattributed = false;
+
+                                for (sa_index, sa) in msg.security_associations.iter().enumerate() {
+                                    if sa.domain_of_interpretation != DOI || sa.situation != SITUATION {
+                                        let quirk = ServerQuirk::UnexpectedSituation {
+                                            doi: sa.domain_of_interpretation as u32,
+                                            situation: sa.situation.clone(),
+                                        };
+                                        warn!("Server answered with an unexpected situation: {quirk}");
+                                        if quirks.insert(quirk.clone()) {
+                                            emit(&opts, ScanEvent::QuirkDetected(quirk));
+                                        }
+                                    }
+
                                     for prop in &sa.proposal_payload {
                                         do_sleep = true;
 
-                                        let Ok(transforms) = payload_to_transforms(prop) else {
+                                        let Some(proposals) = open.get(&msg.header.initiator_cookie) else {
+                                            warn!("Missing initiator cookie");
+                                            trace!("{} :: {:#?}", msg.header.initiator_cookie, open);
+                                            continue;
+                                        };
+
+                                        // Only the transforms of the chosen proposal are affected
+                                        let proposal_no = if proposals.contains_key(&prop.proposal_no) {
+                                            prop.proposal_no
+                                        } else {
+                                            warn!("Unknown proposal number {} for initiator cookie {}", prop.proposal_no, msg.header.initiator_cookie);
+                                            let quirk = ServerQuirk::UnknownProposalNumber { proposal_no: prop.proposal_no };
+                                            if quirks.insert(quirk.clone()) {
+                                                emit(&opts, ScanEvent::QuirkDetected(quirk));
+                                            }
+                                            // A single proposal can only be answered by itself
+                                            match proposals.keys().collect::<Vec<_>>()[..] {
+                                                [proposal_no] => *proposal_no,
+                                                _ => {
+                                                    unattributed = true;
+                                                    continue;
+                                                }
+                                            }
+                                        };
+                                        chosen_proposals.push(proposal_no);
+                                        let all = &proposals[&proposal_no];
+
+                                        // Custom groups are sent as their parameters instead of a group description
+                                        for attribute in prop.transforms.iter().flat_map(|x| &x.sa_attributes) {
+                                            let Some(bits) = group_prime_bits(attribute) else {
+                                                continue;
+                                            };
+                                            if bits < MIN_GROUP_PRIME_BITS {
+                                                warn!("Server uses a weak custom group: {}", format_attribute(attribute));
+                                            }
+                                            custom_group_prime_bits.insert(bits);
+                                        }
+
+                                        let Ok(accepted) = payload_to_transforms(prop) else {
                                             warn!("Could not retrieve transform from msg: {msg:?}");
                                             debug!("{msg:?}");
+                                            for raw in payload_to_raw_transforms(prop) {
+                                                warn!("Accepted transform with unknown values: {}", format_raw_transform(&raw));
+                                                unknown_transforms.push(raw);
+                                            }
+                                            unparsed_acceptances.push(prop.raw.clone());
                                             continue;
                                         };
+                                        let transforms: Vec<Transform> = accepted.iter().map(|x| x.transform.clone()).collect();
 
-                                        // Add the found transform to our list
-                                        found.extend(transforms.clone());
+                                        if transforms.len() > 1 && quirks.insert(ServerQuirk::MultipleTransformsReturned) {
+                                            emit(&opts, ScanEvent::QuirkDetected(ServerQuirk::MultipleTransformsReturned));
+                                        }
 
-                                        let Some(all) = open.get(&msg.header.initiator_cookie) else {
-                                            warn!("Missing initiator cookie");
-                                            trace!("{} :: {:#?}", msg.header.initiator_cookie, open);
+                                        // Only transforms that were offered may be reported as found
+                                        let (transforms, unsolicited) = split_unsolicited(transforms, all);
+                                        for transform in &unsolicited {
+                                            warn!("Discarding transform that was not offered: {transform}");
+                                        }
+                                        if transforms.is_empty() {
+                                            // The proposal was accepted, but not with any of the offered transforms,
+                                            // which leaves them undecided. Sending them again would be answered the same way.
+                                            unanswered_transforms.extend(all.iter().cloned());
+                                            incomplete.insert(IncompleteReason::Unanswered);
                                             continue;
-                                        };
+                                        }
+
+                                        for chosen in &accepted {
+                                            if let Some(index) = all.iter().position(|x| x == &chosen.transform) {
+                                                choices.push(TransformChoice {
+                                                    transform: chosen.transform.clone(),
+                                                    transform_no: chosen.transform_no,
+                                                    index,
+                                                    offered: all.len(),
+                                                    lifetime: chosen.lifetime,
+                                                    rtt,
+                                                });
+                                            }
+                                        }
+
+                                        accepted_proposals.push(AcceptedProposal {
+                                            initiator_cookie: msg.header.initiator_cookie,
+                                            sa_index,
+                                            proposal_no,
+                                            transforms: transforms.clone(),
+                                        });
+
+                                        // Add the found transform to our list
+                                        found.extend(transforms.clone());
+                                        for transform in &transforms {
+                                            emit(&opts, ScanEvent::TransformAccepted(transform.clone()));
+                                        }
 
                                         // Retrieve all transforms not returned in the message
                                         let other: Vec<Transform> = all.clone().into_iter().filter(|x| !transforms.contains(x)).collect();
@@ -148,19 +1329,65 @@ pub async fn scan(opts: ScanOptions) -> Result<ScanResult, ScanError> {
                                         }
                                     }
                                 }
-                                let removed = open.remove(&msg.header.initiator_cookie);
-                                if removed.is_none() {
-                                    warn!("Could not find corresponding initiator cookie: {}", msg.header.initiator_cookie);
-                                    lost_cookies += 1;
+                                match open.remove(&msg.header.initiator_cookie) {
+                                    Some(proposals) => {
+                                        for (proposal_no, transforms) in proposals {
+                                            if chosen_proposals.contains(&proposal_no) {
+                                                continue;
+                                            }
+                                            if unattributed {
+                                                // The server would answer the same way again, so the proposals are given up
+                                                unanswered_transforms.extend(transforms);
+                                                incomplete.insert(IncompleteReason::Unanswered);
+                                            } else {
+                                                // Proposals that weren't chosen are undecided and have to be sent again
+                                                todo.push_back(transforms);
+                                            }
+                                        }
+                                    }
+                                    None => {
+                                        warn!("Could not find corresponding initiator cookie: {}", msg.header.initiator_cookie);
+                                        lost_cookies += 1;
+                                    }
                                 }
 
                             // A notification of type NO_PROPOSAL_CHOSEN means all transforms were invalid
                             } else if msg.notification_payloads.iter().any(|x| x.notify_message_type == NotifyMessageType::NoProposalChosen) {
-                                let removed = open.remove(&msg.header.initiator_cookie);
-                                if removed.is_none() {
-                                    warn!("Could not find corresponding initiator cookie: {}", msg.header.initiator_cookie);
-                                    lost_cookies += 1;
+                                match open.remove(&msg.header.initiator_cookie) {
+                                    Some(proposals) => {
+                                        rejected.extend(proposals.values().flatten().cloned());
+                                        for transforms in proposals.values() {
+                                            emit(&opts, ScanEvent::ProposalRejected(transforms.clone()));
+                                        }
+                                        closed.retain(|_, (closed_at, _)| closed_at.elapsed() < CLOSED_COOKIE_TTL);
+                                        closed.insert(msg.header.initiator_cookie, (Instant::now(), proposals));
+                                    }
+                                    None => {
+                                        warn!("Could not find corresponding initiator cookie: {}", msg.header.initiator_cookie);
+                                        lost_cookies += 1;
+                                    }
+                                }
+                            } else if let Some(error) = msg.notification_payloads.iter().find(|x| x.notify_message_type.is_error()) {
+                                record_notifications(&mut notifications, &msg.notification_payloads);
+
+                                // The server won't answer the proposal anymore, so it is given up
+                                sent_at.remove(&msg.header.initiator_cookie);
+                                match open.remove(&msg.header.initiator_cookie) {
+                                    Some(proposals) => {
+                                        for transform in proposals.values().flatten() {
+                                            debug!("Undecided after {} ({}) {transform}", error.notify_message_type, msg.header.initiator_cookie);
+                                        }
+                                        unanswered_transforms.extend(proposals.into_values().flatten());
+                                        incomplete.insert(IncompleteReason::Unanswered);
+                                    }
+                                    None => {
+                                        warn!("Could not find corresponding initiator cookie: {}", msg.header.initiator_cookie);
+                                        lost_cookies += 1;
+                                    }
                                 }
+                            } else if !msg.notification_payloads.is_empty() {
+                                // Status notifications don't answer the proposal
+                                record_notifications(&mut notifications, &msg.notification_payloads);
                             } else {
                                 warn!("Unknown message: {:?}", msg)
                             }
@@ -174,6 +1401,16 @@ pub async fn scan(opts: ScanOptions) -> Result<ScanResult, ScanError> {
                             ReceiveError::InvalidMessage(err) => {
                                 trace!("Could not parse incoming message: {err}");
                             }
+                            ReceiveError::InvalidFragment(err) => {
+                                debug!("Could not reassemble incoming message: {err}");
+                            }
+                            ReceiveError::UnsupportedVersion(major_version) => {
+                                debug!("Received message with unsupported major version {major_version}");
+                                responded = true;
+                                if major_version == 2 && quirks.insert(ServerQuirk::RespondedAsIkeV2) {
+                                    emit(&opts, ScanEvent::QuirkDetected(ServerQuirk::RespondedAsIkeV2));
+                                }
+                            }
                         }
                     }
                 }
@@ -181,39 +1418,209 @@ pub async fn scan(opts: ScanOptions) -> Result<ScanResult, ScanError> {
 
             // Handle the sending of messages
             _ = interval.tick() => {
-                match todo.pop_front() {
+                if !opts.jitter.is_zero() {
+                    interval.reset_after(jittered_interval(Duration::from_millis(opts.interval), opts.jitter));
+                }
+
+                if let Some(rate_limit) = &opts.rate_limit {
+                    if let Some(until) = paused_until {
+                        if Instant::now() < until {
+                            continue;
+                        }
+                        paused_until = None;
+
+                        // The server didn't respond while backing off
+                        if !unanswered.is_empty() {
+                            let current = backoff.unwrap_or(rate_limit.backoff);
+                            rate_limited = true;
+                            let lost: Vec<Vec<Transform>> = unanswered
+                                .drain(..)
+                                .filter_map(|cookie| {
+                                    abandoned.insert(cookie);
+                                    open.remove(&cookie)
+                                })
+                                .flat_map(|proposals| proposals.into_values())
+                                .collect();
+                            if current >= rate_limit.max_backoff {
+                                warn!("Giving up {} proposals the server didn't respond to", lost.len());
+                                unanswered_transforms.extend(lost.into_iter().flatten());
+                                incomplete.insert(IncompleteReason::Unanswered);
+                            } else {
+                                for transforms in lost.into_iter().rev() {
+                                    todo.push_front(transforms);
+                                }
+                                backoff = Some((current * 2).min(rate_limit.max_backoff));
+                            }
+                        }
+                    }
+
+                    // Responses stopped arriving, although proposals are still sent
+                    if unanswered.len() >= rate_limit.max_unanswered || (todo.is_empty() && !unanswered.is_empty()) {
+                        let current = *backoff.get_or_insert(rate_limit.backoff);
+                        info!("No response to {} proposals, backing off {} ms", unanswered.len(), current.as_millis());
+                        paused_until = Some(Instant::now() + current);
+                        continue;
+                    }
+                }
+
+                // Responses that arrived already are handled before deciding to send again
+                if opts.max_retries > 0 && rx.is_empty() {
+                    let expired: Vec<u64> = sent_at
+                        .iter()
+                        .filter(|(_, (at, sent))| at.elapsed() >= opts.retransmit_interval && *sent <= opts.max_retries)
+                        .map(|(cookie, _)| *cookie)
+                        .collect();
+                    for cookie in expired {
+                        let Some((_, sent)) = sent_at.remove(&cookie) else {
+                            continue;
+                        };
+                        let Some(proposals) = open.remove(&cookie) else {
+                            continue;
+                        };
+                        debug!("No response for initiator cookie {cookie}, sending again ({sent}/{})", opts.max_retries);
+                        abandoned.insert(cookie);
+                        unanswered.retain(|x| *x != cookie);
+                        retransmit.extend(proposals.into_values().map(|transforms| (transforms, sent)));
+                    }
+                }
+
+                if let (Some(exchange_type), Some(transform)) = (probes_todo.pop_front(), &probe_transform) {
+                    let (msg, initiator_cookie) = probe_builder(&opts, exchange_type, transform).build();
+                    debug!("Probe ({initiator_cookie}) exchange type {exchange_type:?}");
+                    probes.insert(initiator_cookie, exchange_type);
+                    send(&*transport, &framing.wrap(msg)).await?;
+                    continue;
+                }
+
+                match retransmit.pop_front().or_else(|| Some((todo.pop_front()?, 0))) {
                     // Nothing more to do, this will be the return path
                     None => {
                         debug!("Nothing more to do, waiting some time for more incoming messages");
                         interval.tick().await;
+                        if !open.is_empty() && last_activity.elapsed() >= opts.response_timeout {
+                            warn!(
+                                "No response within {} ms, giving up {} unanswered proposals",
+                                opts.response_timeout.as_millis(),
+                                open.len(),
+                            );
+                            for (cookie, proposals) in open.drain() {
+                                for transform in proposals.values().flatten() {
+                                    debug!("Unanswered ({cookie}) {transform}");
+                                }
+                                unanswered_transforms.extend(proposals.into_values().flatten());
+                                abandoned.insert(cookie);
+                            }
+                            unanswered.clear();
+                            sent_at.clear();
+                            lost_cookies = 0;
+                            incomplete.insert(IncompleteReason::Unanswered);
+                        }
                         if todo.is_empty() && open.len() <= lost_cookies {
                             found.sort();
                             found.dedup();
 
-                            return Ok(ScanResult {
-                                valid_transforms: found,
-                            })
+                            if opts.verify_findings && verifying.is_none() && !is_cancelled {
+                                // Re-send every found transform on its own to confirm it
+                                info!("Verifying {} found transforms", found.len());
+                                todo.extend(found.iter().map(|transform| vec![transform.clone()]));
+                                verifying = Some(mem::take(&mut found));
+                            } else {
+                                let unverified_transforms = verifying
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .filter(|transform| !found.contains(transform))
+                                    .collect::<Vec<_>>();
+                                for transform in &unverified_transforms {
+                                    warn!("Transform failed verification: {transform}");
+                                }
+
+                                if rejected.is_empty() && found.len() > 1 && quirks.insert(ServerQuirk::AcceptsAll) {
+                                    emit(&opts, ScanEvent::QuirkDetected(ServerQuirk::AcceptsAll));
+                                }
+
+                                rejected.sort();
+                                rejected.dedup();
+                                rejected.retain(|transform| !found.contains(transform));
+
+                                // Transforms may have been answered when they were sent again
+                                unanswered_transforms.sort();
+                                unanswered_transforms.dedup();
+                                unanswered_transforms.retain(|transform| !found.contains(transform) && !rejected.contains(transform));
+
+                                let psk_accepted = found.iter().any(|x| is_pre_shared_key(x.authentication_method));
+                                let grade = grade_transforms(&found)
+                                    .into_iter()
+                                    .chain(custom_group_prime_bits.iter().map(|bits| grade_custom_group(*bits)))
+                                    .min();
+
+                                return Ok(ScanResult {
+                                    target: Some(addr),
+                                    psk_accepted,
+                                    grade,
+                                    custom_group_prime_bits: custom_group_prime_bits.into_iter().collect(),
+                                    valid_transforms: found,
+                                    unverified_transforms,
+                                    rejected_transforms: rejected,
+                                    unanswered_transforms,
+                                    tested_transforms,
+                                    quirks: quirks.into_iter().collect(),
+                                    completed: incomplete.is_empty(),
+                                    incomplete_reasons: incomplete.into_iter().collect(),
+                                    responded,
+                                    sent_messages,
+                                    unparsed_acceptances,
+                                    unknown_transforms,
+                                    supported_exchange_types: supported_exchange_types.into_iter().collect(),
+                                    choices,
+                                    accepted_proposals,
+                                    nat_discovery,
+                                    nat_t_vendor_id,
+                                    vendor_ids,
+                                    notifications,
+                                    round_trip_times: RoundTripTimes::from_samples(&round_trip_times),
+                                    responder_cookie,
+                                })
+                            }
                         }
                     }
-                    Some(transforms) => {
-                        let mut mb = MessageBuilder::new();
-                        for transform in &transforms {
-                            mb = mb.add_transform(transform.clone());
+                    Some((transforms, sent)) => {
+                        let (msg, initiator_cookie) = match replay.get(&transforms).cloned() {
+                            Some(mut msg) => {
+                                let initiator_cookie = rand::random();
+                                set_initiator_cookie(&mut msg, initiator_cookie);
+                                (msg, initiator_cookie)
+                            }
+                            None => message_builder(&opts, &transforms).build(),
+                        };
+                        if opts.record && sent == 0 {
+                            sent_messages.push(msg.clone());
                         }
-                        let (msg, initiator_cookie) = mb.build();
                         trace!("Send ({initiator_cookie}) transforms: {transforms:?}");
+                        for transform in &transforms {
+                            info!(target: "ikebuster::proposals", "Send ({initiator_cookie}) {transform}");
+                        }
 
-                        if do_sleep {
+                        if do_sleep && opts.rate_limit.is_none() {
                             info!(
                                 "Sleep {} seconds to evade running into timeout due to half-open connections",
                                 opts.sleep_on_transform_found.as_secs(),
                             );
-                            sleep(opts.sleep_on_transform_found).await;
+                            select! {
+                                _ = sleep(opts.sleep_on_transform_found) => {}
+                                _ = cancelled(&opts.cancel) => {}
+                            }
                             do_sleep = false;
                         }
 
-                        open.insert(initiator_cookie, transforms);
-                        socket.send(&msg).await.map_err(ScanError::Send)?;
+                        emit(&opts, ScanEvent::ProposalSent {
+                            transforms: transforms.clone(),
+                            remaining: todo.len() + retransmit.len(),
+                        });
+                        open.insert(initiator_cookie, HashMap::from([(PROPOSAL_NO, transforms)]));
+                        sent_at.insert(initiator_cookie, (Instant::now(), sent + 1));
+                        unanswered.push(initiator_cookie);
+                        last_activity = Instant::now();
+                        send(&*transport, &framing.wrap(msg)).await?;
                     }
                 }
             }
@@ -221,14 +1628,189 @@ pub async fn scan(opts: ScanOptions) -> Result<ScanResult, ScanError> {
     }
 }
 
+/// Send a datagram over the transport
+async fn send<T: IkeTransport>(transport: &T, datagram: &[u8]) -> Result<(), ScanError> {
+    transport.send(datagram).await.map_err(|err| {
+        if socket::is_message_too_large(&err) {
+            ScanError::MessageTooLarge(datagram.len())
+        } else {
+            ScanError::Send(err)
+        }
+    })
+}
+
+/// The outcome of a response to a proposal, see [decode_response]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseOutcome {
+    /// The server accepted these of the sent transforms
+    Accepted(Vec<Transform>),
+    /// The server rejected the proposal with an error notification
+    Rejected(NotifyMessageType),
+    /// The datagram is neither an acceptance of sent transforms nor a rejection,
+    /// or it couldn't be parsed
+    Other,
+}
+
+/// Decode the response to a proposal of the `sent` transforms
+///
+/// Accepted transforms that weren't sent are discarded. The datagram is expected
+/// without a non-ESP marker.
+pub fn decode_response(sent: &[Transform], datagram: &[u8]) -> ResponseOutcome {
+    let Ok(packet) = parse_packet(datagram) else {
+        return ResponseOutcome::Other;
+    };
+
+    let mut accepted = vec![];
+    for proposal in packet
+        .security_associations
+        .iter()
+        .flat_map(|sa| &sa.proposal_payload)
+    {
+        if let Ok(transforms) = payload_to_transforms(proposal) {
+            let transforms = transforms.into_iter().map(|x| x.transform).collect();
+            accepted.extend(split_unsolicited(transforms, sent).0);
+        }
+    }
+    if !accepted.is_empty() {
+        return ResponseOutcome::Accepted(accepted);
+    }
+
+    packet
+        .notification_payloads
+        .iter()
+        .find(|x| x.notify_message_type.is_error())
+        .map_or(ResponseOutcome::Other, |x| {
+            ResponseOutcome::Rejected(x.notify_message_type)
+        })
+}
+
+/// Split the accepted transforms into the ones that were offered and the unsolicited ones
+fn split_unsolicited(
+    accepted: Vec<Transform>,
+    offered: &[Transform],
+) -> (Vec<Transform>, Vec<Transform>) {
+    accepted.into_iter().partition(|x| offered.contains(x))
+}
+
+/// Create a [MessageBuilder] for a proposal of the given transforms
+fn message_builder(opts: &ScanOptions, transforms: &[Transform]) -> MessageBuilder {
+    let mut mb = MessageBuilder::new().attribute_order(opts.attribute_order.clone());
+    for transform in transforms {
+        mb = mb.add_transform(transform.clone());
+    }
+    mb
+}
+
+/// Create a [MessageBuilder] for the first message of the given exchange type
+///
+/// The message contains the payloads the exchange type requires, so a server supporting
+/// it answers without an error:
+/// - base and authentication only: security association and nonce
+/// - identity protection: security association
+/// - aggressive: security association, key exchange, nonce and identification
+/// - informational: an INITIAL-CONTACT notification
+fn probe_builder(
+    opts: &ScanOptions,
+    exchange_type: ExchangeType,
+    transform: &Transform,
+) -> MessageBuilder {
+    let mb = message_builder(opts, slice::from_ref(transform)).exchange_type(exchange_type);
+    match exchange_type {
+        ExchangeType::Base | ExchangeType::AuthenticationOnly => mb.nonce(),
+        ExchangeType::Aggressive => mb.aggressive_mode(Identity {
+            id_type: IdentificationType::ID_FQDN,
+            data: PROBE_IDENTITY.to_vec(),
+        }),
+        ExchangeType::Informational => mb.informational(NotifyMessageType::InitialContact),
+        _ => mb,
+    }
+}
+
 /// Errors that may occur while scanning
 #[derive(Debug, Error)]
 #[allow(missing_docs)]
 pub enum ScanError {
     #[error("Could not bind: {0}")]
     CouldNotBind(io::Error),
+    #[error("Could not bind to interface {0}: {1}")]
+    CouldNotBindInterface(String, io::Error),
+    #[error("Could not set the TTL: {0}")]
+    CouldNotSetTtl(io::Error),
+    #[error("Could not set the DF bit: {0}")]
+    CouldNotSetDontFragment(io::Error),
     #[error("Could not recv: {0}")]
     Receive(io::Error),
     #[error("Could not send: {0}")]
     Send(io::Error),
+    #[error("The message of {0} bytes exceeds the path MTU, consider sending fewer transforms per proposal")]
+    MessageTooLarge(usize),
+    #[error("Could not replay: {0}")]
+    InvalidReplay(ReplayError),
+    #[error("Could not start the runtime: {0}")]
+    Runtime(io::Error),
+    #[cfg(feature = "socks5")]
+    #[error("Could not associate with the proxy: {0}")]
+    Proxy(proxy::ProxyError),
+}
+
+/// Any error of the library
+///
+/// Allows propagating the errors of different operations with `?`:
+///
+/// ```
+/// use ikebuster::utils::payload_to_transforms::payload_to_transforms;
+/// use isakmp::v1::definitions::AuthenticationMethod;
+/// use isakmp::v1::definitions::EncryptionAlgorithm;
+/// use isakmp::v1::definitions::GroupDescription;
+/// use isakmp::v1::definitions::HashAlgorithm;
+/// use isakmp::v1::generator::MessageBuilder;
+/// use isakmp::v1::generator::Transform;
+/// use isakmp::v1::parser::parse_packet;
+///
+/// /// Retrieve the transforms proposed in a message
+/// fn proposed_transforms(msg: &[u8]) -> Result<Vec<Transform>, ikebuster::Error> {
+///     let packet = parse_packet(msg)?;
+///     let mut transforms = vec![];
+///     for sa in &packet.security_associations {
+///         for proposal in &sa.proposal_payload {
+///             for accepted in payload_to_transforms(proposal)? {
+///                 transforms.push(accepted.transform);
+///             }
+///         }
+///     }
+///     Ok(transforms)
+/// }
+///
+/// let transform = Transform {
+///     encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+///     hash_algorithm: HashAlgorithm::SHA2_256,
+///     authentication_method: AuthenticationMethod::PreSharedKey,
+///     group_description: GroupDescription::MODP_2048,
+///     key_size: Some(256),
+///     extra_attributes: vec![],
+/// };
+/// let (msg, _) = MessageBuilder::new().add_transform(transform.clone()).build();
+/// assert_eq!(proposed_transforms(&msg)?, vec![transform]);
+/// assert!(matches!(
+///     proposed_transforms(&msg[..10]),
+///     Err(ikebuster::Error::Parse(_))
+/// ));
+/// # Ok::<(), ikebuster::Error>(())
+/// ```
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("{0}")]
+    Scan(#[from] ScanError),
+    #[error("{0}")]
+    InvalidTransform(#[from] InvalidTransform),
+    #[error("Could not parse message: {0}")]
+    Parse(#[from] IsakmpParseError),
+    #[error("{0}")]
+    Replay(#[from] ReplayError),
+    #[cfg(feature = "socks5")]
+    #[error("{0}")]
+    Proxy(#[from] proxy::ProxyError),
+    #[error("{0}")]
+    Io(#[from] io::Error),
 }
@@ -1,183 +1,1214 @@
 use std::env;
+use std::fs;
 use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+#[cfg(feature = "tui")]
+use std::io::IsTerminal;
 use std::io::Write;
 use std::net::IpAddr;
+use std::net::Ipv4Addr;
 use std::net::SocketAddr;
+use std::num::NonZeroUsize;
 use std::process::exit;
 use std::time::Duration;
 
 use clap::ArgAction;
 use clap::Parser;
+use ikebuster::many;
+use ikebuster::many::HostOutcome;
+use ikebuster::many::HostResult;
+use ikebuster::many::ScanManyOptions;
+#[cfg(feature = "socks5")]
+use ikebuster::proxy::ProxyConfig;
+use ikebuster::socket;
+use ikebuster::utils::formatting::format_csv_row;
+use ikebuster::utils::formatting::format_grepable;
+use ikebuster::utils::formatting::format_hex;
+use ikebuster::utils::formatting::format_raw_transform;
+use ikebuster::utils::formatting::CSV_HEADER;
+use ikebuster::utils::formatting::MIN_GROUP_PRIME_BITS;
+use ikebuster::utils::gen_transforms::TransformFilter;
+use ikebuster::utils::grading::Grade;
+use ikebuster::utils::jsonl::JsonlWriter;
+use ikebuster::utils::matrix::MatrixCell;
+use ikebuster::utils::record::read_messages;
+use ikebuster::utils::record::write_messages;
+use ikebuster::utils::targets::parse_targets_file;
+use ikebuster::utils::targets::Targets;
+use ikebuster::AcceptedProposal;
+use ikebuster::IncompleteReason;
+use ikebuster::RateLimitOptions;
 use ikebuster::ScanError;
+use ikebuster::ScanEvent;
 use ikebuster::ScanOptions;
+use ikebuster::ScanResult;
+use ikebuster::ServerQuirk;
+use ikebuster::TestedTransform;
+use ikebuster::DEFAULT_RECEIVE_CAPACITY;
+use ikebuster::NAT_T_PORT;
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::definitions::ExchangeType;
+use isakmp::v1::definitions::NotifyMessageType;
+use isakmp::v1::generator::Lifetime;
 use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::DEFAULT_ATTRIBUTE_ORDER;
+use isakmp::v1::generator::KEY_LENGTH_FIRST_ATTRIBUTE_ORDER;
 use owo_colors::OwoColorize;
+use serde::Deserialize;
 use serde::Serialize;
+use tokio::select;
+use tokio::signal;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing_subscriber::EnvFilter;
 
-const BANNER: &str = r#"
-Welcome to
-  _ _        _               _
- (_) | _____| |__  _   _ ___| |_ ___ _ __
- | | |/ / _ \ '_ \| | | / __| __/ _ \ '__|
- | |   <  __/ |_) | |_| \__ \ ||  __/ |
- |_|_|\_\___|_.__/ \__,_|___/\__\___|_|
+use crate::printer::Printer;
 
-"#;
-
-macro_rules! owo_println {
-    ($input:expr) => {
-        println!("{} {}", "[ikebuster]".purple().bold(), $input);
-    };
-}
+mod printer;
+#[cfg(feature = "tui")]
+mod tui;
 
 /// The cli of ikebuster
 #[derive(Debug, Parser)]
 #[clap(author, version)]
 pub struct Cli {
-    /// The IP to scan
-    pub ip: IpAddr,
+    /// The targets to scan
+    ///
+    /// A single IP, a comma-separated list or a CIDR range (e.g. `10.0.0.0/24`).
+    /// Link-local IPv6 addresses take the interface as zone, e.g. `fe80::1%eth0`.
+    #[clap(required_unless_present_any = ["list_transforms", "dump_packets", "targets_file"])]
+    pub targets: Option<Targets>,
+
+    /// Read additional targets from a file, one `ip[:port]` per line
+    ///
+    /// Targets without a port use the port given by `-p`
+    #[clap(long)]
+    pub targets_file: Option<String>,
 
     /// The port to connect to
     #[clap(short, default_value_t = 500)]
     pub port: u16,
 
+    /// Scan using NAT-traversal
+    ///
+    /// Messages are exchanged with port 4500 and prefixed with the non-ESP marker.
+    /// This also changes the local port to 4500.
+    #[clap(long)]
+    pub nat_t: bool,
+
     /// The interval in milliseconds in which the messages should be sent
     #[clap(short, long, default_value_t = 500)]
     pub interval: u64,
 
+    /// Randomize the interval by up to this many milliseconds in either direction
+    #[clap(long, default_value_t = 0)]
+    pub jitter: u64,
+
+    /// The time in seconds to wait for outstanding responses after the last message
+    ///
+    /// Proposals that are still unanswered afterward are given up
+    #[clap(long, default_value_t = 10)]
+    pub response_timeout: u64,
+
+    /// The number of times an unanswered proposal is sent again
+    #[clap(long, default_value_t = 2)]
+    pub max_retries: usize,
+
+    /// The time in seconds after which an unanswered proposal is sent again
+    #[clap(long, default_value_t = 5)]
+    pub retransmit_interval: u64,
+
     /// The number of transforms to send in a proposal
-    #[clap(long, default_value_t = 20)]
-    pub transforms: usize,
+    #[clap(long, default_value = "20")]
+    pub transforms: NonZeroUsize,
+
+    /// The maximum size of a sent message in bytes
+    ///
+    /// Proposals exceeding this size are split into multiple messages
+    #[clap(long, default_value_t = 1400)]
+    pub max_message_size: usize,
 
     /// Output the results in a json file
     #[clap(long)]
     pub json: Option<String>,
 
+    /// Output the accepted transforms in a csv file, one row per transform
+    #[clap(long)]
+    pub csv: Option<String>,
+
+    /// Output the accepted transforms in a grepable file, one line per target
+    #[clap(long)]
+    pub grepable: Option<String>,
+
+    /// Record all sent messages to a file, so they can be replayed
+    #[clap(long)]
+    pub record: Option<String>,
+
+    /// Replay the messages of a recording instead of generating the transforms
+    #[clap(long)]
+    pub replay: Option<String>,
+
+    /// Test exactly the transforms listed in a json file instead of generating them
+    ///
+    /// The file contains a list of transforms, as found in the json output
+    #[clap(long, conflicts_with = "replay")]
+    pub proposals_file: Option<String>,
+
+    /// Include every tested transform with its status and the accepted proposals in the
+    /// json output
+    #[clap(long, requires = "json")]
+    pub full_output: bool,
+
+    /// Compare the results to a previous json output and print the changes
+    #[clap(long)]
+    pub compare: Option<String>,
+
     /// The sleep time (in seconds) after a valid transform is found.
     ///
     /// Some servers limit new requests when there are half-open connections
     #[clap(long, default_value_t = 45)]
     pub sleep_on_transform_found: u64,
 
+    /// Back off when the server stops responding instead of sleeping after each found
+    /// transform
+    ///
+    /// Some servers silently drop requests when too many of them arrive
+    #[clap(long)]
+    pub detect_rate_limit: bool,
+
+    /// Set the TTL (IPv4) or hop limit (IPv6) of the sent packets
+    #[clap(long)]
+    pub ttl: Option<u32>,
+
+    /// Set the DF (don't fragment) bit on the sent packets
+    ///
+    /// Proposals exceeding the path MTU fail instead of being dropped silently.
+    /// This is only supported on linux.
+    #[clap(long)]
+    pub dont_fragment: bool,
+
+    /// Number of times binding to port 500 is retried if it is in use
+    #[clap(long, default_value_t = 3)]
+    pub bind_retries: u32,
+
+    /// The local port the messages are sent from, 0 for an ephemeral port
+    ///
+    /// Defaults to 500, or to an ephemeral port if targets are scanned in parallel.
+    /// Some servers only answer messages sent from port 500.
+    #[clap(long)]
+    pub source_port: Option<u16>,
+
+    /// The number of targets scanned in parallel
+    #[clap(long, default_value_t = 1)]
+    pub parallel: usize,
+
+    /// Abort the scan of a single target after this many seconds
+    #[clap(long)]
+    pub timeout_per_host: Option<u64>,
+
+    /// Skip the targets that weren't scanned yet after this many seconds
+    #[clap(long)]
+    pub budget: Option<u64>,
+
+    /// Pin the scan to a network interface (e.g. eth1)
+    ///
+    /// This is only supported on linux and requires the CAP_NET_RAW capability
+    #[clap(long)]
+    pub interface: Option<String>,
+
+    /// Re-send every found transform on its own to eliminate false positives
+    #[clap(long)]
+    pub verify_findings: bool,
+
+    /// Stop after the first accepted transform instead of enumerating all of them
+    #[clap(long)]
+    pub first_match: bool,
+
+    /// Only test up to this many transforms, preferring weak ones
+    ///
+    /// This allows for a quick sampling scan
+    #[clap(long)]
+    pub max_transforms: Option<usize>,
+
+    /// Only test transforms with at least one weak algorithm
+    #[clap(long)]
+    pub only_weak: bool,
+
+    /// Only test these algorithms, given as comma separated names (e.g. `AES_CBC,SHA2_256`)
+    ///
+    /// Algorithms of a type that isn't given are all tested
+    #[clap(long, value_delimiter = ',')]
+    pub include: Vec<String>,
+
+    /// Don't test these algorithms, given as comma separated names (e.g. `DES_CBC,MD5`)
+    #[clap(long, value_delimiter = ',')]
+    pub exclude: Vec<String>,
+
+    /// The key sizes to test for encryption algorithms with a variable key size,
+    /// given as comma separated bits
+    ///
+    /// Defaults to 128,192,256
+    #[clap(long, value_delimiter = ',')]
+    pub key_sizes: Vec<u16>,
+
+    /// Exit with code 2 if any target accepts a weak transform
+    ///
+    /// Transforms are weak if they use e.g. DES, 3DES, MD5, SHA-1 or DH group 1 or 2
+    #[clap(long)]
+    pub fail_on_weak: bool,
+
+    /// Send the key length right after the encryption algorithm
+    ///
+    /// Some implementations expect the key length to be adjacent to the encryption algorithm
+    #[clap(long)]
+    pub key_length_first: bool,
+
+    /// Print a matrix of two attributes showing which combinations were accepted
+    ///
+    /// The attributes are given as `x,y`, possible attributes are `enc`, `hash`, `auth`,
+    /// `group` and `keylen`
+    #[clap(long, value_parser = parse_matrix_axes)]
+    pub matrix: Option<(AttributeType, AttributeType)>,
+
+    /// Print all transforms that would be tested and exit without sending anything
+    #[clap(long)]
+    pub list_transforms: bool,
+
+    /// Print the hex encoded messages that would be sent and exit without sending anything
+    ///
+    /// Each message is preceded by the transforms of its proposal. The initiator cookies
+    /// are random.
+    #[clap(long, conflicts_with = "list_transforms")]
+    pub dump_packets: bool,
+
+    /// Rescan the target every given number of minutes and print each result as json line
+    #[clap(long)]
+    pub watch: Option<u64>,
+
+    /// Only print results of `--watch` that changed compared to the previous scan
+    #[clap(long, requires = "watch")]
+    pub on_change_only: bool,
+
+    /// Append each accepted transform as json line to this file as soon as it is found
+    ///
+    /// The findings are kept even if the scan is aborted
+    #[clap(long)]
+    pub append_jsonl: Option<String>,
+
+    /// Probe which exchange types (e.g. aggressive mode) the server answers
+    #[clap(long)]
+    pub probe_exchange_types: bool,
+
+    /// Show a live view of the scan
+    ///
+    /// Logs are disabled while the live view is shown. Falls back to the plain output
+    /// if stdout is not a terminal.
+    #[cfg(feature = "tui")]
+    #[clap(long)]
+    pub tui: bool,
+
+    /// Relay the scan through the SOCKS5 proxy at this address
+    ///
+    /// The proxy has to support `UDP ASSOCIATE`, which many proxies don't
+    #[cfg(feature = "socks5")]
+    #[clap(long)]
+    pub proxy: Option<SocketAddr>,
+
+    /// Authenticate at the proxy with `username:password`
+    #[cfg(feature = "socks5")]
+    #[clap(long, requires = "proxy")]
+    pub proxy_auth: Option<String>,
+
+    /// Only print the findings, without banner and decorations
+    ///
+    /// Nothing is printed to stdout if the results are written to a json file.
+    /// Logs are printed to stderr.
+    #[clap(short, long)]
+    pub quiet: bool,
+
     /// Set the verbosity of the output
+    ///
+    /// `-v` enables debug logs, `-vv` enables trace logs and `-vvv` prints every transform
+    /// of each sent proposal on top of the debug logs, without the trace logs
     #[clap(short, long, action = ArgAction::Count)]
     pub verbose: u8,
 }
 
 /// container struct for json output
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct DataOutput {
     /// The target that was scanned
     pub target: SocketAddr,
     /// All found valid transforms
     pub valid_transforms: Vec<Transform>,
+    /// Noteworthy behavior of the server
+    #[serde(default)]
+    pub quirks: Vec<ServerQuirk>,
+    /// The exchange types the server answered, only included with `--probe-exchange-types`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub supported_exchange_types: Vec<ExchangeType>,
+    /// Whether the server sent NAT discovery payloads
+    #[serde(default)]
+    pub nat_discovery: bool,
+    /// The grade of the weakest accepted transform
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grade: Option<Grade>,
+    /// Whether the server announced support for NAT-traversal by its vendor ID
+    #[serde(default)]
+    pub nat_t_vendor_id: bool,
+    /// The vendor IDs the server sent, by name if known and hex encoded otherwise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vendor_ids: Vec<String>,
+    /// The lifetimes of the security associations the server responded with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lifetimes: Vec<Lifetime>,
+    /// The types of the notifications the server sent, except NO-PROPOSAL-CHOSEN
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notifications: Vec<NotifyMessageType>,
+    /// The responder cookie of the first response accepting a proposal
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub responder_cookie: Option<u64>,
+    /// The transforms of proposals the server never responded to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unanswered_transforms: Vec<Transform>,
+    /// All tested transforms with their status, only included with `--full-output`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tested: Option<Vec<TestedTransform>>,
+    /// The accepted proposals with the security association they were returned in,
+    /// only included with `--full-output`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accepted_proposals: Option<Vec<AcceptedProposal>>,
+    /// The reason the target wasn't scanned completely
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl DataOutput {
+    /// The output of a target whose scan failed
+    fn failed(target: SocketAddr, error: String) -> Self {
+        Self {
+            target,
+            valid_transforms: vec![],
+            quirks: vec![],
+            supported_exchange_types: vec![],
+            nat_discovery: false,
+            grade: None,
+            nat_t_vendor_id: false,
+            vendor_ids: vec![],
+            lifetimes: vec![],
+            notifications: vec![],
+            responder_cookie: None,
+            unanswered_transforms: vec![],
+            tested: None,
+            accepted_proposals: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// A previous json output, which contains a single target if written by an older version
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PreviousOutput {
+    List(Vec<DataOutput>),
+    Single(Box<DataOutput>),
+}
+
+/// Collect the targets given on the command line and in [Cli::targets_file]
+///
+/// Without any targets, e.g. when only listing the transforms, a placeholder is returned
+fn read_targets(cli: &Cli) -> Result<Vec<SocketAddr>, String> {
+    let mut targets: Vec<SocketAddr> = cli
+        .targets
+        .iter()
+        .flat_map(|targets| &targets.ips)
+        .map(|ip| SocketAddr::new(*ip, cli.port))
+        .collect();
+    if let Some(path) = &cli.targets_file {
+        let data =
+            fs::read_to_string(path).map_err(|err| format!("Error reading {path}: {err}"))?;
+        let file_targets = parse_targets_file(&data, cli.port)
+            .map_err(|err| format!("Error reading {path}: {err}"))?;
+        targets.extend(file_targets);
+    }
+    if targets.is_empty() {
+        targets.push(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), cli.port));
+    }
+    Ok(targets)
+}
+
+/// Find the previous result of the target
+fn previous_result(previous: &[DataOutput], ip: IpAddr) -> Option<ScanResult> {
+    let output = previous.iter().find(|output| output.target.ip() == ip)?;
+    if output.error.is_some() {
+        return None;
+    }
+    Some(ScanResult {
+        valid_transforms: output.valid_transforms.clone(),
+        ..Default::default()
+    })
+}
+
+/// Spawn a task writing the accepted transforms to the file at `path` as json lines
+///
+/// The task finishes once the sender is dropped and all events are written
+fn append_jsonl(
+    printer: Printer,
+    path: &str,
+    target: SocketAddr,
+) -> (UnboundedSender<ScanEvent>, JoinHandle<()>) {
+    let file = match OpenOptions::new().create(true).append(true).open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            printer.error(format!("Error opening {path}: {err}").bright_red());
+            exit(1);
+        }
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut writer = JsonlWriter::new(file, target);
+    let task = tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            if let Err(err) = writer.handle(&event) {
+                printer.error(format!("Error appending to json lines: {err}").bright_red());
+                return;
+            }
+        }
+    });
+    (tx, task)
+}
+
+fn parse_attribute_type(s: &str) -> Result<AttributeType, String> {
+    match s {
+        "enc" => Ok(AttributeType::EncryptionAlgorithm),
+        "hash" => Ok(AttributeType::HashAlgorithm),
+        "auth" => Ok(AttributeType::AuthenticationMethod),
+        "group" => Ok(AttributeType::GroupDescription),
+        "keylen" => Ok(AttributeType::KeyLength),
+        _ => Err(format!(
+            "unknown attribute {s}, expected one of enc, hash, auth, group, keylen"
+        )),
+    }
+}
+
+fn parse_matrix_axes(s: &str) -> Result<(AttributeType, AttributeType), String> {
+    let Some((x, y)) = s.split_once(',') else {
+        return Err("expected two attributes separated by a comma, e.g. enc,group".to_string());
+    };
+    Ok((parse_attribute_type(x)?, parse_attribute_type(y)?))
+}
+
+/// Rescan the target every `period` and print each result as json line until interrupted
+async fn watch(printer: Printer, opts: ScanOptions, period: Duration, on_change_only: bool) {
+    let target = opts.target();
+    let mut previous: Option<ScanResult> = None;
+
+    loop {
+        let res = select! {
+            res = ikebuster::scan(opts.clone()) => res,
+            _ = signal::ctrl_c() => break,
+        };
+
+        match res {
+            Ok(res) => {
+                let changed = previous
+                    .as_ref()
+                    .is_none_or(|previous| !res.diff(previous).is_empty());
+                if changed || !on_change_only {
+                    match serde_json::to_string(&DataOutput {
+                        target,
+                        valid_transforms: res.valid_transforms.clone(),
+                        quirks: res.quirks.clone(),
+                        supported_exchange_types: res.supported_exchange_types.clone(),
+                        nat_discovery: res.nat_discovery,
+                        grade: res.grade,
+                        nat_t_vendor_id: res.nat_t_vendor_id,
+                        vendor_ids: res.vendor_ids.clone(),
+                        lifetimes: res.lifetimes().into_iter().collect(),
+                        notifications: res.notifications.clone(),
+                        responder_cookie: res.responder_cookie,
+                        unanswered_transforms: res.unanswered_transforms.clone(),
+                        tested: None,
+                        accepted_proposals: None,
+                        error: None,
+                    }) {
+                        Ok(line) => println!("{line}"),
+                        Err(err) => {
+                            printer.error(format!("Error serializing results: {err}").bright_red());
+                        }
+                    }
+                }
+                previous = Some(res);
+            }
+            Err(err) => {
+                printer.error(format!("{err}").red().bold());
+            }
+        }
+
+        select! {
+            _ = sleep(period) => {}
+            _ = signal::ctrl_c() => break,
+        }
+    }
+
+    printer.info("Stopped watching".bright_black());
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    let targets = read_targets(&cli);
+
+    #[cfg(feature = "tui")]
+    let live_view = cli.tui
+        && io::stdout().is_terminal()
+        && targets.as_ref().is_ok_and(|targets| targets.len() == 1);
+    #[cfg(not(feature = "tui"))]
+    let live_view = false;
 
-    if cli.verbose > 0 {
+    if live_view {
+        env::set_var("RUST_LOG", "off");
+    } else if cli.verbose > 0 {
         match cli.verbose {
-            1 => env::set_var("RUST_LOG", "ikebuster=debug"),
-            _ => env::set_var("RUST_LOG", "ikebuster=trace"),
+            1 => env::set_var("RUST_LOG", "ikebuster=debug,ikebuster::proposals=off"),
+            2 => env::set_var("RUST_LOG", "ikebuster=trace,ikebuster::proposals=off"),
+            _ => env::set_var("RUST_LOG", "ikebuster=debug,ikebuster::proposals=info"),
         }
     } else if env::var("RUST_LOG").is_err() {
-        env::set_var("RUST_LOG", "info");
+        if cli.quiet {
+            env::set_var("RUST_LOG", "warn,ikebuster::proposals=off");
+        } else {
+            env::set_var("RUST_LOG", "info,ikebuster::proposals=off");
+        }
+    }
+    let subscriber = tracing_subscriber::fmt().with_env_filter(EnvFilter::from_default_env());
+    if cli.quiet {
+        subscriber.with_writer(io::stderr).init();
+    } else {
+        subscriber.init();
     }
-    tracing_subscriber::fmt::init();
 
-    println!("{}", BANNER.blue().bold());
+    let printer = Printer::new(cli.quiet, cli.json.is_some());
+
+    printer.banner();
+
+    let targets = match targets {
+        Ok(targets) => targets,
+        Err(err) => {
+            printer.error(err.bright_red());
+            exit(1);
+        }
+    };
+
+    if targets.len() > 1 && (cli.watch.is_some() || cli.record.is_some()) {
+        printer.error("--watch and --record only support a single target".bright_red());
+        exit(1);
+    }
+
+    let previous = match &cli.compare {
+        None => None,
+        Some(path) => {
+            let previous = fs::read_to_string(path)
+                .map_err(|err| err.to_string())
+                .and_then(|data| {
+                    serde_json::from_str::<PreviousOutput>(&data).map_err(|err| err.to_string())
+                });
+            match previous {
+                Ok(PreviousOutput::List(previous)) => Some(previous),
+                Ok(PreviousOutput::Single(previous)) => Some(vec![*previous]),
+                Err(err) => {
+                    printer.error(format!("Error reading {path}: {err}").bright_red());
+                    exit(1);
+                }
+            }
+        }
+    };
+
+    let replay = match &cli.replay {
+        None => None,
+        Some(path) => match File::open(path).and_then(read_messages) {
+            Ok(messages) => Some(messages),
+            Err(err) => {
+                printer.error(format!("Error reading {path}: {err}").bright_red());
+                exit(1);
+            }
+        },
+    };
+
+    let transforms = match &cli.proposals_file {
+        None => None,
+        Some(path) => match fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|data| serde_json::from_str(&data).map_err(|err| err.to_string()))
+        {
+            Ok(transforms) => Some(transforms),
+            Err(err) => {
+                printer.error(format!("Error reading {path}: {err}").bright_red());
+                exit(1);
+            }
+        },
+    };
+
+    let mut transform_filter = TransformFilter {
+        only_weak: cli.only_weak,
+        key_sizes: (!cli.key_sizes.is_empty()).then(|| cli.key_sizes.clone()),
+        ..Default::default()
+    };
+    let included = cli.include.iter().map(|name| (name, true));
+    let excluded = cli.exclude.iter().map(|name| (name, false));
+    for (name, allow) in included.chain(excluded) {
+        if let Err(err) = transform_filter.select(name, allow) {
+            printer.error(err.to_string().bright_red());
+            exit(1);
+        }
+    }
+
+    let zone = cli
+        .targets
+        .as_ref()
+        .and_then(|targets| targets.zone.as_deref());
+    let scope_id = match zone.map(socket::scope_id).transpose() {
+        Ok(scope_id) => scope_id.unwrap_or(0),
+        Err(err) => {
+            printer.error(
+                format!("Could not resolve zone {}: {err}", zone.unwrap_or_default()).bright_red(),
+            );
+            exit(1);
+        }
+    };
 
     let opts = ScanOptions {
-        ip: cli.ip,
-        port: cli.port,
+        ip: targets[0].ip(),
+        scope_id,
+        port: targets[0].port(),
+        source_port: cli
+            .source_port
+            .unwrap_or(if cli.parallel > 1 { 0 } else { 500 }),
+        nat_t: cli.nat_t,
         interval: cli.interval,
-        transform_no: cli.transforms,
+        jitter: Duration::from_millis(cli.jitter),
+        response_timeout: Duration::from_secs(cli.response_timeout),
+        receive_capacity: DEFAULT_RECEIVE_CAPACITY,
+        max_retries: cli.max_retries,
+        retransmit_interval: Duration::from_secs(cli.retransmit_interval),
+        transform_no: cli.transforms.get(),
         sleep_on_transform_found: Duration::new(cli.sleep_on_transform_found, 0),
+        rate_limit: cli.detect_rate_limit.then(RateLimitOptions::default),
+        bind_retries: cli.bind_retries,
+        interface: cli.interface.clone(),
+        ttl: cli.ttl,
+        dont_fragment: cli.dont_fragment,
+        verify_findings: cli.verify_findings,
+        stop_on_first_accept: cli.first_match,
+        max_transforms: cli.max_transforms,
+        transform_filter,
+        transforms,
+        max_message_size: Some(cli.max_message_size),
+        record: cli.record.is_some(),
+        replay,
+        probe_exchange_types: cli.probe_exchange_types,
+        progress: None,
+        cancel: None,
+        #[cfg(feature = "socks5")]
+        proxy: cli.proxy.map(|addr| ProxyConfig {
+            addr,
+            credentials: cli
+                .proxy_auth
+                .as_ref()
+                .map(|auth| match auth.split_once(':') {
+                    Some((username, password)) => (username.to_string(), password.to_string()),
+                    None => (auth.clone(), String::new()),
+                }),
+        }),
+        attribute_order: if cli.key_length_first {
+            KEY_LENGTH_FIRST_ATTRIBUTE_ORDER.to_vec()
+        } else {
+            DEFAULT_ATTRIBUTE_ORDER.to_vec()
+        },
     };
 
-    let res = match ikebuster::scan(opts).await {
-        Ok(res) => res,
-        Err(err) => {
-            match err {
-                ScanError::CouldNotBind(e) => {
-                    owo_println!("---------------");
-                    owo_println!("Could not bind to local port 500".red().bold());
-                    owo_println!(format!("\t{e}").red().bold());
-                    owo_println!("---------------");
-                    owo_println!("Possible solutions:");
-                    owo_println!(format!("\tsudo {}", env::current_exe()?.display()).bright_black());
-                    owo_println!(format!(
-                        "\tsetcap 'cap_net_bind_service=+ep' {}",
-                        env::current_exe()?.display()
-                    )
-                    .bright_black());
-                    owo_println!("---------------");
-                }
-                _ => {
-                    owo_println!(format!("{err}").red().bold());
-                }
+    if cli.dump_packets {
+        let messages = ikebuster::planned_messages(&opts);
+        for (transforms, msg) in &messages {
+            for transform in transforms {
+                println!("# {transform}");
+            }
+            println!("{}", format_hex(msg));
+        }
+        printer.info(format!("{} messages", messages.len()).bright_black());
+        return Ok(());
+    }
+
+    if cli.list_transforms {
+        let transforms = ikebuster::planned_transforms(&opts);
+        for transform in &transforms {
+            println!("{transform}");
+        }
+        printer.info(
+            format!(
+                "{} transforms in {} proposals",
+                transforms.len(),
+                ikebuster::planned_proposals(&opts).len(),
+            )
+            .bright_black(),
+        );
+        return Ok(());
+    }
+
+    if let Some(minutes) = cli.watch {
+        let (progress, _jsonl_task) = cli
+            .append_jsonl
+            .as_ref()
+            .map(|path| append_jsonl(printer, path, opts.target()))
+            .unzip();
+        watch(
+            printer,
+            ScanOptions { progress, ..opts },
+            Duration::from_secs(minutes * 60),
+            cli.on_change_only,
+        )
+        .await;
+        return Ok(());
+    }
+
+    let limits = ScanManyOptions {
+        timeout_per_host: cli.timeout_per_host.map(Duration::from_secs),
+        budget: cli.budget.map(Duration::from_secs),
+        concurrency: cli.parallel,
+    };
+    let source_port = opts.local_port();
+    let results = if live_view {
+        let outcome = match scan_target(printer, cli.append_jsonl.clone(), live_view, opts).await {
+            Ok(res) => HostOutcome::Completed(Box::new(res)),
+            Err(err) => HostOutcome::Failed(err),
+        };
+        vec![HostResult {
+            target: targets[0],
+            outcome,
+        }]
+    } else {
+        many::scan_many_with(opts, targets, limits, |opts| {
+            scan_target(printer, cli.append_jsonl.clone(), false, opts)
+        })
+        .await
+    };
+
+    let multiple = results.len() > 1;
+    let mut completed = 0;
+    let mut outputs = vec![];
+    for HostResult { target, outcome } in results {
+        let target = if cli.nat_t {
+            SocketAddr::new(target.ip(), NAT_T_PORT)
+        } else {
+            target
+        };
+        printer.separator();
+        if multiple {
+            printer.finding(format!("Results of {target}:").bold());
+        }
+
+        let res = match outcome {
+            HostOutcome::Completed(res) => *res,
+            HostOutcome::Failed(err) => {
+                print_error(printer, &err, source_port);
+                outputs.push(DataOutput::failed(target, err.to_string()));
+                continue;
+            }
+            HostOutcome::TimedOut => {
+                printer.error(format!("Scan of {target} timed out").red().bold());
+                outputs.push(DataOutput::failed(target, "Timed out".to_string()));
+                continue;
+            }
+            HostOutcome::Skipped => {
+                printer.error(format!("Skipped {target}, the budget is exhausted").yellow());
+                outputs.push(DataOutput::failed(target, "Skipped".to_string()));
+                continue;
+            }
+        };
+        completed += 1;
+
+        let previous = previous
+            .as_deref()
+            .and_then(|previous| previous_result(previous, target.ip()));
+        print_result(printer, &cli, &res, previous.as_ref());
+
+        if let Some(path) = &cli.record {
+            printer.separator();
+            if let Err(err) =
+                File::create(path).and_then(|file| write_messages(file, &res.sent_messages))
+            {
+                printer.error(format!("Error writing record: {err}").bright_red());
+                exit(1);
+            }
+            printer.info(format!(
+                "{} {}",
+                format!("Recorded {} messages to", res.sent_messages.len()).bright_black(),
+                path.default_color()
+            ));
+        }
+
+        outputs.push(DataOutput {
+            target,
+            tested: cli.full_output.then(|| res.transform_statuses()),
+            lifetimes: res.lifetimes().into_iter().collect(),
+            accepted_proposals: cli.full_output.then_some(res.accepted_proposals),
+            valid_transforms: res.valid_transforms,
+            quirks: res.quirks,
+            supported_exchange_types: res.supported_exchange_types,
+            nat_discovery: res.nat_discovery,
+            grade: res.grade,
+            nat_t_vendor_id: res.nat_t_vendor_id,
+            vendor_ids: res.vendor_ids,
+            notifications: res.notifications,
+            responder_cookie: res.responder_cookie,
+            unanswered_transforms: res.unanswered_transforms,
+            error: None,
+        });
+    }
+
+    if let Some(path) = &cli.json {
+        printer.separator();
+        let Ok(serialized) = serde_json::to_string_pretty(&outputs) else {
+            printer.error("Error serializing results".bright_red());
+            exit(1);
+        };
+        write_output(printer, "json", path, &serialized)?;
+    }
+
+    if let Some(path) = &cli.csv {
+        printer.separator();
+        let mut csv = format!("{CSV_HEADER}\n");
+        for output in &outputs {
+            for transform in &output.valid_transforms {
+                csv.push_str(&format_csv_row(output.target, transform));
+                csv.push('\n');
             }
+        }
+        write_output(printer, "csv", path, &csv)?;
+    }
+
+    if let Some(path) = &cli.grepable {
+        printer.separator();
+        let mut grepable = String::new();
+        for output in &outputs {
+            grepable.push_str(&format_grepable(output.target, &output.valid_transforms));
+            if let Some(error) = &output.error {
+                grepable.push_str(&format!(" error={error:?}"));
+            }
+            grepable.push('\n');
+        }
+        write_output(printer, "grepable", path, &grepable)?;
+    }
+
+    if completed == 0 {
+        exit(1);
+    }
+
+    if cli.fail_on_weak && outputs.iter().any(|x| x.grade == Some(Grade::Weak)) {
+        printer.separator();
+        printer.error("Weak transforms were accepted".red().bold());
+        exit(2);
+    }
+
+    printer.separator();
+    printer.info("See you soon! :)".blue());
+
+    Ok(())
+}
+
+/// Write the output of the given format to the file at `path`
+fn write_output(printer: Printer, format: &str, path: &str, contents: &str) -> io::Result<()> {
+    let mut file = match File::create(path) {
+        Ok(file) => file,
+        Err(err) => {
+            printer.error(format!("Error creating {format} file: {err}").bright_red());
             exit(1);
         }
     };
 
-    owo_println!("---------------");
+    write!(file, "{contents}")?;
+    file.flush()?;
+
+    printer.info(format!(
+        "{} {}",
+        format!("Written {format} output to").bright_black(),
+        path.default_color()
+    ));
+    Ok(())
+}
+
+/// Scan a single target, appending its accepted transforms to the file of `--append-jsonl`
+async fn scan_target(
+    printer: Printer,
+    append_jsonl_path: Option<String>,
+    live_view: bool,
+    opts: ScanOptions,
+) -> Result<ScanResult, ScanError> {
+    let (progress, jsonl_task) = append_jsonl_path
+        .as_ref()
+        .map(|path| append_jsonl(printer, path, opts.target()))
+        .unzip();
+    let opts = ScanOptions { progress, ..opts };
+
+    #[cfg(feature = "tui")]
+    let res = if live_view {
+        tui::run(opts).await
+    } else {
+        ikebuster::scan(opts).await
+    };
+    #[cfg(not(feature = "tui"))]
+    let res = {
+        let _ = live_view;
+        ikebuster::scan(opts).await
+    };
+
+    // Wait for the remaining accepted transforms to be written
+    if let Some(task) = jsonl_task {
+        let _res = task.await;
+    }
+    res
+}
+
+/// Print the reason the scan of a target failed
+fn print_error(printer: Printer, err: &ScanError, source_port: u16) {
+    let ScanError::CouldNotBind(e) = err else {
+        printer.error(format!("{err}").red().bold());
+        return;
+    };
 
-    if res.valid_transforms.is_empty() {
-        owo_println!("No valid transforms found :(".yellow());
+    printer.error("---------------");
+    printer.error(
+        format!("Could not bind to local port {source_port}")
+            .red()
+            .bold(),
+    );
+    printer.error(format!("\t{e}").red().bold());
+    printer.error("---------------");
+    if let Ok(exe) = env::current_exe() {
+        printer.error("Possible solutions:");
+        printer.error(format!("\tsudo {}", exe.display()).bright_black());
+        printer
+            .error(format!("\tsetcap 'cap_net_bind_service=+ep' {}", exe.display()).bright_black());
+        printer.error("---------------");
+    }
+}
+
+/// Print the findings of a completed scan
+fn print_result(printer: Printer, cli: &Cli, res: &ScanResult, previous: Option<&ScanResult>) {
+    for reason in &res.incomplete_reasons {
+        printer.info(
+            match reason {
+                IncompleteReason::Sampled => "Only a sample of all transforms was tested".into(),
+                IncompleteReason::Unanswered => format!(
+                    "{} transforms were left unanswered",
+                    res.unanswered_transforms.len()
+                ),
+                IncompleteReason::StoppedOnAccept => {
+                    "Stopped after the first accepted transform".into()
+                }
+                IncompleteReason::Cancelled => "The scan was cancelled".into(),
+            }
+            .yellow(),
+        );
+    }
+
+    if !res.responded {
+        printer.finding("Host did not respond to IKE (may be filtered)".yellow());
+    } else if res.valid_transforms.is_empty() {
+        printer.finding("Host responded but accepted no transforms :(".yellow());
     } else {
-        owo_println!("Found transforms:");
+        printer.finding("Found transforms:");
     }
 
     for valid in &res.valid_transforms {
-        owo_println!(format!(
-            "\t{}{} {}{} {}{} {}{}",
-            "ENC=".bright_black(),
-            if let Some(key_len) = valid.key_size {
-                format!("{}/{key_len}", valid.encryption_algorithm)
+        printer.finding(format!(
+            "\t{valid} {}",
+            match valid.group_description.approx_security_bits() {
+                Some(bits) if valid.group_description.is_weak() => {
+                    format!("(~{bits} bit, weak)").red().bold().to_string()
+                }
+                Some(bits) => format!("(~{bits} bit)").bright_black().to_string(),
+                None => String::new(),
+            },
+        ));
+    }
+
+    for bits in &res.custom_group_prime_bits {
+        printer.finding(format!(
+            "\tCustom group with a {bits} bit prime {}",
+            if *bits < MIN_GROUP_PRIME_BITS {
+                "(weak)".red().bold().to_string()
             } else {
-                valid.encryption_algorithm.to_string()
+                String::new()
             },
-            "HASH=".bright_black(),
-            valid.hash_algorithm,
-            "AUTH=".bright_black(),
-            valid.authentication_method,
-            "GROUP=".bright_black(),
-            valid.group_description,
         ));
     }
-    if let Some(target) = cli.json {
-        owo_println!("---------------");
-        let Ok(serialized) = serde_json::to_string_pretty(&DataOutput {
-            target: SocketAddr::new(cli.ip, cli.port),
-            valid_transforms: res.valid_transforms,
-        }) else {
-            owo_println!("Error serializing results".bright_red());
-            exit(1);
-        };
 
-        let mut file = match File::create(&target) {
-            Ok(file) => file,
-            Err(err) => {
-                owo_println!(format!("Error creating json file: {err}").bright_red());
-                exit(1);
-            }
-        };
+    match res.grade {
+        Some(Grade::Weak) => printer.finding(format!("Grade: {}", "Weak".red().bold())),
+        Some(Grade::Acceptable) => printer.finding(format!("Grade: {}", "Acceptable".yellow())),
+        Some(Grade::Strong) => printer.finding(format!("Grade: {}", "Strong".green())),
+        None => {}
+    }
 
-        write!(file, "{serialized}")?;
-        file.flush()?;
+    if res.psk_accepted {
+        printer.finding(
+            "Pre-shared key authentication is accepted, the key may be cracked offline if aggressive mode is supported"
+                .red()
+                .bold()
+        );
+    }
 
-        owo_println!(format!(
-            "{} {}",
-            "Written json output to".bright_black(),
-            target.default_color()
+    let preferences: Vec<_> = res.choices.iter().filter(|x| x.offered > 1).collect();
+    if !preferences.is_empty() {
+        printer.finding("Transforms chosen out of multiple offered ones:");
+    }
+    for choice in preferences {
+        printer.finding(format!(
+            "\t{} {}",
+            choice.transform,
+            format!("(#{} of {})", choice.index + 1, choice.offered).bright_black()
         ));
     }
 
-    owo_println!("---------------");
-    owo_println!("See you soon! :)".blue());
+    if !res.unverified_transforms.is_empty() {
+        printer.finding("Transforms that failed verification:".yellow());
+    }
+    for unverified in &res.unverified_transforms {
+        printer.finding(format!("\t{unverified}").bright_black());
+    }
 
-    Ok(())
+    if !res.unanswered_transforms.is_empty() {
+        printer.finding("Transforms the server never responded to:".yellow());
+    }
+    for unanswered in &res.unanswered_transforms {
+        printer.finding(format!("\t{unanswered}").bright_black());
+    }
+
+    if !res.unparsed_acceptances.is_empty() {
+        printer.finding("Accepted proposals that could not be parsed:".yellow());
+    }
+    for raw in &res.unparsed_acceptances {
+        printer.finding(format!("\t{raw:02x?}").bright_black());
+    }
+
+    if !res.unknown_transforms.is_empty() {
+        printer.finding("Accepted transforms with unknown values:".yellow());
+    }
+    for raw in &res.unknown_transforms {
+        printer.finding(format!("\t{}", format_raw_transform(raw)).bright_black());
+    }
+
+    if !res.quirks.is_empty() {
+        printer.finding("Detected server quirks:");
+    }
+    for quirk in &res.quirks {
+        printer.finding(format!("\t{quirk}").bright_black());
+    }
+
+    if cli.probe_exchange_types {
+        printer.finding("Supported exchange types:");
+        if res.supported_exchange_types.is_empty() {
+            printer.finding("\tNone".bright_black());
+        }
+    }
+    for exchange_type in &res.supported_exchange_types {
+        printer.finding(format!("\t{exchange_type:?}").bright_black());
+    }
+
+    if res.nat_discovery {
+        printer.finding("Server sent NAT discovery payloads, NAT-traversal is supported");
+    }
+    if res.nat_t_vendor_id {
+        printer.finding("Server sent a NAT-T vendor ID, NAT-traversal is supported");
+    }
+
+    if !res.vendor_ids.is_empty() {
+        printer.finding("Vendor IDs:");
+    }
+    for vendor_id in &res.vendor_ids {
+        printer.finding(format!("\t{vendor_id}").bright_black());
+    }
+
+    if !res.notifications.is_empty() {
+        printer.finding("Notifications:");
+    }
+    for notification in &res.notifications {
+        printer.finding(format!("\t{notification}").bright_black());
+    }
+
+    if let Some(responder_cookie) = res.responder_cookie {
+        printer.finding(format!("Responder cookie: {responder_cookie:#018x}").bright_black());
+    }
+
+    if let Some(rtt) = res.round_trip_times {
+        printer.finding(
+            format!(
+                "Round-trip time of {} responses: min {} ms, avg {} ms, max {} ms",
+                rtt.count,
+                rtt.min.as_millis(),
+                rtt.avg.as_millis(),
+                rtt.max.as_millis()
+            )
+            .bright_black(),
+        );
+    }
+
+    let lifetimes = res.lifetimes();
+    if !lifetimes.is_empty() {
+        printer.finding("Lifetimes of the security associations:");
+    }
+    for lifetime in lifetimes {
+        printer.finding(format!("\t{lifetime}").bright_black());
+    }
+
+    if let Some(matrix) = cli.matrix.and_then(|(x, y)| res.matrix(x, y)) {
+        printer.separator();
+        printer.finding(format!(
+            "Accepted combinations ({} accepted, {} rejected, {} unknown):",
+            "+".green(),
+            "-".red(),
+            "?".bright_black()
+        ));
+
+        let row_width = matrix.rows.iter().map(|x| x.len()).max().unwrap_or(0);
+        printer.finding(format!("\t{:row_width$} {}", "", matrix.columns.join(" ")));
+        for (row, cells) in matrix.rows.iter().zip(&matrix.cells) {
+            let cells = matrix
+                .columns
+                .iter()
+                .zip(cells)
+                .map(|(column, cell)| {
+                    let width = column.len();
+                    match cell {
+                        MatrixCell::Accepted => format!("{:^width$}", "+").green().to_string(),
+                        MatrixCell::Rejected => format!("{:^width$}", "-").red().to_string(),
+                        MatrixCell::Indeterminate => {
+                            format!("{:^width$}", "?").bright_black().to_string()
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+            printer.finding(format!("\t{row:row_width$} {}", cells.join(" ")));
+        }
+    }
+
+    if let (Some(previous), Some(path)) = (previous, &cli.compare) {
+        printer.separator();
+        let diff = res.diff(previous);
+        if diff.is_empty() {
+            printer.finding(format!("No changes compared to {path}").bright_black());
+        } else {
+            printer.finding(format!("Changes compared to {path}:"));
+        }
+        for added in &diff.added {
+            printer.finding(format!("\t+ {added}").green());
+        }
+        for removed in &diff.removed {
+            printer.finding(format!("\t- {removed}").red());
+        }
+    }
 }
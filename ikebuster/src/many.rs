@@ -0,0 +1,149 @@
+//! Scanning multiple targets with a bounded number of scans at the same time
+//!
+//! Every scan binds [ScanOptions::source_port], so scans running at the same time need
+//! an ephemeral source port.
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::panic;
+use std::time::Duration;
+
+use tokio::task::JoinSet;
+use tokio::time::timeout;
+use tokio::time::Instant;
+use tracing::info;
+use tracing::warn;
+
+use crate::ScanError;
+use crate::ScanOptions;
+use crate::ScanResult;
+
+/// Limits of scanning multiple targets, see [scan_many]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanManyOptions {
+    /// Maximum duration of the scan of a single target
+    pub timeout_per_host: Option<Duration>,
+    /// Overall duration after which the targets that weren't started yet are skipped
+    ///
+    /// The scan of the target that is running when the budget is exhausted is finished.
+    pub budget: Option<Duration>,
+    /// Maximum number of targets scanned at the same time, `0` is treated as `1`
+    pub concurrency: usize,
+}
+
+/// The outcome of scanning a single target of [scan_many]
+#[derive(Debug)]
+pub enum HostOutcome {
+    /// The scan finished
+    Completed(Box<ScanResult>),
+    /// The scan failed
+    Failed(ScanError),
+    /// The scan exceeded [ScanManyOptions::timeout_per_host]
+    TimedOut,
+    /// The scan wasn't started, as [ScanManyOptions::budget] was exhausted
+    Skipped,
+}
+
+/// The result of a single target of [scan_many]
+#[derive(Debug)]
+pub struct HostResult {
+    /// The scanned target
+    pub target: SocketAddr,
+    /// The outcome of the scan
+    pub outcome: HostOutcome,
+}
+
+/// Scan each of the targets using [crate::scan]
+///
+/// The options are used for every target, except for [ScanOptions::ip] and
+/// [ScanOptions::port], which are taken from the target.
+pub async fn scan_many(
+    opts: ScanOptions,
+    targets: Vec<SocketAddr>,
+    limits: ScanManyOptions,
+) -> Vec<HostResult> {
+    scan_many_with(opts, targets, limits, crate::scan).await
+}
+
+/// Scan each of the targets using the given scan function
+///
+/// This allows scanning over a different transport, see [crate::scan_with_transport].
+/// The results are returned in the order of the targets, regardless of the order the
+/// scans finished in.
+pub async fn scan_many_with<F, Fut>(
+    opts: ScanOptions,
+    targets: Vec<SocketAddr>,
+    limits: ScanManyOptions,
+    mut scan: F,
+) -> Vec<HostResult>
+where
+    F: FnMut(ScanOptions) -> Fut,
+    Fut: Future<Output = Result<ScanResult, ScanError>> + Send + 'static,
+{
+    let started = Instant::now();
+    let concurrency = limits.concurrency.max(1);
+    let mut outcomes: Vec<Option<HostOutcome>> = targets.iter().map(|_| None).collect();
+    let mut running = JoinSet::new();
+
+    for (index, target) in targets.iter().copied().enumerate() {
+        while running.len() >= concurrency {
+            if let Some((index, outcome)) = join_next(&mut running).await {
+                outcomes[index] = Some(outcome);
+            }
+        }
+
+        if limits
+            .budget
+            .is_some_and(|budget| started.elapsed() >= budget)
+        {
+            info!("Skipping {target}, the budget is exhausted");
+            outcomes[index] = Some(HostOutcome::Skipped);
+            continue;
+        }
+
+        let scan = scan(ScanOptions {
+            ip: target.ip(),
+            port: target.port(),
+            ..opts.clone()
+        });
+        let timeout_per_host = limits.timeout_per_host;
+        running.spawn(async move {
+            let outcome = match timeout_per_host {
+                None => scan.await,
+                Some(duration) => match timeout(duration, scan).await {
+                    Ok(res) => res,
+                    Err(_) => {
+                        warn!("Scan of {target} timed out after {} s", duration.as_secs());
+                        return (index, HostOutcome::TimedOut);
+                    }
+                },
+            };
+            let outcome = match outcome {
+                Ok(res) => HostOutcome::Completed(Box::new(res)),
+                Err(err) => HostOutcome::Failed(err),
+            };
+            (index, outcome)
+        });
+    }
+    while let Some((index, outcome)) = join_next(&mut running).await {
+        outcomes[index] = Some(outcome);
+    }
+
+    targets
+        .into_iter()
+        .zip(outcomes)
+        .map(|(target, outcome)| HostResult {
+            target,
+            // Every target was either skipped or its scan was joined
+            outcome: outcome.unwrap_or(HostOutcome::Skipped),
+        })
+        .collect()
+}
+
+/// Wait for the next scan to finish, passing on its panic
+async fn join_next(running: &mut JoinSet<(usize, HostOutcome)>) -> Option<(usize, HostOutcome)> {
+    match running.join_next().await? {
+        Ok(res) => Some(res),
+        Err(err) => panic::resume_unwind(err.into_panic()),
+    }
+}
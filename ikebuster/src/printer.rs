@@ -0,0 +1,74 @@
+//! Output of the cli, which is reduced to the findings with `--quiet`
+
+use std::fmt::Display;
+
+use owo_colors::OwoColorize;
+
+const BANNER: &str = r#"
+Welcome to
+  _ _        _               _
+ (_) | _____| |__  _   _ ___| |_ ___ _ __
+ | | |/ / _ \ '_ \| | | / __| __/ _ \ '__|
+ | |   <  __/ |_) | |_| \__ \ ||  __/ |
+ |_|_|\_\___|_.__/ \__,_|___/\__\___|_|
+
+"#;
+
+const SEPARATOR: &str = "---------------";
+
+/// Prints the output of the cli
+#[derive(Debug, Clone, Copy)]
+pub struct Printer {
+    /// Omit the banner, separators and informational output
+    quiet: bool,
+    /// Print the findings to stdout
+    findings: bool,
+}
+
+impl Printer {
+    /// Create a new printer
+    ///
+    /// In quiet mode, the findings are omitted as well if they are written to a json file
+    pub fn new(quiet: bool, json: bool) -> Self {
+        Self {
+            quiet,
+            findings: !(quiet && json),
+        }
+    }
+
+    /// Print the banner
+    pub fn banner(&self) {
+        if !self.quiet {
+            println!("{}", BANNER.blue().bold());
+        }
+    }
+
+    /// Print a separator between two sections
+    pub fn separator(&self) {
+        self.info(SEPARATOR);
+    }
+
+    /// Print informational output
+    pub fn info(&self, line: impl Display) {
+        if !self.quiet {
+            println!("{} {line}", "[ikebuster]".purple().bold());
+        }
+    }
+
+    /// Print a finding of the scan, without decoration in quiet mode
+    pub fn finding(&self, line: impl Display) {
+        if !self.findings {
+            return;
+        }
+        if self.quiet {
+            println!("{line}");
+        } else {
+            println!("{} {line}", "[ikebuster]".purple().bold());
+        }
+    }
+
+    /// Print an error to stderr
+    pub fn error(&self, line: impl Display) {
+        eprintln!("{} {line}", "[ikebuster]".purple().bold());
+    }
+}
@@ -0,0 +1,248 @@
+//! Relaying the scan through a SOCKS5 proxy
+//!
+//! The datagrams are sent to the UDP relay of the proxy, which is requested by a
+//! `UDP ASSOCIATE` command. Each datagram is preceded by a header naming its destination.
+//!
+//! Many SOCKS5 proxies (e.g. `ssh -D`) don't support `UDP ASSOCIATE` at all.
+//!
+//! For more information, take a look at:
+//! https://datatracker.ietf.org/doc/html/rfc1928
+
+use std::io;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddr;
+
+use thiserror::Error;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::net::UdpSocket;
+use tracing::trace;
+
+use crate::transport::IkeTransport;
+
+const VERSION: u8 = 5;
+const METHOD_NO_AUTHENTICATION: u8 = 0x00;
+const METHOD_USERNAME_PASSWORD: u8 = 0x02;
+const COMMAND_UDP_ASSOCIATE: u8 = 0x03;
+const ADDRESS_TYPE_IPV4: u8 = 0x01;
+const ADDRESS_TYPE_IPV6: u8 = 0x04;
+
+/// Configuration of the SOCKS5 proxy the scan is relayed through
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Address of the proxy
+    pub addr: SocketAddr,
+    /// Username and password to authenticate with
+    pub credentials: Option<(String, String)>,
+}
+
+/// An established association with the UDP relay of a proxy
+///
+/// The relay is only kept as long as the association is alive.
+#[derive(Debug)]
+pub struct UdpAssociation {
+    /// The connection the association was requested on
+    _control: TcpStream,
+    /// The address datagrams have to be sent to
+    pub relay: SocketAddr,
+}
+
+/// Request an association with the UDP relay of the proxy
+pub async fn udp_associate(config: &ProxyConfig) -> Result<UdpAssociation, ProxyError> {
+    let mut control = TcpStream::connect(config.addr).await?;
+
+    let method = match config.credentials {
+        Some(_) => METHOD_USERNAME_PASSWORD,
+        None => METHOD_NO_AUTHENTICATION,
+    };
+    control.write_all(&[VERSION, 1, method]).await?;
+    let mut reply = [0; 2];
+    control.read_exact(&mut reply).await?;
+    if reply[0] != VERSION {
+        return Err(ProxyError::UnsupportedVersion(reply[0]));
+    }
+    if reply[1] != method {
+        return Err(ProxyError::NoAcceptableMethod);
+    }
+
+    if let Some((username, password)) = &config.credentials {
+        // https://datatracker.ietf.org/doc/html/rfc1929
+        let mut request = vec![1];
+        for field in [username, password] {
+            let len = u8::try_from(field.len()).map_err(|_| ProxyError::CredentialsTooLong)?;
+            request.push(len);
+            request.extend_from_slice(field.as_bytes());
+        }
+        control.write_all(&request).await?;
+        control.read_exact(&mut reply).await?;
+        if reply[1] != 0 {
+            return Err(ProxyError::AuthenticationFailed);
+        }
+    }
+
+    // The address the datagrams are sent from is unknown until the socket is bound,
+    // so let the proxy accept datagrams from any address
+    let mut request = vec![VERSION, COMMAND_UDP_ASSOCIATE, 0];
+    write_address(
+        &mut request,
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+    );
+    control.write_all(&request).await?;
+
+    let mut reply = [0; 4];
+    control.read_exact(&mut reply).await?;
+    if reply[0] != VERSION {
+        return Err(ProxyError::UnsupportedVersion(reply[0]));
+    }
+    if reply[1] != 0 {
+        return Err(ProxyError::Rejected(reply[1]));
+    }
+    let len = match reply[3] {
+        ADDRESS_TYPE_IPV4 => 4 + 2,
+        ADDRESS_TYPE_IPV6 => 16 + 2,
+        address_type => return Err(ProxyError::UnsupportedAddressType(address_type)),
+    };
+    let mut addr = vec![0; len];
+    control.read_exact(&mut addr).await?;
+    let (mut relay, _) =
+        read_address(reply[3], &addr).ok_or(ProxyError::UnsupportedAddressType(reply[3]))?;
+
+    // Proxies commonly answer with an unspecified address if the relay listens on the
+    // same address as the proxy itself
+    if relay.ip().is_unspecified() {
+        relay.set_ip(config.addr.ip());
+    }
+
+    Ok(UdpAssociation {
+        _control: control,
+        relay,
+    })
+}
+
+/// Transport relaying the datagrams through the UDP relay of a proxy
+#[derive(Debug)]
+pub struct Socks5Transport {
+    /// Socket connected to the relay
+    socket: UdpSocket,
+    /// The association the relay belongs to
+    _association: UdpAssociation,
+    /// The scanned target
+    target: SocketAddr,
+}
+
+impl Socks5Transport {
+    /// Exchange datagrams with `target` through the relay of the association
+    ///
+    /// The socket has to be connected to [UdpAssociation::relay].
+    pub fn new(socket: UdpSocket, association: UdpAssociation, target: SocketAddr) -> Self {
+        Self {
+            socket,
+            _association: association,
+            target,
+        }
+    }
+}
+
+impl IkeTransport for Socks5Transport {
+    async fn send(&self, datagram: &[u8]) -> io::Result<()> {
+        self.socket
+            .send(&encode_udp_header(self.target, datagram))
+            .await
+            .map(|_| ())
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let len = self.socket.recv(buf).await?;
+            match decode_udp_header(&buf[..len]) {
+                Some((source, payload)) if source == self.target => {
+                    let payload = payload.len();
+                    buf.copy_within(len - payload..len, 0);
+                    return Ok(payload);
+                }
+                Some((source, _)) => trace!("Ignoring relayed datagram from {source}"),
+                None => trace!("Ignoring datagram with malformed relay header"),
+            }
+        }
+    }
+}
+
+/// Prepend the header of the UDP relay to a datagram destined for `target`
+pub fn encode_udp_header(target: SocketAddr, payload: &[u8]) -> Vec<u8> {
+    // Two reserved bytes and the fragment number, fragmentation is not used
+    let mut datagram = vec![0, 0, 0];
+    write_address(&mut datagram, target);
+    datagram.extend_from_slice(payload);
+    datagram
+}
+
+/// Split a datagram received from the UDP relay into its source and payload
+///
+/// Returns `None` if the header is malformed, the datagram is a fragment or the source
+/// is given as domain name.
+pub fn decode_udp_header(datagram: &[u8]) -> Option<(SocketAddr, &[u8])> {
+    let [0, 0, 0, address_type, addr @ ..] = datagram else {
+        return None;
+    };
+    let (source, len) = read_address(*address_type, addr)?;
+    Some((source, &addr[len..]))
+}
+
+/// Serialize the address type, address and port
+fn write_address(buf: &mut Vec<u8>, addr: SocketAddr) {
+    match addr.ip() {
+        IpAddr::V4(ip) => {
+            buf.push(ADDRESS_TYPE_IPV4);
+            buf.extend_from_slice(&ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            buf.push(ADDRESS_TYPE_IPV6);
+            buf.extend_from_slice(&ip.octets());
+        }
+    }
+    buf.extend_from_slice(&addr.port().to_be_bytes());
+}
+
+/// Parse an address and port of the given type, returning the number of bytes read
+fn read_address(address_type: u8, buf: &[u8]) -> Option<(SocketAddr, usize)> {
+    let (ip, len) = match address_type {
+        ADDRESS_TYPE_IPV4 => {
+            let octets: [u8; 4] = buf.get(..4)?.try_into().ok()?;
+            (IpAddr::V4(Ipv4Addr::from(octets)), 4)
+        }
+        ADDRESS_TYPE_IPV6 => {
+            let octets: [u8; 16] = buf.get(..16)?.try_into().ok()?;
+            (IpAddr::V6(Ipv6Addr::from(octets)), 16)
+        }
+        // Domain names are never used for relayed IKE messages
+        _ => return None,
+    };
+    let port = buf.get(len..len + 2)?;
+    Some((
+        SocketAddr::new(ip, u16::from_be_bytes([port[0], port[1]])),
+        len + 2,
+    ))
+}
+
+/// Errors that may occur while associating with the proxy
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+pub enum ProxyError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("Unsupported SOCKS version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("The proxy accepts none of the offered authentication methods")]
+    NoAcceptableMethod,
+    #[error("Username and password must not exceed 255 bytes")]
+    CredentialsTooLong,
+    #[error("The proxy rejected the credentials")]
+    AuthenticationFailed,
+    #[error("The proxy rejected the UDP association with reply code {0}")]
+    Rejected(u8),
+    #[error("Unsupported address type of the relay: {0}")]
+    UnsupportedAddressType(u8),
+}
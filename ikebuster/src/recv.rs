@@ -1,45 +1,150 @@
 use std::io;
 use std::sync::Arc;
 
+use isakmp::v1::fragments::FragmentError;
+use isakmp::v1::fragments::Reassembler;
 use isakmp::v1::parser::definitions::Packet;
 use isakmp::v1::parser::errors::IsakmpParseError;
 use thiserror::Error;
-use tokio::net::UdpSocket;
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::Sender;
+use tokio::task::JoinHandle;
+use tracing::debug;
+use tracing::trace;
+
+use crate::transport::IkeTransport;
+use crate::utils::socket_errors::is_transient;
+
+/// The marker that precedes IKE messages on the NAT-T port to distinguish them from ESP packets
+///
+/// For more information, take a look at:
+/// https://datatracker.ietf.org/doc/html/rfc3948#section-2.2
+pub const NON_ESP_MARKER: [u8; 4] = [0; 4];
+
+/// Strip the [NON_ESP_MARKER] from a datagram received on the NAT-T port
+///
+/// Returns `None` if the datagram doesn't start with the marker, so it is an ESP packet
+pub fn strip_non_esp_marker(buf: &[u8]) -> Option<&[u8]> {
+    buf.strip_prefix(NON_ESP_MARKER.as_slice())
+}
+
+/// How the IKE messages are encapsulated in the exchanged datagrams
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Framing {
+    /// Messages are preceded by the [NON_ESP_MARKER]
+    pub non_esp_marker: bool,
+}
+
+impl Framing {
+    /// Encapsulate a message that is about to be sent
+    pub fn wrap(&self, mut msg: Vec<u8>) -> Vec<u8> {
+        if self.non_esp_marker {
+            msg.splice(0..0, NON_ESP_MARKER);
+        }
+        msg
+    }
+
+    /// Extract the message of a received datagram
+    ///
+    /// Returns `None` if the datagram doesn't carry an IKE message of the target
+    pub fn unwrap<'a>(&self, mut datagram: &'a [u8]) -> Option<&'a [u8]> {
+        if self.non_esp_marker {
+            datagram = strip_non_esp_marker(datagram)?;
+        }
+        Some(datagram)
+    }
+
+    /// Number of bytes added to each message
+    pub fn overhead(&self) -> usize {
+        self.wrap(Vec::new()).len()
+    }
+}
+
+/// Guard that aborts the receiving task when dropped
+///
+/// This ensures the task stops on every return path of the scan,
+/// even if the socket is still referenced elsewhere
+pub struct AbortOnDrop(pub JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
 
 /// Handle the receival of isakmp messages
 ///
-/// After a message is received, it is sent back via the provided channel
-pub async fn handle_receive(
-    socket: Arc<UdpSocket>,
-    tx: UnboundedSender<Result<Packet, ReceiveError>>,
+/// After a message is received, it is sent back via the provided channel. While the channel
+/// is full, no further datagrams are read, see [crate::ScanOptions::receive_capacity].
+///
+/// Datagrams are unwrapped according to the [Framing], datagrams that don't carry an
+/// IKE message (e.g. ESP packets) are ignored.
+///
+/// Fragmented messages are buffered until all of their fragments were received,
+/// only the reassembled message is sent back.
+pub async fn handle_receive<T: IkeTransport>(
+    transport: Arc<T>,
+    tx: Sender<Result<Packet, ReceiveError>>,
+    framing: Framing,
 ) {
+    let mut reassembler = Reassembler::default();
     loop {
         const MAX_DATAGRAM_SIZE: usize = 65_507;
         let mut buf = [0u8; MAX_DATAGRAM_SIZE];
-        let len = match socket.recv(&mut buf).await {
+        let len = match transport.recv(&mut buf).await {
             Ok(len) => len,
+            Err(e) if is_transient(&e) => {
+                debug!("Transient error while receiving: {e}");
+                continue;
+            }
             Err(e) => {
-                let _res = tx.send(Err(ReceiveError::Io(e)));
+                let _res = tx.send(Err(ReceiveError::Io(e))).await;
                 return;
             }
         };
 
-        match isakmp::v1::parser::parse_packet(&buf[..len]) {
-            Ok(packet) => {
-                if tx.send(Ok(packet)).is_err() {
-                    // Stop loop if we can't send to channel
-                    return;
-                }
-            }
-            Err(err) => {
-                if tx.send(Err(ReceiveError::InvalidMessage(err))).is_err() {
+        let Some(datagram) = framing.unwrap(&buf[..len]) else {
+            trace!("Ignoring datagram without IKE message");
+            continue;
+        };
+
+        // The major version is located in the upper nibble of the 18th byte of the header
+        if let Some(major_version) = datagram.get(17).map(|version| version >> 4) {
+            if major_version != 1 {
+                if tx
+                    .send(Err(ReceiveError::UnsupportedVersion(major_version)))
+                    .await
+                    .is_err()
+                {
                     // Stop loop if we can't send to channel
                     return;
                 }
+                continue;
             }
         }
+
+        let result = match isakmp::v1::parser::parse_packet(datagram) {
+            Ok(packet) if !packet.fragments.is_empty() => match reassembler.add_packet(&packet) {
+                Ok(Some(msg)) => parse_reassembled(&msg),
+                Ok(None) => continue,
+                Err(err) => Err(ReceiveError::InvalidFragment(err)),
+            },
+            Ok(packet) => Ok(packet),
+            Err(err) => Err(ReceiveError::InvalidMessage(err)),
+        };
+        if tx.send(result).await.is_err() {
+            // Stop loop if we can't send to channel
+            return;
+        }
+    }
+}
+
+/// Parse a message that was reassembled from its fragments
+fn parse_reassembled(msg: &[u8]) -> Result<Packet, ReceiveError> {
+    let packet = isakmp::v1::parser::parse_packet(msg)?;
+    if !packet.fragments.is_empty() {
+        return Err(ReceiveError::InvalidFragment(FragmentError::Nested));
     }
+    Ok(packet)
 }
 
 /// Errors that may occur on the receiving side
@@ -49,4 +154,8 @@ pub enum ReceiveError {
     Io(#[from] io::Error),
     #[error("Error while parsing message: {0}")]
     InvalidMessage(#[from] IsakmpParseError),
+    #[error("Unsupported major version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Error while reassembling fragments: {0}")]
+    InvalidFragment(#[from] FragmentError),
 }
@@ -0,0 +1,123 @@
+//! Helpers to configure the udp socket used for scanning
+
+use std::io;
+use std::net::SocketAddr;
+
+use tokio::net::UdpSocket;
+
+/// Pin the socket to the network interface with the given name (`SO_BINDTODEVICE`)
+#[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+pub fn bind_to_interface(socket: &UdpSocket, interface: &str) -> io::Result<()> {
+    socket2::SockRef::from(socket).bind_device(Some(interface.as_bytes()))
+}
+
+/// Pin the socket to the network interface with the given name
+///
+/// Binding to an interface is only supported on linux
+#[cfg(not(any(target_os = "android", target_os = "fuchsia", target_os = "linux")))]
+pub fn bind_to_interface(_socket: &UdpSocket, _interface: &str) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "binding to an interface is only supported on linux",
+    ))
+}
+
+/// Resolve the zone of an IPv6 address (e.g. `eth0` of `fe80::1%eth0`) to its scope ID
+///
+/// The zone is either the name or the index of a network interface
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn scope_id(zone: &str) -> io::Result<u32> {
+    if let Ok(index) = zone.parse() {
+        return Ok(index);
+    }
+
+    let name = std::ffi::CString::new(zone)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    // SAFETY: The name is a valid nul-terminated string
+    let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+    if index == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(index)
+}
+
+/// Resolve the zone of an IPv6 address (e.g. `3` of `fe80::1%3`) to its scope ID
+///
+/// Resolving interface names is only supported on linux, other zones have to be given as
+/// the index of the interface
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+pub fn scope_id(zone: &str) -> io::Result<u32> {
+    zone.parse().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "resolving interface names is only supported on linux",
+        )
+    })
+}
+
+/// Set the TTL (IPv4) or hop limit (IPv6) of outgoing packets
+///
+/// The option matching the address family of the local address of the socket is set
+pub fn set_ttl(socket: &UdpSocket, ttl: u32) -> io::Result<()> {
+    match socket.local_addr()? {
+        SocketAddr::V4(_) => socket.set_ttl(ttl),
+        SocketAddr::V6(_) => socket2::SockRef::from(socket).set_unicast_hops_v6(ttl),
+    }
+}
+
+/// Set the DF (don't fragment) bit on outgoing packets
+///
+/// Messages exceeding the path MTU are rejected with `EMSGSIZE` instead of being fragmented,
+/// see [is_message_too_large].
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn set_dont_fragment(socket: &UdpSocket) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let (level, name, value) = match socket.local_addr()? {
+        SocketAddr::V4(_) => (
+            libc::IPPROTO_IP,
+            libc::IP_MTU_DISCOVER,
+            libc::IP_PMTUDISC_DO,
+        ),
+        SocketAddr::V6(_) => (
+            libc::IPPROTO_IPV6,
+            libc::IPV6_MTU_DISCOVER,
+            libc::IPV6_PMTUDISC_DO,
+        ),
+    };
+    // SAFETY: The file descriptor is valid for the lifetime of the socket and the option
+    // value is a c_int, as expected by both options
+    let res = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            level,
+            name,
+            (&value as *const libc::c_int).cast(),
+            size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if res == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Set the DF (don't fragment) bit on outgoing packets
+///
+/// Setting the DF bit is only supported on linux
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+pub fn set_dont_fragment(_socket: &UdpSocket) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "setting the DF bit is only supported on linux",
+    ))
+}
+
+/// Check whether sending failed, as the message exceeds the path MTU
+pub fn is_message_too_large(err: &io::Error) -> bool {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    return err.raw_os_error() == Some(libc::EMSGSIZE);
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    return false;
+}
@@ -0,0 +1,477 @@
+//! Harness to run scans against a scripted server
+//!
+//! The [ScriptedResponder] is an [IkeTransport] that answers every sent proposal
+//! according to a [Behavior] and a set of transforms the server supports.
+
+use std::collections::VecDeque;
+use std::io;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use isakmp::v1::definitions::DomainOfInterpretation;
+use isakmp::v1::definitions::ExchangeType;
+use isakmp::v1::definitions::NotifyMessageType;
+use isakmp::v1::definitions::PayloadType;
+use isakmp::v1::fragments::fragment_message;
+use isakmp::v1::generator::Lifetime;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::DEFAULT_ATTRIBUTE_ORDER;
+use isakmp::v1::generator::PROPOSAL_NO;
+use isakmp::v1::parser::parse_packet;
+use tokio::sync::Notify;
+
+use crate::planned_transforms;
+use crate::recv::strip_non_esp_marker;
+use crate::recv::NON_ESP_MARKER;
+use crate::transport::IkeTransport;
+use crate::utils::gen_transforms::TransformFilter;
+use crate::utils::payload_to_transforms::payload_to_transforms;
+use crate::ScanOptions;
+use crate::DEFAULT_RECEIVE_CAPACITY;
+
+/// The responder cookie of all responses
+pub const RESPONDER_COOKIE: u64 = 0x1122_3344_5566_7788;
+
+/// Header (28) + generic payload header of the SA payload (4)
+const DOI_OFFSET: usize = 32;
+
+/// Time after which a response that is held back to reorder it is released anyway
+const REORDER_TIMEOUT: Duration = Duration::from_millis(20);
+
+/// How the [ScriptedResponder] answers a proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Behavior {
+    /// Accept the first offered transform the server supports,
+    /// reject the proposal if there is none
+    PickOne,
+    /// Accept the first offered transform, regardless of the supported transforms
+    AcceptsAll,
+    /// Reject every proposal
+    RejectsAll,
+    /// Like [Behavior::PickOne], but every other response is delivered after the
+    /// response to the following proposal
+    Reorders,
+    /// Like [Behavior::PickOne], but proposals without a supported transform are accepted
+    /// with a supported transform that was not offered
+    Unsolicited,
+    /// Accept every offered transform the server supports, each in a security association
+    /// of its own
+    SeparateAssociations,
+    /// Like [Behavior::PickOne], but the security association is returned with the
+    /// ISAKMP domain of interpretation instead of the proposed one
+    OtherDomainOfInterpretation,
+    /// Answer every proposal with a notification of the given type only, e.g. an error
+    /// other than NO-PROPOSAL-CHOSEN
+    Notifies(NotifyMessageType),
+}
+
+/// An [IkeTransport] answering with canned responses
+#[derive(Debug)]
+pub struct ScriptedResponder {
+    behavior: Behavior,
+    supported: Vec<Transform>,
+    rate_limit: Option<(usize, Duration)>,
+    drop_every: Option<usize>,
+    nat_t: bool,
+    vendor_ids: Vec<Vec<u8>>,
+    lifetime: Lifetime,
+    proposal_no: Option<u8>,
+    fragment_size: Option<usize>,
+    state: Mutex<State>,
+    notify: Notify,
+}
+
+/// The responses of a [ScriptedResponder]
+#[derive(Debug, Default)]
+struct State {
+    /// Responses that can be received
+    queue: VecDeque<Vec<u8>>,
+    /// A response that is held back to deliver it after the next one
+    held: Option<Vec<Vec<u8>>>,
+    /// All proposals that were received
+    proposals: Vec<Vec<Transform>>,
+    /// Number of responses since the rate limit was lifted
+    answered: usize,
+    /// The time the rate limit was hit
+    limited_since: Option<Instant>,
+    /// Number of received proposals
+    received: usize,
+}
+
+impl ScriptedResponder {
+    /// Create a responder for a server supporting the given transforms
+    pub fn new(behavior: Behavior, supported: Vec<Transform>) -> Self {
+        Self {
+            behavior,
+            supported,
+            rate_limit: None,
+            drop_every: None,
+            nat_t: false,
+            vendor_ids: vec![],
+            lifetime: Lifetime::default(),
+            proposal_no: None,
+            fragment_size: None,
+            state: Mutex::new(State::default()),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Stop answering after `limit` responses until `cooldown` has passed
+    pub fn rate_limit(mut self, limit: usize, cooldown: Duration) -> Self {
+        self.rate_limit = Some((limit, cooldown));
+        self
+    }
+
+    /// Don't answer every `n`-th proposal
+    pub fn drop_every(mut self, n: usize) -> Self {
+        self.drop_every = Some(n);
+        self
+    }
+
+    /// Expect and answer messages prefixed with the non-ESP marker, like a server on the
+    /// NAT-traversal port
+    ///
+    /// Messages without the marker are ignored.
+    pub fn nat_t(mut self) -> Self {
+        self.nat_t = true;
+        self
+    }
+
+    /// Append a vendor ID payload to every response
+    pub fn vendor_id(mut self, vendor_id: impl Into<Vec<u8>>) -> Self {
+        self.vendor_ids.push(vendor_id.into());
+        self
+    }
+
+    /// Respond with the given lifetime of the security association instead of the default
+    pub fn lifetime(mut self, lifetime: Lifetime) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Accept proposals with the given proposal number instead of the offered one
+    pub fn proposal_no(mut self, proposal_no: u8) -> Self {
+        self.proposal_no = Some(proposal_no);
+        self
+    }
+
+    /// Split every response into fragments carrying at most `fragment_size` bytes of it
+    pub fn fragment(mut self, fragment_size: usize) -> Self {
+        self.fragment_size = Some(fragment_size);
+        self
+    }
+
+    /// The transforms of every proposal that was sent to the responder
+    pub fn proposals(&self) -> Vec<Vec<Transform>> {
+        self.lock().proposals.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // The state is never left inconsistent, so a poisoned lock can be used nevertheless
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Determine the datagrams of the response to a sent message
+    fn respond(&self, mut datagram: &[u8]) -> Option<Vec<Vec<u8>>> {
+        if self.nat_t {
+            datagram = strip_non_esp_marker(datagram)?;
+        }
+        let packet = parse_packet(datagram).ok()?;
+        let initiator_cookie = packet.header.initiator_cookie;
+
+        let mut offered = vec![];
+        for sa in &packet.security_associations {
+            for proposal in &sa.proposal_payload {
+                offered.extend(payload_to_transforms(proposal).ok()?);
+            }
+        }
+        self.lock()
+            .proposals
+            .push(offered.iter().map(|x| x.transform.clone()).collect());
+
+        let mut supported = offered
+            .iter()
+            .filter(|x| self.supported.contains(&x.transform))
+            .map(|x| (x.transform.clone(), x.transform_no));
+        let chosen: Vec<(Transform, u8)> = match self.behavior {
+            Behavior::PickOne | Behavior::Reorders | Behavior::OtherDomainOfInterpretation => {
+                supported.next().into_iter().collect()
+            }
+            Behavior::Unsolicited => supported
+                .next()
+                .or_else(|| Some((self.supported.first()?.clone(), 0)))
+                .into_iter()
+                .collect(),
+            Behavior::SeparateAssociations => supported.collect(),
+            Behavior::AcceptsAll => offered
+                .first()
+                .map(|x| (x.transform.clone(), x.transform_no))
+                .into_iter()
+                .collect(),
+            Behavior::RejectsAll => vec![],
+            Behavior::Notifies(notify_message_type) => {
+                return Some(self.datagrams(notification(initiator_cookie, notify_message_type)));
+            }
+        };
+
+        let rejected = chosen.is_empty();
+        let mut msg = if rejected {
+            reject(initiator_cookie)
+        } else {
+            accept(initiator_cookie, chosen, self.lifetime, self.proposal_no)
+        };
+        if !rejected && self.behavior == Behavior::OtherDomainOfInterpretation {
+            msg[DOI_OFFSET..DOI_OFFSET + 4]
+                .copy_from_slice(&(DomainOfInterpretation::ISAKMP as u32).to_be_bytes());
+        }
+        append_vendor_ids(&mut msg, &self.vendor_ids);
+        Some(self.datagrams(msg))
+    }
+
+    /// Split the response into fragments if enabled and prefix every datagram with the
+    /// non-ESP marker if the responder uses NAT-traversal
+    fn datagrams(&self, msg: Vec<u8>) -> Vec<Vec<u8>> {
+        let datagrams = match self.fragment_size {
+            Some(fragment_size) => fragment_message(&msg, 1, fragment_size).unwrap_or_default(),
+            None => vec![msg],
+        };
+        datagrams
+            .into_iter()
+            .map(|mut datagram| {
+                if self.nat_t {
+                    datagram.splice(0..0, NON_ESP_MARKER);
+                }
+                datagram
+            })
+            .collect()
+    }
+}
+
+impl IkeTransport for ScriptedResponder {
+    async fn send(&self, datagram: &[u8]) -> io::Result<()> {
+        let Some(response) = self.respond(datagram) else {
+            return Ok(());
+        };
+
+        let mut state = self.lock();
+        state.received += 1;
+        if self
+            .drop_every
+            .is_some_and(|n| state.received.is_multiple_of(n))
+        {
+            return Ok(());
+        }
+        if let Some((limit, cooldown)) = self.rate_limit {
+            match state.limited_since {
+                Some(since) if since.elapsed() < cooldown => return Ok(()),
+                Some(_) => {
+                    state.limited_since = None;
+                    state.answered = 0;
+                }
+                None => {}
+            }
+            state.answered += 1;
+            if state.answered >= limit {
+                state.limited_since = Some(Instant::now());
+            }
+        }
+
+        if self.behavior == Behavior::Reorders {
+            match state.held.take() {
+                None => {
+                    state.held = Some(response);
+                    return Ok(());
+                }
+                Some(held) => {
+                    state.queue.extend(response);
+                    state.queue.extend(held);
+                }
+            }
+        } else {
+            state.queue.extend(response);
+        }
+        drop(state);
+
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let notified = self.notify.notified();
+            {
+                let mut state = self.lock();
+                if let Some(response) = state.queue.pop_front() {
+                    let len = response.len().min(buf.len());
+                    buf[..len].copy_from_slice(&response[..len]);
+                    return Ok(len);
+                }
+            }
+
+            if tokio::time::timeout(REORDER_TIMEOUT, notified)
+                .await
+                .is_err()
+            {
+                // The held response has no successor to swap places with
+                let mut state = self.lock();
+                if let Some(held) = state.held.take() {
+                    state.queue.extend(held);
+                }
+            }
+        }
+    }
+}
+
+/// Options for a quick scan against a [ScriptedResponder]
+pub fn scan_options() -> ScanOptions {
+    ScanOptions {
+        ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        scope_id: 0,
+        port: 500,
+        source_port: 500,
+        nat_t: false,
+        interval: 1,
+        jitter: Duration::ZERO,
+        response_timeout: Duration::from_secs(5),
+        receive_capacity: DEFAULT_RECEIVE_CAPACITY,
+        max_retries: 0,
+        retransmit_interval: Duration::from_secs(1),
+        transform_no: 8,
+        sleep_on_transform_found: Duration::ZERO,
+        rate_limit: None,
+        bind_retries: 0,
+        interface: None,
+        ttl: None,
+        dont_fragment: false,
+        verify_findings: false,
+        stop_on_first_accept: false,
+        max_transforms: Some(40),
+        transform_filter: TransformFilter::default(),
+        transforms: None,
+        attribute_order: DEFAULT_ATTRIBUTE_ORDER.to_vec(),
+        max_message_size: None,
+        record: false,
+        replay: None,
+        probe_exchange_types: false,
+        progress: None,
+        cancel: None,
+        #[cfg(feature = "socks5")]
+        proxy: None,
+    }
+}
+
+/// Every `n`-th of the transforms planned for [scan_options], to be supported by a
+/// [ScriptedResponder]
+pub fn supported_every(n: usize) -> Vec<Transform> {
+    planned_transforms(&scan_options())
+        .into_iter()
+        .step_by(n)
+        .collect()
+}
+
+/// A response accepting the transforms in a security association each,
+/// echoing their transform numbers
+///
+/// The proposals are numbered `proposal_no` if set, [PROPOSAL_NO] otherwise.
+fn accept(
+    initiator_cookie: u64,
+    chosen: Vec<(Transform, u8)>,
+    lifetime: Lifetime,
+    proposal_no: Option<u8>,
+) -> Vec<u8> {
+    const HEADER_LENGTH: usize = 28;
+    /// SA payload (12) + generic payload header (4)
+    const PROPOSAL_NO_OFFSET: usize = 16;
+    /// SA payload (12) + proposal payload (8) + generic payload header (4)
+    const TRANSFORM_NO_OFFSET: usize = 24;
+
+    let mut msg = vec![];
+    let associations = chosen.len();
+    for (i, (transform, transform_no)) in chosen.into_iter().enumerate() {
+        let (built, _) = MessageBuilder::new()
+            .initiator_cookie(initiator_cookie)
+            .responder_cookie(RESPONDER_COOKIE)
+            .lifetime(lifetime)
+            .add_transform(transform)
+            .build();
+        if msg.is_empty() {
+            msg.extend_from_slice(&built[..HEADER_LENGTH]);
+        }
+
+        let mut sa = built[HEADER_LENGTH..].to_vec();
+        sa[TRANSFORM_NO_OFFSET] = transform_no;
+        sa[PROPOSAL_NO_OFFSET] = proposal_no.unwrap_or(PROPOSAL_NO);
+        if i + 1 < associations {
+            sa[0] = PayloadType::SecurityAssociation as u8;
+        }
+        msg.extend_from_slice(&sa);
+    }
+
+    let length = msg.len() as u32;
+    msg[HEADER_LENGTH - 4..HEADER_LENGTH].copy_from_slice(&length.to_be_bytes());
+    msg
+}
+
+/// Append a vendor ID payload for each of the `vendor_ids` to the end of the payload chain
+fn append_vendor_ids(msg: &mut Vec<u8>, vendor_ids: &[Vec<u8>]) {
+    const HEADER_LENGTH: usize = 28;
+    const NEXT_PAYLOAD_OFFSET: usize = 16;
+
+    // Find the next payload field of the last payload
+    let mut next_payload = NEXT_PAYLOAD_OFFSET;
+    let mut offset = HEADER_LENGTH;
+    while msg[next_payload] != PayloadType::None as u8 {
+        next_payload = offset;
+        offset += u16::from_be_bytes([msg[offset + 2], msg[offset + 3]]) as usize;
+    }
+
+    for vendor_id in vendor_ids {
+        msg[next_payload] = PayloadType::VendorID as u8;
+        next_payload = msg.len();
+        msg.push(PayloadType::None as u8);
+        msg.push(0);
+        msg.extend_from_slice(&(4 + vendor_id.len() as u16).to_be_bytes());
+        msg.extend_from_slice(vendor_id);
+    }
+
+    let length = msg.len() as u32;
+    msg[HEADER_LENGTH - 4..HEADER_LENGTH].copy_from_slice(&length.to_be_bytes());
+}
+
+/// An informational response with a NO-PROPOSAL-CHOSEN notification
+fn reject(initiator_cookie: u64) -> Vec<u8> {
+    notification(initiator_cookie, NotifyMessageType::NoProposalChosen)
+}
+
+/// An informational response with a single notification of the given type
+fn notification(initiator_cookie: u64, notify_message_type: NotifyMessageType) -> Vec<u8> {
+    const HEADER_LENGTH: u32 = 28;
+    const NOTIFICATION_LENGTH: u16 = 12;
+
+    let mut msg = vec![];
+    msg.extend_from_slice(&initiator_cookie.to_be_bytes());
+    msg.extend_from_slice(&0u64.to_be_bytes());
+    msg.push(PayloadType::Notification as u8);
+    msg.push(0x10);
+    msg.push(ExchangeType::Informational as u8);
+    msg.push(0);
+    msg.extend_from_slice(&0u32.to_be_bytes());
+    msg.extend_from_slice(&(HEADER_LENGTH + NOTIFICATION_LENGTH as u32).to_be_bytes());
+
+    msg.push(PayloadType::None as u8);
+    msg.push(0);
+    msg.extend_from_slice(&NOTIFICATION_LENGTH.to_be_bytes());
+    // IPsec DOI
+    msg.extend_from_slice(&1u32.to_be_bytes());
+    // ISAKMP protocol without SPI
+    msg.push(1);
+    msg.push(0);
+    msg.extend_from_slice(&(notify_message_type as u16).to_be_bytes());
+    msg
+}
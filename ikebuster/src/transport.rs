@@ -0,0 +1,29 @@
+//! The transport the IKE messages of a scan are exchanged over
+
+use std::future::Future;
+use std::io;
+
+use tokio::net::UdpSocket;
+
+/// A connection to the scanned target, exchanging whole datagrams
+///
+/// Besides the plain [UdpSocket], this allows relaying the scan (e.g. through a proxy)
+/// or scripting the responses of a server.
+pub trait IkeTransport: Send + Sync + 'static {
+    /// Send a datagram to the target
+    fn send(&self, datagram: &[u8]) -> impl Future<Output = io::Result<()>> + Send;
+
+    /// Receive the next datagram of the target, returning its length
+    fn recv(&self, buf: &mut [u8]) -> impl Future<Output = io::Result<usize>> + Send;
+}
+
+/// A socket that is connected to the target
+impl IkeTransport for UdpSocket {
+    async fn send(&self, datagram: &[u8]) -> io::Result<()> {
+        UdpSocket::send(self, datagram).await.map(|_| ())
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        UdpSocket::recv(self, buf).await
+    }
+}
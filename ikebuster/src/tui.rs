@@ -0,0 +1,117 @@
+//! Interactive live view of a running scan
+
+use std::net::SocketAddr;
+use std::panic;
+use std::process::exit;
+use std::time::Duration;
+
+use ikebuster::utils::gen_transforms::weak_components;
+use ikebuster::utils::progress::watch_progress;
+use ikebuster::utils::progress::ScanProgress;
+use ikebuster::ScanError;
+use ikebuster::ScanOptions;
+use ikebuster::ScanResult;
+use ratatui::crossterm::event;
+use ratatui::crossterm::event::Event;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::crossterm::event::KeyModifiers;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::style::Color;
+use ratatui::style::Style;
+use ratatui::widgets::Block;
+use ratatui::widgets::Gauge;
+use ratatui::widgets::List;
+use ratatui::widgets::ListItem;
+use ratatui::Frame;
+
+/// Run the scan while rendering its progress
+///
+/// The scan can be aborted by pressing `q` or `Ctrl+C`
+pub async fn run(mut opts: ScanOptions) -> Result<ScanResult, ScanError> {
+    let target = opts.target();
+
+    // Events are passed on to a previously set receiver (e.g. `--append-jsonl`)
+    let progress = watch_progress(&mut opts);
+    let scan = tokio::spawn(ikebuster::scan(opts));
+
+    let mut terminal = ratatui::init();
+
+    while !scan.is_finished() {
+        // Without a working terminal, the scan continues without the live view
+        let progress = progress.borrow().clone();
+        if terminal
+            .draw(|frame| draw(frame, target, &progress))
+            .is_err()
+        {
+            break;
+        }
+
+        if let Ok(true) = event::poll(Duration::from_millis(100)) {
+            if let Ok(Event::Key(key)) = event::read() {
+                let ctrl_c =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if key.code == KeyCode::Char('q') || ctrl_c {
+                    ratatui::restore();
+                    scan.abort();
+                    exit(130);
+                }
+            }
+        }
+    }
+
+    ratatui::restore();
+
+    match scan.await {
+        Ok(res) => res,
+        Err(err) => panic::resume_unwind(err.into_panic()),
+    }
+}
+
+/// Render the progress of the scan
+fn draw(frame: &mut Frame, target: SocketAddr, progress: &ScanProgress) {
+    let [gauge_area, accepted_area, quirks_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(3),
+        Constraint::Length(progress.quirks.len() as u16 + 2),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::bordered().title(format!(" Scanning {target} (q to quit) ")))
+            .gauge_style(Style::default().fg(Color::Blue))
+            .ratio(progress.ratio())
+            .label(format!(
+                "{} proposals sent, {} remaining, {} rejected",
+                progress.sent, progress.remaining, progress.rejected
+            )),
+        gauge_area,
+    );
+
+    // Show the latest findings first
+    let accepted = progress.accepted.iter().rev().map(|transform| {
+        let color = match weak_components(transform) {
+            0 => Color::Green,
+            1 => Color::Yellow,
+            _ => Color::Red,
+        };
+        ListItem::new(transform.to_string()).style(Style::default().fg(color))
+    });
+    frame.render_widget(
+        List::new(accepted).block(Block::bordered().title(format!(
+            " Accepted transforms ({}) ",
+            progress.accepted.len()
+        ))),
+        accepted_area,
+    );
+
+    let quirks = progress
+        .quirks
+        .iter()
+        .map(|quirk| ListItem::new(quirk.to_string()));
+    frame.render_widget(
+        List::new(quirks).block(Block::bordered().title(" Detected quirks ")),
+        quirks_area,
+    );
+}
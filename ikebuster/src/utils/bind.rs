@@ -0,0 +1,45 @@
+//! Retrying to bind while the address is briefly held by another process
+
+use std::future::Future;
+use std::io;
+use std::time::Duration;
+
+use tokio::time::sleep;
+use tracing::warn;
+
+/// The delay before the first retry, which is doubled for every further retry
+pub const BIND_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Check whether binding failed due to a condition that may resolve itself
+///
+/// This is the case if the address is in use (e.g. by a restarting IPsec daemon)
+/// or not yet available (e.g. an interface that is being brought up).
+pub fn is_retryable(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::AddrInUse | io::ErrorKind::AddrNotAvailable
+    )
+}
+
+/// Call `bind` until it succeeds, retrying at most `retries` times with an exponential backoff
+///
+/// Errors which are not [retryable](is_retryable) are returned immediately.
+pub async fn bind_with_retries<T, F, Fut>(mut bind: F, retries: u32) -> io::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = io::Result<T>>,
+{
+    let mut delay = BIND_RETRY_DELAY;
+    let mut attempt = 0;
+    loop {
+        match bind().await {
+            Err(err) if attempt < retries && is_retryable(&err) => {
+                attempt += 1;
+                warn!("Could not bind ({err}), retrying in {delay:?} ({attempt}/{retries})");
+                sleep(delay).await;
+                delay *= 2;
+            }
+            res => return res,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Removal of duplicate transforms
+
+use std::collections::BTreeSet;
+
+use isakmp::v1::generator::Transform;
+use tracing::warn;
+
+use crate::utils::gen_transforms::has_variable_key_size;
+
+/// Remove duplicate transforms while keeping the order of the remaining ones
+///
+/// Transforms that only differ in a key size the server ignores, as the encryption algorithm
+/// has a fixed key size, are logged, but kept.
+///
+/// Returns the number of removed transforms
+pub fn dedup_transforms(transforms: &mut Vec<Transform>) -> usize {
+    let len = transforms.len();
+
+    let mut seen = BTreeSet::new();
+    transforms.retain(|transform| seen.insert(transform.clone()));
+
+    let removed = len - transforms.len();
+    if removed > 0 {
+        warn!("Dropped {removed} duplicate transforms");
+    }
+
+    let mut ignoring_key_size = BTreeSet::new();
+    for transform in transforms.iter() {
+        if transform.key_size.is_none() || has_variable_key_size(transform.encryption_algorithm) {
+            continue;
+        }
+
+        let without_key_size = Transform {
+            key_size: None,
+            ..transform.clone()
+        };
+        if seen.contains(&without_key_size) || !ignoring_key_size.insert(without_key_size) {
+            warn!(
+                "Transform only differs in the key size, which is ignored for {}: {}",
+                transform.encryption_algorithm, transform
+            );
+        }
+    }
+
+    removed
+}
@@ -1,5 +1,8 @@
 //! Formatting helpers
 
+use std::fmt::Write;
+use std::net::SocketAddr;
+
 use isakmp::v1::definitions::AttributeType;
 use isakmp::v1::definitions::AuthenticationMethod;
 use isakmp::v1::definitions::EncryptionAlgorithm;
@@ -7,8 +10,54 @@ use isakmp::v1::definitions::GroupDescription;
 use isakmp::v1::definitions::GroupType;
 use isakmp::v1::definitions::HashAlgorithm;
 use isakmp::v1::definitions::LifeType;
+use isakmp::v1::generator::Transform;
 use isakmp::v1::parser::definitions::DataAttribute;
 
+use crate::utils::payload_to_transforms::RawTransform;
+
+/// Encode the bytes as lowercase hex without separators
+pub fn format_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+/// Minimum size in bits of the prime of a custom group to not be considered weak
+pub const MIN_GROUP_PRIME_BITS: usize = 2048;
+
+/// Compute the size in bits of a big-endian encoded prime
+pub fn prime_bit_length(prime: &[u8]) -> usize {
+    let Some(start) = prime.iter().position(|x| *x != 0) else {
+        return 0;
+    };
+
+    (prime.len() - start) * 8 - prime[start].leading_zeros() as usize
+}
+
+/// The size in bits of the prime of a custom group, if the attribute advertises one
+pub fn group_prime_bits(attribute: &DataAttribute) -> Option<usize> {
+    match attribute {
+        DataAttribute::DataAttributeShort(attr)
+            if attr.attribute_type == AttributeType::GroupPrime =>
+        {
+            Some(prime_bit_length(&attr.attribute_value.to_be_bytes()))
+        }
+        DataAttribute::DataAttributeLong(attr)
+            if attr.attribute_type == AttributeType::GroupPrime =>
+        {
+            Some(prime_bit_length(&attr.attribute_value))
+        }
+        _ => None,
+    }
+}
+
+/// Check whether the attribute advertises the prime of a custom group
+/// which is smaller than [MIN_GROUP_PRIME_BITS]
+pub fn is_weak_group_prime(attribute: &DataAttribute) -> bool {
+    group_prime_bits(attribute).is_some_and(|bits| bits < MIN_GROUP_PRIME_BITS)
+}
+
 fn format_attribute_value_short(attribute_type: &AttributeType, attribute_value: u16) -> String {
     match attribute_type {
         AttributeType::Reserved => attribute_value.to_string(),
@@ -38,8 +87,92 @@ pub fn format_attribute(attribute: &DataAttribute) -> String {
                 format_attribute_value_short(&attr.attribute_type, attr.attribute_value)
             )
         }
+        DataAttribute::DataAttributeLong(attr)
+            if attr.attribute_type == AttributeType::GroupPrime =>
+        {
+            format!(
+                "{}={} bit{}",
+                attr.attribute_type,
+                prime_bit_length(&attr.attribute_value),
+                if is_weak_group_prime(attribute) {
+                    " (weak)"
+                } else {
+                    ""
+                }
+            )
+        }
         DataAttribute::DataAttributeLong(attr) => {
             format!("{}={:?}", attr.attribute_type, attr.attribute_value)
         }
     }
 }
+
+/// The header of the rows of [format_csv_row]
+pub const CSV_HEADER: &str = "target,enc,key_size,hash,auth,group";
+
+/// Format a transform accepted by `target` as CSV row, see [CSV_HEADER]
+///
+/// The key size is empty if it isn't set. None of the values contain characters that
+/// have to be quoted.
+pub fn format_csv_row(target: SocketAddr, transform: &Transform) -> String {
+    format!(
+        "{target},{},{},{},{},{}",
+        transform.encryption_algorithm,
+        transform
+            .key_size
+            .map(|key_size| key_size.to_string())
+            .unwrap_or_default(),
+        transform.hash_algorithm,
+        transform.authentication_method,
+        transform.group_description,
+    )
+}
+
+/// Format all transforms accepted by `target` in a single line, separated by `;`
+///
+/// Example: `host=192.0.2.1 port=500 ENC=AES_CBC/256 HASH=SHA2_256 AUTH=PreSharedKey GROUP=MODP_2048`
+pub fn format_grepable(target: SocketAddr, transforms: &[Transform]) -> String {
+    let mut line = format!("host={} port={}", target.ip(), target.port());
+    for (i, transform) in transforms.iter().enumerate() {
+        line.push_str(if i == 0 { " " } else { "; " });
+        line.push_str(&transform.to_string());
+    }
+    line
+}
+
+/// Format the raw values of a transform like the [Display](std::fmt::Display)
+/// implementation of [Transform]
+///
+/// Unknown values are shown as hex, e.g. `ENC=0xfff0 (unknown)`
+pub fn format_raw_transform(transform: &RawTransform) -> String {
+    let encryption_algorithm = format_raw_value(transform.encryption_algorithm, |x| {
+        EncryptionAlgorithm::try_from(x).ok().map(|x| x.to_string())
+    });
+    let encryption_algorithm = match transform.key_size {
+        Some(key_size) => format!("{encryption_algorithm}/{key_size}"),
+        None => encryption_algorithm,
+    };
+
+    format!(
+        "ENC={encryption_algorithm} HASH={} AUTH={} GROUP={}",
+        format_raw_value(transform.hash_algorithm, |x| {
+            HashAlgorithm::try_from(x).ok().map(|x| x.to_string())
+        }),
+        format_raw_value(transform.authentication_method, |x| {
+            AuthenticationMethod::try_from(x)
+                .ok()
+                .map(|x| x.to_string())
+        }),
+        format_raw_value(transform.group_description, |x| {
+            GroupDescription::try_from(x).ok().map(|x| x.to_string())
+        }),
+    )
+}
+
+/// Format a raw value with the name of its variant, if it is known
+fn format_raw_value(value: Option<u16>, name: impl Fn(u16) -> Option<String>) -> String {
+    match value {
+        None => "missing".to_string(),
+        Some(value) => name(value).unwrap_or_else(|| format!("{value:#06x} (unknown)")),
+    }
+}
@@ -1,6 +1,7 @@
 //! Generate transforms for different types of servers
 
 use std::collections::VecDeque;
+use std::ops::RangeInclusive;
 
 use isakmp::strum::IntoEnumIterator;
 use isakmp::v1::definitions::AuthenticationMethod;
@@ -9,13 +10,144 @@ use isakmp::v1::definitions::GroupDescription;
 use isakmp::v1::definitions::HashAlgorithm;
 use isakmp::v1::generator::Transform;
 use itertools::iproduct;
+use thiserror::Error;
 
-/// Generate all possible transforms for IKE v1
+use crate::utils::grading::grade_components;
+use crate::utils::grading::Grade;
+
+/// The key sizes tested for encryption algorithms with a variable key size
+///
+/// See [TransformFilter::key_sizes]
+pub const DEFAULT_KEY_SIZES: [u16; 3] = [128, 192, 256];
+
+/// The variants of an algorithm to include in the generated transforms
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection<T> {
+    /// Only include these variants, or all variants if this is empty
+    pub allow: Vec<T>,
+    /// Exclude these variants, even if they are allowed
+    pub deny: Vec<T>,
+}
+
+impl<T> Default for Selection<T> {
+    fn default() -> Self {
+        Self {
+            allow: vec![],
+            deny: vec![],
+        }
+    }
+}
+
+impl<T: PartialEq> Selection<T> {
+    /// Check whether the variant is included
+    pub fn includes(&self, variant: &T) -> bool {
+        (self.allow.is_empty() || self.allow.contains(variant)) && !self.deny.contains(variant)
+    }
+
+    fn select(&mut self, variant: T, allow: bool) {
+        if allow {
+            self.allow.push(variant);
+        } else {
+            self.deny.push(variant);
+        }
+    }
+}
+
+/// Which transforms to generate, see [gen_v1_transform_list]
+///
+/// The default includes all transforms
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransformFilter {
+    /// The encryption algorithms to include
+    pub encryption_algorithms: Selection<EncryptionAlgorithm>,
+    /// The hash algorithms to include
+    pub hash_algorithms: Selection<HashAlgorithm>,
+    /// The authentication methods to include
+    pub authentication_methods: Selection<AuthenticationMethod>,
+    /// The groups to include
+    pub groups: Selection<GroupDescription>,
+    /// Only include transforms with at least one weak component, see [weak_components]
+    ///
+    /// This allows to quickly check whether the server accepts anything weak
+    pub only_weak: bool,
+    /// The key sizes tested for encryption algorithms with a variable key size,
+    /// [DEFAULT_KEY_SIZES] if unset
+    ///
+    /// Key sizes outside the range of an algorithm (see [key_size_range]) are skipped for it.
+    pub key_sizes: Option<Vec<u16>>,
+}
+
+impl TransformFilter {
+    /// Allow or deny the variant with the given name
+    ///
+    /// The name is matched case-insensitively against the variants of all algorithm types,
+    /// e.g. `DES_CBC`, `MD5`, `PreSharedKey` or `MODP_768`.
+    pub fn select(&mut self, name: &str, allow: bool) -> Result<(), UnknownAlgorithm> {
+        let matches = |variant: &dyn ToString| variant.to_string().eq_ignore_ascii_case(name);
+
+        if let Some(x) = EncryptionAlgorithm::iter().find(|x| matches(x)) {
+            self.encryption_algorithms.select(x, allow);
+        } else if let Some(x) = HashAlgorithm::iter().find(|x| matches(x)) {
+            self.hash_algorithms.select(x, allow);
+        } else if let Some(x) = AuthenticationMethod::iter().find(|x| matches(x)) {
+            self.authentication_methods.select(x, allow);
+        } else if let Some(x) = GroupDescription::iter().find(|x| matches(x)) {
+            self.groups.select(x, allow);
+        } else {
+            return Err(UnknownAlgorithm(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// The key sizes to test for the encryption algorithm
+    ///
+    /// This is empty for algorithms with a fixed key size
+    pub fn key_sizes_of(&self, encryption_algorithm: EncryptionAlgorithm) -> Vec<u16> {
+        let Some(range) = key_size_range(encryption_algorithm) else {
+            return vec![];
+        };
+        self.key_sizes
+            .as_deref()
+            .unwrap_or(&DEFAULT_KEY_SIZES)
+            .iter()
+            .copied()
+            .filter(|key_size| range.contains(key_size))
+            .collect()
+    }
+
+    /// Check whether the transform is included
+    pub fn includes(&self, transform: &Transform) -> bool {
+        self.encryption_algorithms
+            .includes(&transform.encryption_algorithm)
+            && self.hash_algorithms.includes(&transform.hash_algorithm)
+            && self
+                .authentication_methods
+                .includes(&transform.authentication_method)
+            && self.groups.includes(&transform.group_description)
+            && (!self.only_weak || weak_components(transform) > 0)
+    }
+}
+
+/// The name passed to [TransformFilter::select] doesn't match any algorithm
+#[derive(Debug, Error)]
+#[error("Unknown algorithm: {0}")]
+pub struct UnknownAlgorithm(pub String);
+
+/// Generate the transforms for IKE v1 and split them into proposals
 ///
 /// # Parameters
+/// - `filter`: The transforms to include
 /// - `transform_no`: Max number of transform to put into a single message
-pub fn gen_v1_transforms(transform_no: usize) -> VecDeque<Vec<Transform>> {
-    let transforms = iproduct!(
+pub fn gen_v1_transforms(
+    filter: &TransformFilter,
+    transform_no: usize,
+) -> VecDeque<Vec<Transform>> {
+    chunk_transforms(gen_v1_transform_list(filter), transform_no)
+}
+
+/// Generate all possible transforms for IKE v1 that are included by the `filter`
+pub fn gen_v1_transform_list(filter: &TransformFilter) -> Vec<Transform> {
+    iproduct!(
         EncryptionAlgorithm::iter().filter(|x| *x as u16 != 0),
         HashAlgorithm::iter().filter(|x| *x as u16 != 0),
         AuthenticationMethod::iter().filter(|x| *x as u16 != 0),
@@ -27,22 +159,68 @@ pub fn gen_v1_transforms(transform_no: usize) -> VecDeque<Vec<Transform>> {
         authentication_method: a,
         group_description: g,
         key_size: None,
+        extra_attributes: vec![],
     })
+    .filter(|transform| filter.includes(transform))
     .fold(Vec::new(), |mut acc, transform| {
-        if transform.encryption_algorithm == EncryptionAlgorithm::AES_CBC {
-            let [mut a, mut b, mut c] = [transform.clone(), transform.clone(), transform];
-            a.key_size = Some(128);
-            b.key_size = Some(192);
-            c.key_size = Some(256);
-
-            acc.extend([a, b, c]);
+        if has_variable_key_size(transform.encryption_algorithm) {
+            let key_sizes = filter.key_sizes_of(transform.encryption_algorithm);
+            acc.extend(key_sizes.into_iter().map(|key_size| Transform {
+                key_size: Some(key_size),
+                ..transform.clone()
+            }));
         } else {
             acc.push(transform);
         }
 
         acc
-    });
+    })
+}
+
+/// The valid key sizes in bits of an encryption algorithm, if its key size is negotiated
+///
+/// Taken from https://datatracker.ietf.org/doc/html/rfc2409#appendix-A and
+/// https://datatracker.ietf.org/doc/html/rfc4132
+pub fn key_size_range(encryption_algorithm: EncryptionAlgorithm) -> Option<RangeInclusive<u16>> {
+    match encryption_algorithm {
+        EncryptionAlgorithm::AES_CBC | EncryptionAlgorithm::CAMELLIA_CBC => Some(128..=256),
+        EncryptionAlgorithm::BlowfishCBC => Some(40..=448),
+        EncryptionAlgorithm::RC5_R16_B64_CBC => Some(40..=2040),
+        EncryptionAlgorithm::CAST_CBC => Some(40..=128),
+        EncryptionAlgorithm::Reserved
+        | EncryptionAlgorithm::DES_CBC
+        | EncryptionAlgorithm::IDEA_CBC
+        | EncryptionAlgorithm::TrippleDES_CBC => None,
+    }
+}
 
+/// Check whether the key size of the encryption algorithm is negotiated
+pub fn has_variable_key_size(encryption_algorithm: EncryptionAlgorithm) -> bool {
+    key_size_range(encryption_algorithm).is_some()
+}
+
+/// Reduce the transforms to at most `max_transforms`
+///
+/// The transforms with the most weak components are kept first, so a sampling scan
+/// still covers the interesting combinations.
+///
+/// Returns whether transforms were removed
+pub fn truncate_transforms(transforms: &mut Vec<Transform>, max_transforms: usize) -> bool {
+    if transforms.len() <= max_transforms {
+        return false;
+    }
+
+    transforms.sort_by_key(|transform| std::cmp::Reverse(weak_components(transform)));
+    transforms.truncate(max_transforms);
+
+    true
+}
+
+/// Split the transforms into proposals of at most `transform_no` transforms
+pub fn chunk_transforms(
+    transforms: Vec<Transform>,
+    transform_no: usize,
+) -> VecDeque<Vec<Transform>> {
     let mut t = VecDeque::new();
 
     for chunk in transforms.chunks(transform_no) {
@@ -51,3 +229,38 @@ pub fn gen_v1_transforms(transform_no: usize) -> VecDeque<Vec<Transform>> {
 
     t
 }
+
+/// Split proposals until their message doesn't exceed `max_message_size` bytes
+///
+/// Proposals with a single transform are kept, even if they are too large.
+///
+/// # Parameters
+/// - `message_size`: Computes the size of the message for the given transforms
+pub fn split_oversized(
+    proposals: VecDeque<Vec<Transform>>,
+    max_message_size: usize,
+    message_size: impl Fn(&[Transform]) -> usize,
+) -> VecDeque<Vec<Transform>> {
+    let mut t = VecDeque::new();
+
+    let mut remaining = proposals;
+    while let Some(mut transforms) = remaining.pop_front() {
+        if transforms.len() > 1 && message_size(&transforms) > max_message_size {
+            let b = transforms.split_off(transforms.len() / 2);
+            remaining.push_front(b);
+            remaining.push_front(transforms);
+        } else {
+            t.push_back(transforms);
+        }
+    }
+
+    t
+}
+
+/// Count the components of a transform that are graded [Grade::Weak], see [grade_components]
+pub fn weak_components(transform: &Transform) -> usize {
+    grade_components(transform)
+        .into_iter()
+        .filter(|grade| *grade == Grade::Weak)
+        .count()
+}
@@ -0,0 +1,104 @@
+//! Grading of the accepted transforms
+//!
+//! Each transform is graded by its weakest algorithm:
+//!
+//! - [Grade::Weak]: DES, 3DES, IDEA, Blowfish, RC5 or CAST encryption, MD5, SHA-1 or Tiger
+//!   hashes, or a group providing less than
+//!   [MIN_GROUP_SECURITY_BITS](isakmp::v1::definitions::MIN_GROUP_SECURITY_BITS) bits of security
+//!   (e.g. MODP 768 and 1024, DH group 1 and 2)
+//! - [Grade::Strong]: AES or Camellia encryption, a SHA-2 hash and a group providing at least
+//!   128 bits of security (e.g. MODP 3072, ECP 256)
+//! - [Grade::Acceptable]: Everything else, e.g. MODP 2048
+//!
+//! Custom groups are graded by the size of their prime, see [grade_custom_group]. They are
+//! weak below [MIN_GROUP_PRIME_BITS] and strong from [STRONG_GROUP_PRIME_BITS] bits on.
+//!
+//! The authentication method is not graded, accepting pre-shared keys is reported by
+//! [ScanResult::psk_accepted](crate::ScanResult::psk_accepted).
+
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::utils::formatting::MIN_GROUP_PRIME_BITS;
+
+/// Groups providing at least this many bits of security are considered strong
+pub const STRONG_GROUP_SECURITY_BITS: u16 = 128;
+
+/// Custom groups with a prime of at least this many bits are considered strong,
+/// as MODP groups of this size provide 128 bits of security
+pub const STRONG_GROUP_PRIME_BITS: usize = 3072;
+
+/// The verdict on a transform, ordered from the weakest to the strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Grade {
+    /// At least one algorithm is broken or deprecated
+    Weak,
+    /// The algorithms are fine, but not all of them provide 128 bits of security
+    Acceptable,
+    /// All algorithms provide at least 128 bits of security
+    Strong,
+}
+
+/// Grade the encryption algorithm, hash algorithm and group of a transform each,
+/// see the [module](self) for the rules
+pub fn grade_components(transform: &Transform) -> [Grade; 3] {
+    let encryption = match transform.encryption_algorithm {
+        EncryptionAlgorithm::AES_CBC | EncryptionAlgorithm::CAMELLIA_CBC => Grade::Strong,
+        EncryptionAlgorithm::Reserved
+        | EncryptionAlgorithm::DES_CBC
+        | EncryptionAlgorithm::IDEA_CBC
+        | EncryptionAlgorithm::BlowfishCBC
+        | EncryptionAlgorithm::RC5_R16_B64_CBC
+        | EncryptionAlgorithm::TrippleDES_CBC
+        | EncryptionAlgorithm::CAST_CBC => Grade::Weak,
+    };
+    let hash = match transform.hash_algorithm {
+        HashAlgorithm::SHA2_256 | HashAlgorithm::SHA2_384 | HashAlgorithm::SHA2_512 => {
+            Grade::Strong
+        }
+        HashAlgorithm::Reserved
+        | HashAlgorithm::MD5
+        | HashAlgorithm::SHA
+        | HashAlgorithm::Tiger => Grade::Weak,
+    };
+    let group = if transform.group_description.is_weak() {
+        Grade::Weak
+    } else if transform
+        .group_description
+        .approx_security_bits()
+        .is_some_and(|bits| bits >= STRONG_GROUP_SECURITY_BITS)
+    {
+        Grade::Strong
+    } else {
+        Grade::Acceptable
+    };
+
+    [encryption, hash, group]
+}
+
+/// Grade a transform by its weakest algorithm, see the [module](self) for the rules
+pub fn grade_transform(transform: &Transform) -> Grade {
+    let [encryption, hash, group] = grade_components(transform);
+    encryption.min(hash).min(group)
+}
+
+/// Grade a custom group by the size of its prime in bits
+pub fn grade_custom_group(prime_bits: usize) -> Grade {
+    if prime_bits < MIN_GROUP_PRIME_BITS {
+        Grade::Weak
+    } else if prime_bits < STRONG_GROUP_PRIME_BITS {
+        Grade::Acceptable
+    } else {
+        Grade::Strong
+    }
+}
+
+/// Grade the accepted transforms by the weakest one
+///
+/// Returns `None` if no transform was accepted
+pub fn grade_transforms(transforms: &[Transform]) -> Option<Grade> {
+    transforms.iter().map(grade_transform).min()
+}
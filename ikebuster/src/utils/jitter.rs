@@ -0,0 +1,19 @@
+//! Randomizing the interval between sent messages
+//!
+//! A fixed interval is easily recognized by intrusion prevention systems and rate limiters,
+//! see [crate::ScanOptions::jitter].
+
+use std::time::Duration;
+
+/// Pick a random delay in `[interval - jitter, interval + jitter]`
+///
+/// The delay doesn't go below zero, so a jitter exceeding the interval favors longer delays.
+pub fn jittered_interval(interval: Duration, jitter: Duration) -> Duration {
+    if jitter.is_zero() {
+        return interval;
+    }
+    let min = interval.saturating_sub(jitter);
+    let max = interval.saturating_add(jitter);
+    let nanos = rand::random_range(min.as_nanos()..=max.as_nanos());
+    Duration::from_nanos(nanos as u64)
+}
@@ -0,0 +1,68 @@
+//! Incremental output of the accepted transforms as JSON lines
+//!
+//! Every accepted transform is written as soon as it is reported by a [ScanEvent],
+//! so the findings are kept even if the scan is aborted.
+
+use std::collections::BTreeSet;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::net::SocketAddr;
+
+use isakmp::v1::generator::Transform;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::ScanEvent;
+
+/// A single line of the output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AcceptedLine {
+    /// The scanned target
+    pub target: SocketAddr,
+    /// The accepted transform
+    pub transform: Transform,
+}
+
+/// Writer of the accepted transforms of [ScanEvent]s as JSON lines
+#[derive(Debug)]
+pub struct JsonlWriter<W: Write> {
+    writer: BufWriter<W>,
+    target: SocketAddr,
+    written: BTreeSet<Transform>,
+}
+
+impl<W: Write> JsonlWriter<W> {
+    /// Create a writer for the events of a scan of `target`
+    pub fn new(writer: W, target: SocketAddr) -> Self {
+        Self {
+            writer: BufWriter::new(writer),
+            target,
+            written: BTreeSet::new(),
+        }
+    }
+
+    /// Write the transform of a [ScanEvent::TransformAccepted] and flush it
+    ///
+    /// Other events and transforms that were already written are ignored.
+    pub fn handle(&mut self, event: &ScanEvent) -> io::Result<()> {
+        let ScanEvent::TransformAccepted(transform) = event else {
+            return Ok(());
+        };
+        if self.written.contains(transform) {
+            return Ok(());
+        }
+
+        serde_json::to_writer(
+            &mut self.writer,
+            &AcceptedLine {
+                target: self.target,
+                transform: transform.clone(),
+            },
+        )?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        self.written.insert(transform.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+//! Matrix of accepted and rejected combinations of two transform attributes
+
+use std::collections::BTreeMap;
+
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::generator::Transform;
+
+/// The state of a combination of two attribute values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixCell {
+    /// At least one transform with this combination was accepted
+    Accepted,
+    /// Transforms with this combination were rejected and none was accepted
+    Rejected,
+    /// No transform with this combination was answered
+    Indeterminate,
+}
+
+/// A table of two transform attributes showing which combinations were accepted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    /// The values of the attribute on the x axis
+    pub columns: Vec<String>,
+    /// The values of the attribute on the y axis
+    pub rows: Vec<String>,
+    /// The cells of the matrix, indexed by row and column
+    pub cells: Vec<Vec<MatrixCell>>,
+}
+
+/// Retrieve the value of an attribute of a transform
+///
+/// Returns a key to sort the values by and the formatted value, or `None` if the attribute type
+/// is not part of a [Transform]
+pub fn attribute_value(
+    transform: &Transform,
+    attribute_type: AttributeType,
+) -> Option<(u32, String)> {
+    Some(match attribute_type {
+        AttributeType::EncryptionAlgorithm => match transform.key_size {
+            Some(key_size) => (
+                (transform.encryption_algorithm as u32) << 16 | key_size as u32,
+                format!("{}/{key_size}", transform.encryption_algorithm),
+            ),
+            None => (
+                (transform.encryption_algorithm as u32) << 16,
+                transform.encryption_algorithm.to_string(),
+            ),
+        },
+        AttributeType::HashAlgorithm => (
+            transform.hash_algorithm as u32,
+            transform.hash_algorithm.to_string(),
+        ),
+        AttributeType::AuthenticationMethod => (
+            transform.authentication_method as u32,
+            transform.authentication_method.to_string(),
+        ),
+        AttributeType::GroupDescription => (
+            transform.group_description as u32,
+            transform.group_description.to_string(),
+        ),
+        AttributeType::KeyLength => match transform.key_size {
+            Some(key_size) => (key_size as u32, key_size.to_string()),
+            None => (0, "-".to_string()),
+        },
+        _ => return None,
+    })
+}
+
+/// Build a matrix of the attributes `x` and `y`
+///
+/// Returns `None` if one of the attribute types is not part of a [Transform]
+pub fn build_matrix(
+    accepted: &[Transform],
+    rejected: &[Transform],
+    x: AttributeType,
+    y: AttributeType,
+) -> Option<Matrix> {
+    let is_axis = |attribute_type| {
+        matches!(
+            attribute_type,
+            AttributeType::EncryptionAlgorithm
+                | AttributeType::HashAlgorithm
+                | AttributeType::AuthenticationMethod
+                | AttributeType::GroupDescription
+                | AttributeType::KeyLength
+        )
+    };
+    if !is_axis(x) || !is_axis(y) {
+        return None;
+    }
+
+    let mut columns = BTreeMap::new();
+    let mut rows = BTreeMap::new();
+    // Number of accepted and rejected transforms by row and column label
+    let mut counts: BTreeMap<(String, String), (usize, usize)> = BTreeMap::new();
+
+    for (transform, is_accepted) in accepted
+        .iter()
+        .map(|x| (x, true))
+        .chain(rejected.iter().map(|x| (x, false)))
+    {
+        let (column_key, column) = attribute_value(transform, x)?;
+        let (row_key, row) = attribute_value(transform, y)?;
+        columns.insert(column_key, column.clone());
+        rows.insert(row_key, row.clone());
+
+        let count = counts.entry((row, column)).or_default();
+        if is_accepted {
+            count.0 += 1;
+        } else {
+            count.1 += 1;
+        }
+    }
+
+    let columns: Vec<String> = columns.into_values().collect();
+    let rows: Vec<String> = rows.into_values().collect();
+    let cells = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| match counts.get(&(row.clone(), column.clone())) {
+                    Some((accepted, _)) if *accepted > 0 => MatrixCell::Accepted,
+                    Some(_) => MatrixCell::Rejected,
+                    None => MatrixCell::Indeterminate,
+                })
+                .collect()
+        })
+        .collect();
+
+    Some(Matrix {
+        columns,
+        rows,
+        cells,
+    })
+}
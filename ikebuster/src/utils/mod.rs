@@ -1,5 +1,16 @@
 //! This module contain helper modules
 
+pub mod bind;
+pub mod dedup;
 pub mod formatting;
 pub mod gen_transforms;
+pub mod grading;
+pub mod jitter;
+pub mod jsonl;
+pub mod matrix;
 pub mod payload_to_transforms;
+pub mod progress;
+pub mod record;
+pub mod socket_errors;
+pub mod targets;
+pub mod vendor_id;
@@ -5,9 +5,15 @@ use isakmp::v1::definitions::AuthenticationMethod;
 use isakmp::v1::definitions::EncryptionAlgorithm;
 use isakmp::v1::definitions::GroupDescription;
 use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::definitions::LifeType;
+use isakmp::v1::generator::AttributeValue;
+use isakmp::v1::generator::Lifetime;
 use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::DEFAULT_ATTRIBUTE_ORDER;
 use isakmp::v1::parser::definitions::DataAttribute;
 use isakmp::v1::parser::definitions::ProposalPayload;
+use serde::Deserialize;
+use serde::Serialize;
 use thiserror::Error;
 
 /// Could not retrieve full transform
@@ -15,10 +21,49 @@ use thiserror::Error;
 #[error("Invalid transform")]
 pub struct InvalidTransform;
 
+/// A transform that was returned by the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcceptedTransform {
+    /// The transform itself
+    pub transform: Transform,
+    /// The number of the transform in the proposal
+    ///
+    /// A responder echoes the number of the transform it chose
+    pub transform_no: u8,
+    /// The lifetime of the security association, if the server sent one
+    ///
+    /// An unknown life type or a duration exceeding 32 bits leave the lifetime unset
+    /// instead of invalidating the transform.
+    pub lifetime: Option<Lifetime>,
+}
+
+/// The raw values of the attributes of a transform that was returned by the server
+///
+/// Unlike an [AcceptedTransform], values that are unknown (e.g. of the private use range)
+/// are kept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RawTransform {
+    /// The number of the transform in the proposal
+    pub transform_no: u8,
+    /// Value of the encryption algorithm
+    pub encryption_algorithm: Option<u16>,
+    /// Value of the hash algorithm
+    pub hash_algorithm: Option<u16>,
+    /// Value of the authentication method
+    pub authentication_method: Option<u16>,
+    /// Value of the group description
+    pub group_description: Option<u16>,
+    /// The key size
+    pub key_size: Option<u16>,
+}
+
 /// Build a vector of transforms from a given [ProposalPayload]
+///
+/// Attributes which aren't covered by the fields of a [Transform] are kept in
+/// [Transform::extra_attributes] in the order they were sent.
 pub fn payload_to_transforms(
     payload: &ProposalPayload,
-) -> Result<Vec<Transform>, InvalidTransform> {
+) -> Result<Vec<AcceptedTransform>, InvalidTransform> {
     let mut v = vec![];
 
     for transform in &payload.transforms {
@@ -27,6 +72,9 @@ pub fn payload_to_transforms(
         let mut authentication_method = None;
         let mut group_description = None;
         let mut key_size = None;
+        let mut life_type = None;
+        let mut life_duration = None;
+        let mut extra_attributes = vec![];
 
         for attr in &transform.sa_attributes {
             match attr {
@@ -56,8 +104,33 @@ pub fn payload_to_transforms(
                         );
                     }
                     AttributeType::KeyLength => key_size = Some(attr.attribute_value),
-                    _ => {}
+                    AttributeType::LifeType => {
+                        life_type = LifeType::try_from(attr.attribute_value).ok();
+                    }
+                    AttributeType::LifeDuration => {
+                        life_duration = Some(u32::from(attr.attribute_value));
+                    }
+                    attribute_type => extra_attributes
+                        .push((attribute_type, AttributeValue::Short(attr.attribute_value))),
                 },
+                // Life durations exceed two bytes regularly, e.g. when measured in kilobytes
+                DataAttribute::DataAttributeLong(attr)
+                    if attr.attribute_type == AttributeType::LifeDuration =>
+                {
+                    life_duration = (attr.attribute_value.len() <= size_of::<u32>()).then(|| {
+                        attr.attribute_value
+                            .iter()
+                            .fold(0, |duration, byte| duration << 8 | u32::from(*byte))
+                    });
+                }
+                DataAttribute::DataAttributeLong(attr)
+                    if !DEFAULT_ATTRIBUTE_ORDER.contains(&attr.attribute_type) =>
+                {
+                    extra_attributes.push((
+                        attr.attribute_type,
+                        AttributeValue::Long(attr.attribute_value.clone()),
+                    ));
+                }
                 DataAttribute::DataAttributeLong(attr) => {
                     let a = attr
                         .attribute_value
@@ -95,20 +168,74 @@ pub fn payload_to_transforms(
                             );
                         }
                         AttributeType::KeyLength => key_size = Some(value),
+                        AttributeType::LifeType => life_type = LifeType::try_from(value).ok(),
                         _ => {}
                     }
                 }
             }
         }
 
-        v.push(Transform {
-            encryption_algorithm: encryption_algorithm.ok_or(InvalidTransform)?,
-            hash_algorithm: hash_algorithm.ok_or(InvalidTransform)?,
-            authentication_method: authentication_method.ok_or(InvalidTransform)?,
-            group_description: group_description.ok_or(InvalidTransform)?,
-            key_size,
+        v.push(AcceptedTransform {
+            transform: Transform {
+                encryption_algorithm: encryption_algorithm.ok_or(InvalidTransform)?,
+                hash_algorithm: hash_algorithm.ok_or(InvalidTransform)?,
+                authentication_method: authentication_method.ok_or(InvalidTransform)?,
+                group_description: group_description.ok_or(InvalidTransform)?,
+                key_size,
+                extra_attributes,
+            },
+            transform_no: transform.transform_no,
+            lifetime: life_type
+                .zip(life_duration)
+                .map(|(life_type, duration)| Lifetime {
+                    life_type,
+                    duration,
+                }),
         });
     }
 
     Ok(v)
 }
+
+/// Retrieve the raw values of the transforms of a given [ProposalPayload]
+///
+/// This never fails, attributes that are missing or don't fit into two bytes are `None`
+pub fn payload_to_raw_transforms(payload: &ProposalPayload) -> Vec<RawTransform> {
+    payload
+        .transforms
+        .iter()
+        .map(|transform| {
+            let mut raw = RawTransform {
+                transform_no: transform.transform_no,
+                encryption_algorithm: None,
+                hash_algorithm: None,
+                authentication_method: None,
+                group_description: None,
+                key_size: None,
+            };
+
+            for attr in &transform.sa_attributes {
+                let (attribute_type, value) = match attr {
+                    DataAttribute::DataAttributeShort(attr) => {
+                        (attr.attribute_type, attr.attribute_value)
+                    }
+                    DataAttribute::DataAttributeLong(attr) => match *attr.attribute_value {
+                        [a] => (attr.attribute_type, u16::from(a)),
+                        [a, b] => (attr.attribute_type, u16::from_be_bytes([a, b])),
+                        _ => continue,
+                    },
+                };
+                match attribute_type {
+                    AttributeType::EncryptionAlgorithm => raw.encryption_algorithm = Some(value),
+                    AttributeType::HashAlgorithm => raw.hash_algorithm = Some(value),
+                    AttributeType::AuthenticationMethod => raw.authentication_method = Some(value),
+                    AttributeType::GroupDescription => raw.group_description = Some(value),
+                    AttributeType::KeyLength => raw.key_size = Some(value),
+                    _ => {}
+                }
+            }
+
+            raw
+        })
+        .collect()
+}
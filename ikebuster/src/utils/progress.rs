@@ -0,0 +1,78 @@
+//! Aggregation of [ScanEvent]s into the current state of a scan
+
+use isakmp::v1::generator::Transform;
+use tokio::sync::mpsc;
+use tokio::sync::watch;
+
+use crate::ScanEvent;
+use crate::ScanOptions;
+use crate::ServerQuirk;
+
+/// The state of a running scan, built from its [ScanEvent]s
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanProgress {
+    /// Number of sent proposals
+    pub sent: usize,
+    /// Number of proposals that are still to be sent
+    pub remaining: usize,
+    /// Number of rejected proposals
+    pub rejected: usize,
+    /// The accepted transforms in the order they were reported
+    pub accepted: Vec<Transform>,
+    /// The detected quirks
+    pub quirks: Vec<ServerQuirk>,
+}
+
+impl ScanProgress {
+    /// Update the state with an event
+    pub fn apply(&mut self, event: ScanEvent) {
+        match event {
+            ScanEvent::ProposalSent { remaining, .. } => {
+                self.sent += 1;
+                self.remaining = remaining;
+            }
+            ScanEvent::TransformAccepted(transform) => {
+                if !self.accepted.contains(&transform) {
+                    self.accepted.push(transform);
+                }
+            }
+            ScanEvent::ProposalRejected(_) => self.rejected += 1,
+            ScanEvent::QuirkDetected(quirk) => self.quirks.push(quirk),
+        }
+    }
+
+    /// The ratio of sent proposals to all known proposals, between 0 and 1
+    ///
+    /// As proposals are split when transforms are accepted, the ratio may decrease
+    pub fn ratio(&self) -> f64 {
+        match self.sent + self.remaining {
+            0 => 0.0,
+            total => self.sent as f64 / total as f64,
+        }
+    }
+}
+
+/// Track the progress of a scan with the options
+///
+/// The events of the scan are aggregated by a spawned task, the receiver always holds the
+/// current [ScanProgress], e.g. to render a progress bar. Events are passed on to a
+/// previously set [ScanOptions::progress]. The receiver is closed once the scan finished
+/// and all of its events are applied.
+///
+/// This has to be called within a tokio runtime.
+pub fn watch_progress(opts: &mut ScanOptions) -> watch::Receiver<ScanProgress> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let forward = opts.progress.replace(tx);
+    let (progress_tx, progress_rx) = watch::channel(ScanProgress::default());
+
+    tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            if let Some(forward) = &forward {
+                let _res = forward.send(event.clone());
+            }
+            progress_tx.send_modify(|progress| progress.apply(event));
+        }
+    });
+
+    progress_rx
+}
@@ -0,0 +1,93 @@
+//! Recording of sent messages to replay them later
+//!
+//! A record consists of the raw messages, each prefixed with its length as big-endian `u32`.
+
+use std::io;
+use std::io::Read;
+use std::io::Write;
+
+use isakmp::v1::generator::Transform;
+use isakmp::v1::parser::errors::IsakmpParseError;
+use isakmp::v1::parser::parse_packet;
+use thiserror::Error;
+
+use crate::utils::payload_to_transforms::payload_to_transforms;
+use crate::utils::payload_to_transforms::InvalidTransform;
+
+/// Write the messages as record
+pub fn write_messages(mut writer: impl Write, messages: &[Vec<u8>]) -> io::Result<()> {
+    for msg in messages {
+        let len = u32::try_from(msg.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Message is too large"))?;
+        writer.write_all(&len.to_be_bytes())?;
+        writer.write_all(msg)?;
+    }
+    writer.flush()
+}
+
+/// Read all messages of a record
+pub fn read_messages(mut reader: impl Read) -> io::Result<Vec<Vec<u8>>> {
+    let mut data = vec![];
+    reader.read_to_end(&mut data)?;
+
+    let mut messages = vec![];
+    let mut remaining = data.as_slice();
+    while !remaining.is_empty() {
+        let Some((len, rest)) = remaining.split_first_chunk::<4>() else {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        };
+        let len = u32::from_be_bytes(*len) as usize;
+        if rest.len() < len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        let (msg, rest) = rest.split_at(len);
+        messages.push(msg.to_vec());
+        remaining = rest;
+    }
+
+    Ok(messages)
+}
+
+/// Retrieve the transforms that are proposed in a recorded message
+pub fn message_transforms(msg: &[u8]) -> Result<Vec<Transform>, ReplayError> {
+    let packet = parse_packet(msg)?;
+
+    let mut transforms = vec![];
+    for sa in &packet.security_associations {
+        for proposal in &sa.proposal_payload {
+            transforms.extend(
+                payload_to_transforms(proposal)?
+                    .into_iter()
+                    .map(|x| x.transform),
+            );
+        }
+    }
+
+    if transforms.is_empty() {
+        return Err(ReplayError::NoTransforms);
+    }
+
+    Ok(transforms)
+}
+
+/// Replace the initiator cookie of a message
+///
+/// A replayed message must not reuse the cookie of the recorded exchange
+pub fn set_initiator_cookie(msg: &mut [u8], initiator_cookie: u64) {
+    if let Some(cookie) = msg.get_mut(..8) {
+        cookie.copy_from_slice(&initiator_cookie.to_be_bytes());
+    }
+}
+
+/// Errors that may occur while preparing a recorded message to be replayed
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+pub enum ReplayError {
+    #[error("Could not parse recorded message: {0}")]
+    InvalidMessage(#[from] IsakmpParseError),
+    #[error("Could not parse transform of recorded message: {0}")]
+    InvalidTransform(#[from] InvalidTransform),
+    #[error("Recorded message proposes no transforms")]
+    NoTransforms,
+}
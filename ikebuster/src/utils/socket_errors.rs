@@ -0,0 +1,11 @@
+//! Classification of errors of the socket
+
+use std::io;
+
+/// Check whether an error of the socket is recoverable, so receiving can continue
+pub fn is_transient(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted | io::ErrorKind::TimedOut
+    )
+}
@@ -0,0 +1,181 @@
+//! Parsing the targets of a scan
+//!
+//! Targets are given as a single address, a comma-separated list or a CIDR range
+//! (e.g. `10.0.0.0/24`), which may be combined: `192.0.2.1,10.0.0.0/30`.
+//!
+//! IPv6 addresses may carry a zone (e.g. `fe80::1%eth0`) to scan link-local targets.
+//!
+//! Longer lists are read from a file with one `ip[:port]` per line, see [parse_targets_file].
+
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// Maximum number of addresses the targets may expand to
+///
+/// This guards against accidentally scanning e.g. an IPv6 `/64`
+pub const MAX_TARGETS: u128 = 1 << 16;
+
+/// The addresses to scan, in the order they were given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Targets {
+    /// The addresses to scan
+    pub ips: Vec<IpAddr>,
+    /// The zone of the IPv6 addresses, e.g. `eth0` for `fe80::1%eth0`
+    ///
+    /// All addresses share the zone, as they are reached over the same interface.
+    /// See [crate::socket::scope_id] to resolve it.
+    pub zone: Option<String>,
+}
+
+impl FromStr for Targets {
+    type Err = TargetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut zone: Option<&str> = None;
+        let mut entries = vec![];
+        for entry in s.split(',').map(str::trim) {
+            let Some((ip, rest)) = entry.split_once('%') else {
+                entries.push(entry.to_string());
+                continue;
+            };
+
+            // The zone precedes the prefix length of a range, e.g. fe80::%eth0/64
+            let (entry_zone, prefix) = match rest.split_once('/') {
+                Some((entry_zone, prefix)) => (entry_zone, Some(prefix)),
+                None => (rest, None),
+            };
+            if entry_zone.is_empty() || !ip.contains(':') {
+                return Err(TargetError::InvalidZone(entry.to_string()));
+            }
+            if zone.is_some_and(|zone| zone != entry_zone) {
+                return Err(TargetError::MixedZones);
+            }
+            zone = Some(entry_zone);
+
+            entries.push(match prefix {
+                Some(prefix) => format!("{ip}/{prefix}"),
+                None => ip.to_string(),
+            });
+        }
+
+        let ips = parse_targets(&entries.join(","))?;
+        if zone.is_some() && ips.iter().any(IpAddr::is_ipv4) {
+            return Err(TargetError::MixedZones);
+        }
+        Ok(Self {
+            ips,
+            zone: zone.map(str::to_string),
+        })
+    }
+}
+
+/// Parse a comma-separated list of addresses and CIDR ranges
+///
+/// All addresses of a range are included, including the network and broadcast address.
+pub fn parse_targets(s: &str) -> Result<Vec<IpAddr>, TargetError> {
+    let mut targets = vec![];
+    for entry in s.split(',').map(str::trim) {
+        if entry.is_empty() {
+            return Err(TargetError::Empty);
+        }
+
+        let Some((ip, prefix)) = entry.split_once('/') else {
+            targets.push(parse_ip(entry)?);
+            continue;
+        };
+        let ip = parse_ip(ip)?;
+        let max_prefix = match ip {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix = prefix
+            .parse::<u32>()
+            .ok()
+            .filter(|prefix| *prefix <= max_prefix)
+            .ok_or_else(|| TargetError::InvalidPrefix(entry.to_string()))?;
+
+        let host_bits = max_prefix - prefix;
+        let count = 1u128.checked_shl(host_bits).unwrap_or(u128::MAX);
+        if count > MAX_TARGETS - targets.len() as u128 {
+            return Err(TargetError::TooManyTargets);
+        }
+
+        // Zero the host bits, so e.g. 10.0.0.5/24 starts at 10.0.0.0
+        let first = match ip {
+            IpAddr::V4(ip) => u128::from(u32::from(ip)) >> host_bits << host_bits,
+            IpAddr::V6(ip) => u128::from(ip).checked_shr(host_bits).unwrap_or(0) << host_bits,
+        };
+        // The range is inclusive, as the last range of IPv6 ends at u128::MAX
+        targets.extend((first..=first + (count - 1)).map(|x| match ip {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::from(x as u32)),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::from(x)),
+        }));
+    }
+
+    if targets.len() as u128 > MAX_TARGETS {
+        return Err(TargetError::TooManyTargets);
+    }
+    Ok(targets)
+}
+
+/// Parse a file listing one target per line as `ip[:port]`
+///
+/// IPv6 addresses with a port are enclosed in brackets, e.g. `[2001:db8::1]:4500`.
+/// Targets without a port use `default_port`. Empty lines and lines starting with `#` are
+/// skipped.
+pub fn parse_targets_file(s: &str, default_port: u16) -> Result<Vec<SocketAddr>, TargetError> {
+    let mut targets = vec![];
+    for (index, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let target = match line.parse::<SocketAddr>() {
+            Ok(target) => target,
+            Err(_) => line
+                .parse::<IpAddr>()
+                .map(|ip| SocketAddr::new(ip, default_port))
+                .map_err(|_| TargetError::InvalidLine(index + 1, line.to_string()))?,
+        };
+        if targets.len() as u128 >= MAX_TARGETS {
+            return Err(TargetError::TooManyTargets);
+        }
+        targets.push(target);
+    }
+
+    if targets.is_empty() {
+        return Err(TargetError::Empty);
+    }
+    Ok(targets)
+}
+
+fn parse_ip(s: &str) -> Result<IpAddr, TargetError> {
+    s.parse()
+        .map_err(|_| TargetError::InvalidAddress(s.to_string()))
+}
+
+/// Errors that may occur while parsing the targets
+#[derive(Debug, Error, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum TargetError {
+    #[error("Empty target")]
+    Empty,
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    #[error("Invalid prefix length: {0}")]
+    InvalidPrefix(String),
+    #[error("The targets exceed {MAX_TARGETS} addresses")]
+    TooManyTargets,
+    #[error("Invalid zone: {0}")]
+    InvalidZone(String),
+    #[error("All targets have to be IPv6 addresses with the same zone")]
+    MixedZones,
+    #[error("Invalid target in line {0}: {1}")]
+    InvalidLine(usize, String),
+}
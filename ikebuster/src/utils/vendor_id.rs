@@ -0,0 +1,59 @@
+//! Fingerprinting the server by its vendor IDs
+//!
+//! Most implementations send the MD5 hash of a vendor specific string, some of them
+//! followed by version or capability bytes. Therefore, the vendor IDs are matched by
+//! their prefix.
+
+use crate::utils::formatting::format_hex;
+
+/// Known vendor IDs as (name, hex encoded prefix)
+pub const KNOWN_VENDOR_IDS: &[(&str, &str)] = &[
+    ("RFC 3947 NAT-T", "4a131c81070358455c5728f20e95452f"),
+    (
+        "draft-ietf-ipsec-nat-t-ike-00",
+        "4485152d18b6bbcd0be8a8469579ddcc",
+    ),
+    (
+        "draft-ietf-ipsec-nat-t-ike-02",
+        "cd60464335df21f87cfdb2fc68b6a448",
+    ),
+    (
+        "draft-ietf-ipsec-nat-t-ike-02\\n",
+        "90cb80913ebb696e086381b5ec427b1f",
+    ),
+    (
+        "draft-ietf-ipsec-nat-t-ike-03",
+        "7d9419a65310ca6f2c179d9215529d56",
+    ),
+    ("Dead Peer Detection", "afcad71368a1f1c96b8696fc77570100"),
+    ("IKE Fragmentation", "4048b7d56ebce88525e7de7f00d6c2d3"),
+    ("XAUTH", "09002689dfd6b712"),
+    ("Heartbeat Notify", "4865617274426561745f4e6f74696679"),
+    ("Cisco Unity", "12f5f28c457168a9702d9fe274cc"),
+    ("strongSwan", "882fe56d6fd20dbc2251613b2ebe5beb"),
+    ("FortiGate", "1d6e178f6c2c0be284985465450fe9d4"),
+    ("Check Point", "f4ed19e0c114eb516faaac0ee37daf2807b4381f"),
+    ("SonicWall", "404bf439522ca3f6"),
+    ("Microsoft Windows", "1e2b516905991c7d7c96fcbfb587e461"),
+    (
+        "Microsoft Vid-Initial-Contact",
+        "26244d38eddb61b3172a36e3d0cfb819",
+    ),
+];
+
+/// Look up the name of the implementation that sent the `vendor_id`
+pub fn identify_vendor_id(vendor_id: &[u8]) -> Option<&'static str> {
+    let hex = format_hex(vendor_id);
+    KNOWN_VENDOR_IDS
+        .iter()
+        .find(|(_, prefix)| hex.starts_with(prefix))
+        .map(|(name, _)| *name)
+}
+
+/// Describe a vendor ID by the name of the implementation, or by its hex encoding
+/// if it is unknown
+pub fn describe_vendor_id(vendor_id: &[u8]) -> String {
+    identify_vendor_id(vendor_id)
+        .map(str::to_string)
+        .unwrap_or_else(|| format_hex(vendor_id))
+}
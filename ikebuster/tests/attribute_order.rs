@@ -0,0 +1,81 @@
+use ikebuster::utils::payload_to_transforms::payload_to_transforms;
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::KEY_LENGTH_FIRST_ATTRIBUTE_ORDER;
+use isakmp::v1::parser::definitions::DataAttribute;
+use isakmp::v1::parser::parse_packet;
+
+fn transform() -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_2048,
+        key_size: Some(256),
+        extra_attributes: vec![],
+    }
+}
+
+fn build(attribute_order: Vec<AttributeType>) -> Vec<u8> {
+    let (msg, _) = MessageBuilder::new()
+        .attribute_order(attribute_order)
+        .add_transform(transform())
+        .build();
+    msg
+}
+
+fn attribute_types(msg: &[u8]) -> Vec<AttributeType> {
+    let packet = parse_packet(msg).unwrap();
+    packet.security_associations[0].proposal_payload[0].transforms[0]
+        .sa_attributes
+        .iter()
+        .map(|attribute| match attribute {
+            DataAttribute::DataAttributeShort(attr) => attr.attribute_type,
+            DataAttribute::DataAttributeLong(attr) => attr.attribute_type,
+        })
+        .collect()
+}
+
+#[test]
+fn orderings_parse_to_the_same_transform() {
+    let default = build(vec![]);
+    let key_length_first = build(KEY_LENGTH_FIRST_ATTRIBUTE_ORDER.to_vec());
+
+    assert_ne!(default, key_length_first);
+    assert_eq!(default.len(), key_length_first.len());
+    assert_eq!(
+        attribute_types(&key_length_first)[..2],
+        [AttributeType::EncryptionAlgorithm, AttributeType::KeyLength]
+    );
+
+    for msg in [default, key_length_first] {
+        let packet = parse_packet(&msg).unwrap();
+        let accepted =
+            payload_to_transforms(&packet.security_associations[0].proposal_payload[0]).unwrap();
+        assert_eq!(accepted.len(), 1);
+        assert_eq!(accepted[0].transform, transform());
+    }
+}
+
+#[test]
+fn missing_attributes_are_appended() {
+    let msg = build(vec![AttributeType::GroupDescription]);
+
+    assert_eq!(
+        attribute_types(&msg),
+        [
+            AttributeType::GroupDescription,
+            AttributeType::EncryptionAlgorithm,
+            AttributeType::HashAlgorithm,
+            AttributeType::AuthenticationMethod,
+            AttributeType::LifeType,
+            AttributeType::LifeDuration,
+            AttributeType::KeyLength,
+        ]
+    );
+}
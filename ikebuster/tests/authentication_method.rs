@@ -0,0 +1,42 @@
+use ikebuster::is_pre_shared_key;
+use ikebuster::ScanResult;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+
+fn transform(authentication_method: AuthenticationMethod) -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method,
+        group_description: GroupDescription::MODP_2048,
+        key_size: Some(256),
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn accepted_transforms_grouped_by_authentication_method() {
+    let result = ScanResult {
+        valid_transforms: vec![
+            transform(AuthenticationMethod::PreSharedKey),
+            transform(AuthenticationMethod::RSASignatures),
+            transform(AuthenticationMethod::PreSharedKey),
+        ],
+        ..Default::default()
+    };
+
+    let grouped = result.by_authentication_method();
+    assert_eq!(grouped.len(), 2);
+    assert_eq!(grouped[&AuthenticationMethod::PreSharedKey].len(), 2);
+    assert_eq!(grouped[&AuthenticationMethod::RSASignatures].len(), 1);
+}
+
+#[test]
+fn pre_shared_key_methods() {
+    assert!(is_pre_shared_key(AuthenticationMethod::PreSharedKey));
+    assert!(is_pre_shared_key(AuthenticationMethod::XAUTH));
+    assert!(!is_pre_shared_key(AuthenticationMethod::RSASignatures));
+}
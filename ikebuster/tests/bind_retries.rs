@@ -0,0 +1,49 @@
+//! Retrying to bind if the address is in use
+
+use std::cell::Cell;
+use std::io;
+
+use ikebuster::utils::bind::bind_with_retries;
+
+/// A binder that fails with `kind` for the given number of attempts
+fn failing_binder(
+    attempts: &Cell<u32>,
+    failures: u32,
+    kind: io::ErrorKind,
+) -> impl FnMut() -> std::future::Ready<io::Result<u32>> + '_ {
+    move || {
+        attempts.set(attempts.get() + 1);
+        std::future::ready(if attempts.get() <= failures {
+            Err(io::Error::from(kind))
+        } else {
+            Ok(attempts.get())
+        })
+    }
+}
+
+#[tokio::test]
+async fn retries_while_in_use() {
+    let attempts = Cell::new(0);
+    let res = bind_with_retries(failing_binder(&attempts, 2, io::ErrorKind::AddrInUse), 3).await;
+    assert_eq!(res.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_limit() {
+    let attempts = Cell::new(0);
+    let res = bind_with_retries(failing_binder(&attempts, 5, io::ErrorKind::AddrInUse), 2).await;
+    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::AddrInUse);
+    assert_eq!(attempts.get(), 3);
+}
+
+#[tokio::test]
+async fn other_errors_are_not_retried() {
+    let attempts = Cell::new(0);
+    let res = bind_with_retries(
+        failing_binder(&attempts, 5, io::ErrorKind::PermissionDenied),
+        3,
+    )
+    .await;
+    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(attempts.get(), 1);
+}
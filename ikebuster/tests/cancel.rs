@@ -0,0 +1,133 @@
+//! Stopping a running scan early
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::IncompleteReason;
+use ikebuster::RateLimitOptions;
+use ikebuster::ScanEvent;
+use ikebuster::ScanOptions;
+use ikebuster::ScanResult;
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+
+/// Run the scan and cancel it after the first event matching `cancel_on`
+async fn cancel_scan(
+    opts: ScanOptions,
+    responder: ScriptedResponder,
+    cancel_on: impl Fn(&ScanEvent) -> bool,
+) -> ScanResult {
+    cancel_scan_after(opts, responder, cancel_on, Duration::ZERO).await
+}
+
+/// Run the scan and cancel it `delay` after the first event matching `cancel_on`
+async fn cancel_scan_after(
+    mut opts: ScanOptions,
+    responder: ScriptedResponder,
+    cancel_on: impl Fn(&ScanEvent) -> bool,
+    delay: Duration,
+) -> ScanResult {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let cancel = CancellationToken::new();
+    opts.progress = Some(tx);
+    opts.cancel = Some(cancel.clone());
+
+    let scan = tokio::spawn(scan_with_transport(opts, Arc::new(responder)));
+    while let Some(event) = rx.recv().await {
+        if cancel_on(&event) {
+            break;
+        }
+    }
+    sleep(delay).await;
+    cancel.cancel();
+
+    timeout(Duration::from_secs(1), scan)
+        .await
+        .expect("The scan didn't stop")
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn cancelled_scan_returns_partial_result() {
+    let mut opts = scan_options();
+    opts.interval = 20;
+    opts.transform_no = 1;
+    let responder = ScriptedResponder::new(Behavior::PickOne, supported_every(3));
+
+    let result = cancel_scan(opts, responder, |event| {
+        matches!(event, ScanEvent::TransformAccepted(_))
+    })
+    .await;
+
+    assert!(!result.completed);
+    assert!(result
+        .incomplete_reasons
+        .contains(&IncompleteReason::Cancelled));
+    assert!(!result.valid_transforms.is_empty());
+    assert!(result.valid_transforms.len() < supported_every(3).len());
+}
+
+#[tokio::test]
+async fn cancellation_stops_waiting_for_responses() {
+    let mut opts = scan_options();
+    opts.response_timeout = Duration::from_secs(3600);
+    let responder = ScriptedResponder::new(Behavior::PickOne, supported_every(3)).drop_every(1);
+
+    let result = cancel_scan(opts, responder, |event| {
+        matches!(event, ScanEvent::ProposalSent { remaining: 0, .. })
+    })
+    .await;
+
+    assert!(!result.completed);
+    assert!(result.valid_transforms.is_empty());
+}
+
+#[tokio::test]
+async fn cancellation_interrupts_sleep_on_transform_found() {
+    let mut opts = scan_options();
+    opts.sleep_on_transform_found = Duration::from_secs(3600);
+    let responder = ScriptedResponder::new(Behavior::AcceptsAll, supported_every(3));
+
+    let result = cancel_scan(opts, responder, |event| {
+        matches!(event, ScanEvent::TransformAccepted(_))
+    })
+    .await;
+
+    assert!(!result.completed);
+    assert!(!result.valid_transforms.is_empty());
+}
+
+#[tokio::test]
+async fn cancellation_interrupts_backoff() {
+    let mut opts = scan_options();
+    opts.rate_limit = Some(RateLimitOptions {
+        max_unanswered: 1,
+        backoff: Duration::from_secs(3600),
+        max_backoff: Duration::from_secs(3600),
+    });
+    let responder = ScriptedResponder::new(Behavior::PickOne, supported_every(3)).drop_every(1);
+
+    // The scan backs off right after the first unanswered proposal
+    let result = cancel_scan_after(
+        opts,
+        responder,
+        |event| matches!(event, ScanEvent::ProposalSent { .. }),
+        Duration::from_millis(50),
+    )
+    .await;
+
+    assert!(result
+        .incomplete_reasons
+        .contains(&IncompleteReason::Cancelled));
+    assert!(result.valid_transforms.is_empty());
+}
@@ -0,0 +1,39 @@
+use ikebuster::utils::formatting::format_attribute;
+use ikebuster::utils::formatting::is_weak_group_prime;
+use ikebuster::utils::formatting::prime_bit_length;
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::parser::definitions::DataAttribute;
+use isakmp::v1::parser::definitions::DataAttributeLong;
+
+fn group_prime(bits: usize) -> DataAttribute {
+    let mut prime = vec![0xff; bits / 8];
+    prime[bits / 8 - 1] = 0xfd;
+
+    DataAttribute::DataAttributeLong(DataAttributeLong {
+        attribute_type: AttributeType::GroupPrime,
+        attribute_value: prime,
+    })
+}
+
+#[test]
+fn bit_length_ignores_leading_zeros() {
+    assert_eq!(prime_bit_length(&[]), 0);
+    assert_eq!(prime_bit_length(&[0x00, 0x01]), 1);
+    assert_eq!(prime_bit_length(&[0x00, 0x80, 0x00]), 16);
+}
+
+#[test]
+fn custom_1024_bit_group_is_weak() {
+    let attribute = group_prime(1024);
+
+    assert!(is_weak_group_prime(&attribute));
+    assert_eq!(format_attribute(&attribute), "GroupPrime=1024 bit (weak)");
+}
+
+#[test]
+fn custom_2048_bit_group_is_not_weak() {
+    let attribute = group_prime(2048);
+
+    assert!(!is_weak_group_prime(&attribute));
+    assert_eq!(format_attribute(&attribute), "GroupPrime=2048 bit");
+}
@@ -0,0 +1,96 @@
+//! Decoding single responses to a proposal
+
+use ikebuster::decode_response;
+use ikebuster::ResponseOutcome;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::ExchangeType;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::definitions::NotifyMessageType;
+use isakmp::v1::definitions::PayloadType;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+
+fn transform(group_description: GroupDescription) -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description,
+        key_size: Some(256),
+        extra_attributes: vec![],
+    }
+}
+
+fn sent() -> Vec<Transform> {
+    vec![
+        transform(GroupDescription::MODP_1024),
+        transform(GroupDescription::MODP_2048),
+    ]
+}
+
+/// An informational message with a single notification of the given type
+fn notification(notify_message_type: NotifyMessageType) -> Vec<u8> {
+    let mut msg = vec![];
+    msg.extend_from_slice(&1u64.to_be_bytes());
+    msg.extend_from_slice(&0u64.to_be_bytes());
+    msg.extend_from_slice(&[
+        PayloadType::Notification as u8,
+        0x10,
+        ExchangeType::Informational as u8,
+        0,
+    ]);
+    msg.extend_from_slice(&0u32.to_be_bytes());
+    msg.extend_from_slice(&40u32.to_be_bytes());
+
+    msg.extend_from_slice(&[PayloadType::None as u8, 0]);
+    msg.extend_from_slice(&12u16.to_be_bytes());
+    // IPsec DOI, ISAKMP protocol without SPI
+    msg.extend_from_slice(&1u32.to_be_bytes());
+    msg.extend_from_slice(&[1, 0]);
+    msg.extend_from_slice(&(notify_message_type as u16).to_be_bytes());
+    msg
+}
+
+#[test]
+fn acceptance() {
+    let (msg, _) = MessageBuilder::new()
+        .add_transform(transform(GroupDescription::MODP_2048))
+        .build();
+
+    assert_eq!(
+        decode_response(&sent(), &msg),
+        ResponseOutcome::Accepted(vec![transform(GroupDescription::MODP_2048)])
+    );
+}
+
+#[test]
+fn unsolicited_acceptance() {
+    let (msg, _) = MessageBuilder::new()
+        .add_transform(transform(GroupDescription::MODP_4096))
+        .build();
+
+    assert_eq!(decode_response(&sent(), &msg), ResponseOutcome::Other);
+}
+
+#[test]
+fn no_proposal_chosen() {
+    assert_eq!(
+        decode_response(&sent(), &notification(NotifyMessageType::NoProposalChosen)),
+        ResponseOutcome::Rejected(NotifyMessageType::NoProposalChosen)
+    );
+    assert_eq!(
+        decode_response(&sent(), &notification(NotifyMessageType::Connected)),
+        ResponseOutcome::Other
+    );
+}
+
+#[test]
+fn unparseable_datagram() {
+    assert_eq!(
+        decode_response(&sent(), b"not an isakmp message"),
+        ResponseOutcome::Other
+    );
+    assert_eq!(decode_response(&sent(), &[]), ResponseOutcome::Other);
+}
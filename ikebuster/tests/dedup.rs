@@ -0,0 +1,49 @@
+use ikebuster::utils::dedup::dedup_transforms;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+
+fn transform(encryption_algorithm: EncryptionAlgorithm, key_size: Option<u16>) -> Transform {
+    Transform {
+        encryption_algorithm,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_2048,
+        key_size,
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn duplicates_are_collapsed() {
+    let mut transforms = vec![
+        transform(EncryptionAlgorithm::AES_CBC, Some(256)),
+        transform(EncryptionAlgorithm::TrippleDES_CBC, None),
+        transform(EncryptionAlgorithm::AES_CBC, Some(128)),
+        transform(EncryptionAlgorithm::AES_CBC, Some(256)),
+        transform(EncryptionAlgorithm::TrippleDES_CBC, None),
+    ];
+
+    assert_eq!(dedup_transforms(&mut transforms), 2);
+    assert_eq!(
+        transforms,
+        [
+            transform(EncryptionAlgorithm::AES_CBC, Some(256)),
+            transform(EncryptionAlgorithm::TrippleDES_CBC, None),
+            transform(EncryptionAlgorithm::AES_CBC, Some(128)),
+        ]
+    );
+}
+
+#[test]
+fn ignored_key_sizes_are_kept() {
+    let mut transforms = vec![
+        transform(EncryptionAlgorithm::TrippleDES_CBC, None),
+        transform(EncryptionAlgorithm::TrippleDES_CBC, Some(192)),
+    ];
+
+    assert_eq!(dedup_transforms(&mut transforms), 0);
+    assert_eq!(transforms.len(), 2);
+}
@@ -0,0 +1,49 @@
+//! Setting the DF bit on outgoing packets
+
+#![cfg(target_os = "linux")]
+
+use std::os::fd::AsRawFd;
+
+use ikebuster::socket::set_dont_fragment;
+use tokio::net::UdpSocket;
+
+/// Retrieve an integer socket option
+fn get_option(socket: &UdpSocket, level: libc::c_int, name: libc::c_int) -> libc::c_int {
+    let mut value: libc::c_int = 0;
+    let mut len = size_of::<libc::c_int>() as libc::socklen_t;
+    // SAFETY: The buffer matches the given length and the socket is alive
+    let res = unsafe {
+        libc::getsockopt(
+            socket.as_raw_fd(),
+            level,
+            name,
+            (&mut value as *mut libc::c_int).cast(),
+            &mut len,
+        )
+    };
+    assert_eq!(res, 0);
+    value
+}
+
+#[tokio::test]
+async fn ipv4_dont_fragment() {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    set_dont_fragment(&socket).unwrap();
+    assert_eq!(
+        get_option(&socket, libc::IPPROTO_IP, libc::IP_MTU_DISCOVER),
+        libc::IP_PMTUDISC_DO
+    );
+}
+
+#[tokio::test]
+async fn ipv6_dont_fragment() {
+    // IPv6 may be disabled in the test environment
+    let Ok(socket) = UdpSocket::bind("[::1]:0").await else {
+        return;
+    };
+    set_dont_fragment(&socket).unwrap();
+    assert_eq!(
+        get_option(&socket, libc::IPPROTO_IPV6, libc::IPV6_MTU_DISCOVER),
+        libc::IPV6_PMTUDISC_DO
+    );
+}
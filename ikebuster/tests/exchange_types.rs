@@ -0,0 +1,50 @@
+//! Probing the exchange types the server supports
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::ScanOptions;
+use isakmp::v1::definitions::ExchangeType;
+
+#[tokio::test]
+async fn rejected_probes_are_not_supported() {
+    let opts = ScanOptions {
+        probe_exchange_types: true,
+        ..scan_options()
+    };
+    // The probes use the first tested transform, which the server supports,
+    // but the informational probe offers no transform and is rejected
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::PickOne,
+        supported_every(5),
+    ));
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    assert_eq!(
+        result.supported_exchange_types,
+        [
+            ExchangeType::Base,
+            ExchangeType::IdentityProtection,
+            ExchangeType::AuthenticationOnly,
+            ExchangeType::Aggressive,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn probes_are_not_supported_without_acceptable_transform() {
+    let opts = ScanOptions {
+        probe_exchange_types: true,
+        ..scan_options()
+    };
+    let responder = Arc::new(ScriptedResponder::new(Behavior::RejectsAll, vec![]));
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    assert!(result.supported_exchange_types.is_empty());
+}
@@ -0,0 +1,47 @@
+//! Scanning transforms with attributes beyond the standard ones
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use ikebuster::planned_transforms;
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::ScanOptions;
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::generator::AttributeValue;
+use isakmp::v1::generator::Transform;
+
+#[tokio::test]
+async fn accepted_transforms_keep_extra_attributes() {
+    let mut transforms: Vec<Transform> = planned_transforms(&scan_options())
+        .into_iter()
+        .take(12)
+        .map(|transform| Transform {
+            extra_attributes: vec![(AttributeType::PRF, AttributeValue::Short(1))],
+            ..transform
+        })
+        .collect();
+    transforms[9].extra_attributes.push((
+        AttributeType::GroupOrder,
+        AttributeValue::Long(vec![0xff; 6]),
+    ));
+    let mut supported = vec![transforms[4].clone(), transforms[9].clone()];
+    supported.sort();
+
+    let opts = ScanOptions {
+        transforms: Some(transforms.clone()),
+        ..scan_options()
+    };
+    let responder = Arc::new(ScriptedResponder::new(Behavior::PickOne, supported.clone()));
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    assert_eq!(result.valid_transforms, supported);
+    let mut rejected = result.rejected_transforms;
+    rejected.extend(supported);
+    rejected.sort();
+    transforms.sort();
+    assert_eq!(rejected, transforms);
+}
@@ -0,0 +1,83 @@
+//! Stopping the scan after the first accepted transform
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use ikebuster::planned_transforms;
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::IncompleteReason;
+use ikebuster::ScanOptions;
+
+fn options() -> ScanOptions {
+    ScanOptions {
+        // Wait for the response to each proposal before sending the next one
+        interval: 200,
+        stop_on_first_accept: true,
+        ..scan_options()
+    }
+}
+
+#[tokio::test]
+async fn stops_after_first_accept() {
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::PickOne,
+        supported_every(6),
+    ));
+
+    let result = scan_with_transport(options(), responder.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(result.valid_transforms, [supported_every(6)[0].clone()]);
+    assert!(!result.completed);
+    assert!(result
+        .incomplete_reasons
+        .contains(&IncompleteReason::StoppedOnAccept));
+    assert_eq!(responder.proposals().len(), 1);
+}
+
+#[tokio::test]
+async fn enumerates_all_without_accept() {
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::RejectsAll,
+        supported_every(6),
+    ));
+    let opts = ScanOptions {
+        stop_on_first_accept: true,
+        ..scan_options()
+    };
+
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    assert!(result.valid_transforms.is_empty());
+    assert_eq!(
+        result.rejected_transforms.len(),
+        planned_transforms(&scan_options()).len()
+    );
+}
+
+#[tokio::test]
+async fn found_transform_is_verified() {
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::PickOne,
+        supported_every(6),
+    ));
+    let opts = ScanOptions {
+        verify_findings: true,
+        ..options()
+    };
+
+    let result = scan_with_transport(opts, responder.clone()).await.unwrap();
+
+    assert_eq!(result.valid_transforms, [supported_every(6)[0].clone()]);
+    assert!(result.unverified_transforms.is_empty());
+    assert_eq!(
+        responder.proposals().last().unwrap(),
+        &[supported_every(6)[0].clone()]
+    );
+}
@@ -0,0 +1,47 @@
+//! Scanning servers that fragment their responses
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+
+#[tokio::test]
+async fn fragmented_responses_are_reassembled() {
+    let responder = Arc::new(
+        ScriptedResponder::new(Behavior::PickOne, supported_every(5))
+            .vendor_id([0xab; 64])
+            .fragment(32),
+    );
+
+    let result = scan_with_transport(scan_options(), responder)
+        .await
+        .unwrap();
+
+    let mut expected = supported_every(5);
+    expected.sort();
+    assert_eq!(result.valid_transforms, expected);
+    assert!(!result.rejected_transforms.is_empty());
+    assert!(result.unanswered_transforms.is_empty());
+    assert_eq!(result.vendor_ids, ["ab".repeat(64)]);
+}
+
+#[tokio::test]
+async fn fragmented_responses_with_nat_t() {
+    let responder = Arc::new(
+        ScriptedResponder::new(Behavior::PickOne, supported_every(5))
+            .nat_t()
+            .fragment(50),
+    );
+    let mut opts = scan_options();
+    opts.nat_t = true;
+
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    assert_eq!(result.valid_transforms.len(), supported_every(5).len());
+    assert!(result.unanswered_transforms.is_empty());
+}
@@ -0,0 +1,90 @@
+//! Grading of the accepted transforms
+
+use ikebuster::utils::grading::grade_transform;
+use ikebuster::utils::grading::grade_transforms;
+use ikebuster::utils::grading::Grade;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+
+fn transform(
+    encryption_algorithm: EncryptionAlgorithm,
+    hash_algorithm: HashAlgorithm,
+    group_description: GroupDescription,
+) -> Transform {
+    Transform {
+        encryption_algorithm,
+        hash_algorithm,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description,
+        key_size: None,
+        extra_attributes: vec![],
+    }
+}
+
+fn weak() -> Transform {
+    transform(
+        EncryptionAlgorithm::DES_CBC,
+        HashAlgorithm::MD5,
+        GroupDescription::MODP_768,
+    )
+}
+
+fn acceptable() -> Transform {
+    transform(
+        EncryptionAlgorithm::AES_CBC,
+        HashAlgorithm::SHA2_256,
+        GroupDescription::MODP_2048,
+    )
+}
+
+fn strong() -> Transform {
+    transform(
+        EncryptionAlgorithm::AES_CBC,
+        HashAlgorithm::SHA2_256,
+        GroupDescription::MODP_3072,
+    )
+}
+
+#[test]
+fn grades_by_weakest_algorithm() {
+    assert_eq!(grade_transform(&weak()), Grade::Weak);
+    assert_eq!(grade_transform(&acceptable()), Grade::Acceptable);
+    assert_eq!(grade_transform(&strong()), Grade::Strong);
+
+    // A single weak algorithm is enough
+    let mut sha1 = strong();
+    sha1.hash_algorithm = HashAlgorithm::SHA;
+    assert_eq!(grade_transform(&sha1), Grade::Weak);
+
+    let mut triple_des = strong();
+    triple_des.encryption_algorithm = EncryptionAlgorithm::TrippleDES_CBC;
+    assert_eq!(grade_transform(&triple_des), Grade::Weak);
+
+    let mut modp_1024 = strong();
+    modp_1024.group_description = GroupDescription::MODP_1024;
+    assert_eq!(grade_transform(&modp_1024), Grade::Weak);
+}
+
+#[test]
+fn authentication_method_is_not_graded() {
+    let mut rsa = strong();
+    rsa.authentication_method = AuthenticationMethod::RSASignatures;
+    assert_eq!(grade_transform(&rsa), Grade::Strong);
+}
+
+#[test]
+fn transforms_are_graded_by_weakest() {
+    assert_eq!(grade_transforms(&[]), None);
+    assert_eq!(grade_transforms(&[strong()]), Some(Grade::Strong));
+    assert_eq!(
+        grade_transforms(&[strong(), acceptable()]),
+        Some(Grade::Acceptable)
+    );
+    assert_eq!(
+        grade_transforms(&[strong(), weak(), acceptable()]),
+        Some(Grade::Weak)
+    );
+}
@@ -0,0 +1,192 @@
+//! End-to-end scans against a scripted server
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use ikebuster::planned_transforms;
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::IncompleteReason;
+use ikebuster::ScanOptions;
+use ikebuster::ScanResult;
+use ikebuster::ServerQuirk;
+use ikebuster::TransformChoice;
+use isakmp::v1::definitions::LifeType;
+use isakmp::v1::generator::Lifetime;
+use isakmp::v1::generator::Transform;
+
+async fn run(
+    behavior: Behavior,
+    supported: Vec<Transform>,
+) -> (ScanResult, Arc<ScriptedResponder>) {
+    let responder = Arc::new(ScriptedResponder::new(behavior, supported));
+    let result = scan_with_transport(scan_options(), responder.clone())
+        .await
+        .unwrap();
+    (result, responder)
+}
+
+fn sorted(mut transforms: Vec<Transform>) -> Vec<Transform> {
+    transforms.sort();
+    transforms
+}
+
+#[tokio::test]
+async fn pick_one() {
+    let (result, responder) = run(Behavior::PickOne, supported_every(5)).await;
+
+    assert!(result.responded);
+    assert_eq!(result.valid_transforms, sorted(supported_every(5)));
+    assert!(result.quirks.is_empty());
+    // Every planned transform is offered at least once
+    let offered: Vec<Transform> = responder.proposals().into_iter().flatten().collect();
+    for transform in planned_transforms(&scan_options()) {
+        assert!(offered.contains(&transform));
+    }
+}
+
+#[tokio::test]
+async fn accepts_all() {
+    let (result, _) = run(Behavior::AcceptsAll, vec![]).await;
+
+    assert_eq!(
+        result.valid_transforms,
+        sorted(planned_transforms(&scan_options()))
+    );
+    assert!(result.rejected_transforms.is_empty());
+    assert!(result.quirks.contains(&ServerQuirk::AcceptsAll));
+}
+
+#[tokio::test]
+async fn rejects_all() {
+    let (result, responder) = run(Behavior::RejectsAll, supported_every(5)).await;
+
+    assert!(result.responded);
+    assert!(result.valid_transforms.is_empty());
+    assert_eq!(
+        sorted(result.rejected_transforms),
+        sorted(planned_transforms(&scan_options()))
+    );
+    // Each proposal is sent exactly once
+    assert_eq!(responder.proposals().len(), 5);
+}
+
+#[tokio::test]
+async fn reorders() {
+    let (result, _) = run(Behavior::Reorders, supported_every(5)).await;
+
+    assert_eq!(result.valid_transforms, sorted(supported_every(5)));
+    assert!(result.quirks.is_empty());
+}
+
+#[tokio::test]
+async fn unsolicited_transforms_are_discarded() {
+    let (result, _) = run(Behavior::Unsolicited, supported_every(5)).await;
+
+    assert_eq!(result.valid_transforms, sorted(supported_every(5)));
+    // Proposals accepted only with transforms that weren't offered are undecided
+    assert!(result.rejected_transforms.is_empty());
+    let mut unanswered = result.unanswered_transforms;
+    unanswered.extend(supported_every(5));
+    assert_eq!(
+        sorted(unanswered),
+        sorted(planned_transforms(&scan_options()))
+    );
+    assert!(result
+        .incomplete_reasons
+        .contains(&IncompleteReason::Unanswered));
+}
+
+#[tokio::test]
+async fn chosen_transform_of_proposal() {
+    let planned = planned_transforms(&scan_options());
+    let opts = ScanOptions {
+        transform_no: 3,
+        max_transforms: Some(3),
+        ..scan_options()
+    };
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::PickOne,
+        vec![planned[1].clone()],
+    ));
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    assert_eq!(
+        result.choices,
+        vec![TransformChoice {
+            transform: planned[1].clone(),
+            transform_no: 1,
+            index: 1,
+            offered: 3,
+            lifetime: Some(Lifetime::default()),
+            rtt: result.choices[0].rtt,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn lifetime_of_response() {
+    // Exceeds two bytes, so it is sent as a variable length attribute
+    let lifetime = Lifetime {
+        life_type: LifeType::Kilobytes,
+        duration: 4_608_000,
+    };
+    let responder =
+        Arc::new(ScriptedResponder::new(Behavior::PickOne, supported_every(5)).lifetime(lifetime));
+    let result = scan_with_transport(scan_options(), responder)
+        .await
+        .unwrap();
+
+    assert!(!result.choices.is_empty());
+    assert!(result.choices.iter().all(|x| x.lifetime == Some(lifetime)));
+    assert_eq!(
+        result.lifetimes().into_iter().collect::<Vec<_>>(),
+        [lifetime]
+    );
+    assert_eq!(lifetime.to_string(), "4608000 kilobytes");
+}
+
+#[tokio::test]
+async fn separate_associations() {
+    let planned = planned_transforms(&scan_options());
+    let opts = ScanOptions {
+        transform_no: 3,
+        max_transforms: Some(3),
+        ..scan_options()
+    };
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::SeparateAssociations,
+        vec![planned[0].clone(), planned[2].clone()],
+    ));
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    let first = &result.accepted_proposals[..2];
+    assert_eq!(first[0].initiator_cookie, first[1].initiator_cookie);
+    assert_eq!(first[0].sa_index, 0);
+    assert_eq!(first[0].transforms, vec![planned[0].clone()]);
+    assert_eq!(first[1].sa_index, 1);
+    assert_eq!(first[1].transforms, vec![planned[2].clone()]);
+    assert_eq!(
+        result.valid_transforms,
+        sorted(vec![planned[0].clone(), planned[2].clone()])
+    );
+}
+
+#[tokio::test]
+async fn other_domain_of_interpretation() {
+    let (result, _) = run(Behavior::OtherDomainOfInterpretation, supported_every(5)).await;
+
+    // The transforms are found nevertheless
+    assert_eq!(result.valid_transforms, sorted(supported_every(5)));
+    assert_eq!(
+        result.quirks,
+        [ServerQuirk::UnexpectedSituation {
+            doi: 0,
+            situation: vec![0, 0, 0, 1],
+        }]
+    );
+}
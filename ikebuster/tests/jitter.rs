@@ -0,0 +1,63 @@
+//! Randomizing the interval between sent messages
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use ikebuster::utils::jitter::jittered_interval;
+
+#[test]
+fn no_jitter_keeps_interval() {
+    let interval = Duration::from_millis(500);
+    assert_eq!(jittered_interval(interval, Duration::ZERO), interval);
+}
+
+#[test]
+fn jitter_stays_within_bounds() {
+    let interval = Duration::from_millis(500);
+    let jitter = Duration::from_millis(100);
+
+    let delays: HashSet<Duration> = (0..1000)
+        .map(|_| jittered_interval(interval, jitter))
+        .collect();
+    assert!(delays
+        .iter()
+        .all(|x| (interval - jitter..=interval + jitter).contains(x)));
+    assert!(delays.len() > 1);
+}
+
+#[test]
+fn jitter_exceeding_interval_doesnt_go_negative() {
+    let interval = Duration::from_millis(10);
+    let jitter = Duration::from_millis(50);
+
+    for _ in 0..1000 {
+        assert!(jittered_interval(interval, jitter) <= interval + jitter);
+    }
+}
+
+#[cfg(feature = "test-util")]
+#[tokio::test]
+async fn scan_with_jitter() {
+    use std::sync::Arc;
+
+    use ikebuster::planned_transforms;
+    use ikebuster::scan_with_transport;
+    use ikebuster::testing::scan_options;
+    use ikebuster::testing::Behavior;
+    use ikebuster::testing::ScriptedResponder;
+    use ikebuster::ScanOptions;
+
+    let supported = planned_transforms(&scan_options())[..3].to_vec();
+    let responder = Arc::new(ScriptedResponder::new(Behavior::PickOne, supported.clone()));
+    let opts = ScanOptions {
+        interval: 5,
+        jitter: Duration::from_millis(5),
+        ..scan_options()
+    };
+
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    let mut expected = supported;
+    expected.sort();
+    assert_eq!(result.valid_transforms, expected);
+}
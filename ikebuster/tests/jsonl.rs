@@ -0,0 +1,66 @@
+//! Incremental output of accepted transforms
+
+use std::net::SocketAddr;
+
+use ikebuster::utils::jsonl::AcceptedLine;
+use ikebuster::utils::jsonl::JsonlWriter;
+use ikebuster::ScanEvent;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+
+fn transform(encryption_algorithm: EncryptionAlgorithm) -> Transform {
+    Transform {
+        encryption_algorithm,
+        hash_algorithm: HashAlgorithm::SHA,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_1024,
+        key_size: None,
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn accepted_transforms_are_written_as_lines() {
+    let target: SocketAddr = "192.0.2.1:500".parse().unwrap();
+    let des = transform(EncryptionAlgorithm::DES_CBC);
+    let idea = transform(EncryptionAlgorithm::IDEA_CBC);
+
+    let mut output = vec![];
+    let mut writer = JsonlWriter::new(&mut output, target);
+    for event in [
+        ScanEvent::ProposalSent {
+            transforms: vec![des.clone(), idea.clone()],
+            remaining: 0,
+        },
+        ScanEvent::TransformAccepted(des.clone()),
+        ScanEvent::ProposalRejected(vec![idea.clone()]),
+        ScanEvent::TransformAccepted(idea.clone()),
+        // Verifying the findings reports them again
+        ScanEvent::TransformAccepted(des.clone()),
+    ] {
+        writer.handle(&event).unwrap();
+    }
+    drop(writer);
+
+    let lines: Vec<AcceptedLine> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            AcceptedLine {
+                target,
+                transform: des
+            },
+            AcceptedLine {
+                target,
+                transform: idea
+            },
+        ]
+    );
+}
@@ -0,0 +1,79 @@
+//! Decoding the lifetimes of returned transforms
+
+use ikebuster::utils::payload_to_transforms::payload_to_transforms;
+use ikebuster::utils::payload_to_transforms::AcceptedTransform;
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::definitions::LifeType;
+use isakmp::v1::generator::AttributeValue;
+use isakmp::v1::generator::Lifetime;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::parser::parse_packet;
+
+fn transform() -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_2048,
+        key_size: Some(256),
+        extra_attributes: vec![],
+    }
+}
+
+/// Decode a transform whose attributes are followed by the given ones,
+/// which replace the lifetime the builder emits
+fn decode(extra_attributes: Vec<(AttributeType, AttributeValue)>) -> AcceptedTransform {
+    let (msg, _) = MessageBuilder::new()
+        .lifetime(Lifetime {
+            life_type: LifeType::Kilobytes,
+            duration: 4_608_000,
+        })
+        .add_transform(Transform {
+            extra_attributes,
+            ..transform()
+        })
+        .build();
+    let packet = parse_packet(&msg).unwrap();
+    let mut accepted =
+        payload_to_transforms(&packet.security_associations[0].proposal_payload[0]).unwrap();
+    assert_eq!(accepted.len(), 1);
+    accepted.remove(0)
+}
+
+#[test]
+fn known_lifetime() {
+    let accepted = decode(vec![]);
+    assert_eq!(accepted.transform, transform());
+    assert_eq!(
+        accepted.lifetime,
+        Some(Lifetime {
+            life_type: LifeType::Kilobytes,
+            duration: 4_608_000,
+        })
+    );
+}
+
+#[test]
+fn unknown_life_type_is_ignored() {
+    let accepted = decode(vec![(
+        AttributeType::LifeType,
+        AttributeValue::Short(0xff00),
+    )]);
+    assert_eq!(accepted.transform, transform());
+    assert_eq!(accepted.lifetime, None);
+}
+
+#[test]
+fn overlong_life_duration_is_ignored() {
+    let accepted = decode(vec![(
+        AttributeType::LifeDuration,
+        AttributeValue::Long(vec![0x01; 8]),
+    )]);
+    assert_eq!(accepted.transform, transform());
+    assert_eq!(accepted.lifetime, None);
+}
@@ -0,0 +1,64 @@
+use ikebuster::utils::matrix::MatrixCell;
+use ikebuster::ScanResult;
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+
+fn transform(encryption_algorithm: EncryptionAlgorithm, group: GroupDescription) -> Transform {
+    Transform {
+        encryption_algorithm,
+        hash_algorithm: HashAlgorithm::SHA,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: group,
+        key_size: None,
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn matrix_of_encryption_and_group() {
+    let result = ScanResult {
+        valid_transforms: vec![
+            transform(EncryptionAlgorithm::DES_CBC, GroupDescription::MODP_1024),
+            transform(
+                EncryptionAlgorithm::TrippleDES_CBC,
+                GroupDescription::MODP_1024,
+            ),
+        ],
+        rejected_transforms: vec![
+            transform(EncryptionAlgorithm::DES_CBC, GroupDescription::MODP_2048),
+            transform(EncryptionAlgorithm::DES_CBC, GroupDescription::MODP_768),
+        ],
+        ..Default::default()
+    };
+
+    let matrix = result
+        .matrix(
+            AttributeType::EncryptionAlgorithm,
+            AttributeType::GroupDescription,
+        )
+        .unwrap();
+
+    assert_eq!(matrix.columns, ["DES_CBC", "TrippleDES_CBC"]);
+    assert_eq!(matrix.rows, ["MODP_768", "MODP_1024", "MODP_2048"]);
+    assert_eq!(
+        matrix.cells,
+        [
+            [MatrixCell::Rejected, MatrixCell::Indeterminate],
+            [MatrixCell::Accepted, MatrixCell::Accepted],
+            [MatrixCell::Rejected, MatrixCell::Indeterminate],
+        ]
+    );
+}
+
+#[test]
+fn matrix_of_unsupported_attribute() {
+    let result = ScanResult::default();
+
+    assert!(result
+        .matrix(AttributeType::LifeType, AttributeType::GroupDescription)
+        .is_none());
+}
@@ -0,0 +1,58 @@
+use std::net::SocketAddr;
+
+use ikebuster::ScanResult;
+use ikebuster::ServerQuirk;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+
+fn transform(encryption_algorithm: EncryptionAlgorithm) -> Transform {
+    Transform {
+        encryption_algorithm,
+        hash_algorithm: HashAlgorithm::SHA,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_1024,
+        key_size: None,
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn merge_keeps_provenance() {
+    let ike: SocketAddr = "192.0.2.1:500".parse().unwrap();
+    let nat_t: SocketAddr = "192.0.2.1:4500".parse().unwrap();
+
+    let merged = ScanResult::merge(&[
+        ScanResult {
+            target: Some(ike),
+            valid_transforms: vec![
+                transform(EncryptionAlgorithm::DES_CBC),
+                transform(EncryptionAlgorithm::TrippleDES_CBC),
+            ],
+            quirks: vec![ServerQuirk::MultipleTransformsReturned],
+            ..Default::default()
+        },
+        ScanResult {
+            target: Some(nat_t),
+            valid_transforms: vec![transform(EncryptionAlgorithm::TrippleDES_CBC)],
+            ..Default::default()
+        },
+    ]);
+
+    assert_eq!(merged.targets, [ike, nat_t]);
+    assert_eq!(merged.valid_transforms.len(), 2);
+    assert_eq!(
+        merged.valid_transforms[0].transform,
+        transform(EncryptionAlgorithm::DES_CBC)
+    );
+    assert_eq!(merged.valid_transforms[0].found_on, [ike]);
+    assert_eq!(
+        merged.valid_transforms[1].transform,
+        transform(EncryptionAlgorithm::TrippleDES_CBC)
+    );
+    assert_eq!(merged.valid_transforms[1].found_on, [ike, nat_t]);
+    assert_eq!(merged.quirks.len(), 1);
+    assert_eq!(merged.quirks[0].found_on, [ike]);
+}
@@ -0,0 +1,46 @@
+use std::collections::VecDeque;
+
+use ikebuster::utils::gen_transforms::gen_v1_transforms;
+use ikebuster::utils::gen_transforms::split_oversized;
+use ikebuster::utils::gen_transforms::TransformFilter;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+
+fn message_size(transforms: &[Transform]) -> usize {
+    let mut mb = MessageBuilder::new();
+    for transform in transforms {
+        mb = mb.add_transform(transform.clone());
+    }
+    mb.build().0.len()
+}
+
+#[test]
+fn oversized_proposals_are_split() {
+    let proposals: VecDeque<_> = gen_v1_transforms(&TransformFilter::default(), 64)
+        .into_iter()
+        .take(2)
+        .collect();
+    assert!(proposals.iter().all(|x| message_size(x) > 1000));
+
+    let split = split_oversized(proposals.clone(), 1000, message_size);
+
+    assert!(split.len() > proposals.len());
+    assert!(split.iter().all(|x| message_size(x) <= 1000));
+    assert_eq!(
+        split.into_iter().flatten().collect::<Vec<_>>(),
+        proposals.into_iter().flatten().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn single_transforms_are_kept() {
+    let proposals: VecDeque<_> = gen_v1_transforms(&TransformFilter::default(), 1)
+        .into_iter()
+        .take(3)
+        .collect();
+
+    assert_eq!(
+        split_oversized(proposals.clone(), 0, message_size),
+        proposals
+    );
+}
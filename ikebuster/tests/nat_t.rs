@@ -0,0 +1,68 @@
+//! Scanning using NAT-traversal
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::NAT_T_PORT;
+use ikebuster::NAT_T_VENDOR_IDS;
+
+#[test]
+fn nat_t_changes_ports() {
+    let mut opts = scan_options();
+    opts.nat_t = true;
+    assert_eq!(opts.target().port(), NAT_T_PORT);
+    assert_eq!(opts.local_port(), NAT_T_PORT);
+
+    opts.source_port = 0;
+    assert_eq!(opts.local_port(), 0);
+}
+
+#[tokio::test]
+async fn messages_carry_non_esp_marker() {
+    let responder = Arc::new(ScriptedResponder::new(Behavior::PickOne, supported_every(7)).nat_t());
+    let mut opts = scan_options();
+    opts.nat_t = true;
+
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    let mut expected = supported_every(7);
+    expected.sort();
+    assert_eq!(result.valid_transforms, expected);
+    assert!(!result.rejected_transforms.is_empty());
+}
+
+#[tokio::test]
+async fn nat_t_vendor_id_is_detected() {
+    let responder = Arc::new(
+        ScriptedResponder::new(Behavior::PickOne, supported_every(7))
+            .vendor_id(*b"unrelated vendor")
+            .vendor_id(NAT_T_VENDOR_IDS[0]),
+    );
+
+    let result = scan_with_transport(scan_options(), responder)
+        .await
+        .unwrap();
+
+    assert!(result.nat_t_vendor_id);
+    assert_eq!(result.valid_transforms.len(), supported_every(7).len());
+}
+
+#[tokio::test]
+async fn without_vendor_id_nat_t_is_not_reported() {
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::PickOne,
+        supported_every(7),
+    ));
+
+    let result = scan_with_transport(scan_options(), responder)
+        .await
+        .unwrap();
+
+    assert!(!result.nat_t_vendor_id);
+}
@@ -0,0 +1,82 @@
+//! Handling of notifications other than NO-PROPOSAL-CHOSEN
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use ikebuster::planned_transforms;
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::IncompleteReason;
+use ikebuster::ScanOptions;
+use ikebuster::TransformStatus;
+use isakmp::v1::definitions::NotifyMessageType;
+
+#[tokio::test]
+async fn error_notification_gives_up_proposal() {
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::Notifies(NotifyMessageType::PayloadMalformed),
+        vec![],
+    ));
+    let opts = scan_options();
+    let response_timeout = opts.response_timeout;
+
+    let started = Instant::now();
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    // The scan doesn't wait for further responses to the proposals
+    assert!(started.elapsed() < response_timeout);
+    assert_eq!(result.notifications, [NotifyMessageType::PayloadMalformed]);
+    assert!(result.valid_transforms.is_empty());
+    assert!(result.rejected_transforms.is_empty());
+    assert_eq!(
+        result.tested_transforms,
+        planned_transforms(&scan_options())
+    );
+    // The proposals are given up instead of being lost
+    assert!(!result.completed);
+    assert!(result
+        .incomplete_reasons
+        .contains(&IncompleteReason::Unanswered));
+    assert!(result
+        .transform_statuses()
+        .iter()
+        .all(|x| x.status == TransformStatus::Unanswered));
+}
+
+#[tokio::test]
+async fn status_notification_doesnt_answer_proposal() {
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::Notifies(NotifyMessageType::ResponderLifetime),
+        vec![],
+    ));
+    let opts = ScanOptions {
+        response_timeout: Duration::from_millis(200),
+        ..scan_options()
+    };
+
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    assert_eq!(result.notifications, [NotifyMessageType::ResponderLifetime]);
+    assert!(!NotifyMessageType::ResponderLifetime.is_error());
+    assert_eq!(
+        result.unanswered_transforms.len(),
+        planned_transforms(&scan_options()).len()
+    );
+}
+
+#[tokio::test]
+async fn no_proposal_chosen_is_not_recorded() {
+    let responder = Arc::new(ScriptedResponder::new(Behavior::RejectsAll, vec![]));
+
+    let result = scan_with_transport(scan_options(), responder)
+        .await
+        .unwrap();
+
+    assert!(result.notifications.is_empty());
+    assert!(!result.rejected_transforms.is_empty());
+}
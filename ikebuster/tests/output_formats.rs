@@ -0,0 +1,54 @@
+use std::net::SocketAddr;
+
+use ikebuster::utils::formatting::format_csv_row;
+use ikebuster::utils::formatting::format_grepable;
+use ikebuster::utils::formatting::CSV_HEADER;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+
+fn transform(encryption_algorithm: EncryptionAlgorithm, key_size: Option<u16>) -> Transform {
+    Transform {
+        encryption_algorithm,
+        hash_algorithm: HashAlgorithm::SHA,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_1024,
+        key_size,
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn csv_row_matches_header() {
+    let target: SocketAddr = "192.0.2.1:500".parse().unwrap();
+    let with_key_size = format_csv_row(target, &transform(EncryptionAlgorithm::AES_CBC, Some(256)));
+    let without_key_size = format_csv_row(target, &transform(EncryptionAlgorithm::DES_CBC, None));
+
+    let columns = CSV_HEADER.split(',').count();
+    assert_eq!(with_key_size.split(',').count(), columns);
+    assert_eq!(without_key_size.split(',').count(), columns);
+    assert!(with_key_size.starts_with("192.0.2.1:500,"));
+    assert_eq!(with_key_size.split(',').nth(2), Some("256"));
+    assert_eq!(without_key_size.split(',').nth(2), Some(""));
+}
+
+#[test]
+fn grepable_is_a_single_line_per_host() {
+    let target: SocketAddr = "192.0.2.1:500".parse().unwrap();
+    let line = format_grepable(
+        target,
+        &[
+            transform(EncryptionAlgorithm::AES_CBC, Some(256)),
+            transform(EncryptionAlgorithm::DES_CBC, None),
+        ],
+    );
+
+    assert!(!line.contains('\n'));
+    assert!(line.starts_with("host=192.0.2.1 port=500 ENC="));
+    assert!(line.contains("/256 HASH="));
+    assert_eq!(line.matches("; ENC=").count(), 1);
+
+    assert_eq!(format_grepable(target, &[]), "host=192.0.2.1 port=500");
+}
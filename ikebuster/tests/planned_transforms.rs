@@ -0,0 +1,241 @@
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use ikebuster::planned_messages;
+use ikebuster::planned_proposals;
+use ikebuster::planned_transforms;
+use ikebuster::utils::gen_transforms::weak_components;
+use ikebuster::utils::gen_transforms::TransformFilter;
+use ikebuster::utils::grading::grade_transform;
+use ikebuster::utils::grading::Grade;
+use ikebuster::utils::record::message_transforms;
+use ikebuster::ScanOptions;
+use ikebuster::DEFAULT_RECEIVE_CAPACITY;
+use isakmp::strum::IntoEnumIterator;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+
+fn options() -> ScanOptions {
+    ScanOptions {
+        ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        scope_id: 0,
+        port: 500,
+        source_port: 500,
+        nat_t: false,
+        interval: 500,
+        jitter: Duration::ZERO,
+        response_timeout: Duration::from_secs(10),
+        receive_capacity: DEFAULT_RECEIVE_CAPACITY,
+        max_retries: 2,
+        retransmit_interval: Duration::from_secs(5),
+        transform_no: 20,
+        sleep_on_transform_found: Duration::from_secs(45),
+        rate_limit: None,
+        bind_retries: 0,
+        interface: None,
+        ttl: None,
+        dont_fragment: false,
+        verify_findings: false,
+        stop_on_first_accept: false,
+        max_transforms: None,
+        transform_filter: TransformFilter::default(),
+        transforms: None,
+        attribute_order: vec![],
+        max_message_size: None,
+        record: false,
+        replay: None,
+        probe_exchange_types: false,
+        progress: None,
+        cancel: None,
+        #[cfg(feature = "socks5")]
+        proxy: None,
+    }
+}
+
+#[test]
+fn default_options_test_every_combination() {
+    // Every variant except reserved. AES, Camellia, Blowfish and RC5 are tested with
+    // three key sizes, CAST only supports 128 bit of them.
+    let encryption_algorithms = EncryptionAlgorithm::iter().count() - 1 + 4 * 2;
+    let hash_algorithms = HashAlgorithm::iter().count() - 1;
+    let authentication_methods = AuthenticationMethod::iter().count() - 1;
+    let groups = GroupDescription::iter().count() - 1;
+
+    assert_eq!(
+        planned_transforms(&options()).len(),
+        encryption_algorithms * hash_algorithms * authentication_methods * groups
+    );
+}
+
+#[test]
+fn max_transforms_limits_planned_transforms() {
+    let opts = ScanOptions {
+        max_transforms: Some(42),
+        ..options()
+    };
+
+    assert_eq!(planned_transforms(&opts).len(), 42);
+}
+
+#[test]
+fn only_weak_skips_strong_transforms() {
+    let mut opts = options();
+    opts.transform_filter.only_weak = true;
+
+    let transforms = planned_transforms(&opts);
+    assert!(!transforms.is_empty());
+    assert!(transforms.len() < planned_transforms(&options()).len());
+    assert!(transforms.iter().all(|x| weak_components(x) > 0));
+}
+
+#[test]
+fn only_weak_includes_triple_des() {
+    let filter = TransformFilter {
+        only_weak: true,
+        ..Default::default()
+    };
+    let transform = Transform {
+        encryption_algorithm: EncryptionAlgorithm::TrippleDES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_2048,
+        key_size: None,
+        extra_attributes: vec![],
+    };
+
+    // The filter agrees with the grading
+    assert_eq!(grade_transform(&transform), Grade::Weak);
+    assert_eq!(weak_components(&transform), 1);
+    assert!(filter.includes(&transform));
+}
+
+#[test]
+fn selected_algorithms_limit_planned_transforms() {
+    let mut opts = options();
+    opts.transform_filter.select("aes_cbc", true).unwrap();
+    opts.transform_filter.select("MD5", false).unwrap();
+    opts.transform_filter.select("PreSharedKey", true).unwrap();
+
+    let transforms = planned_transforms(&opts);
+    assert!(!transforms.is_empty());
+    assert!(transforms.iter().all(|x| {
+        x.encryption_algorithm == EncryptionAlgorithm::AES_CBC
+            && x.hash_algorithm != HashAlgorithm::MD5
+            && x.authentication_method == AuthenticationMethod::PreSharedKey
+    }));
+
+    assert!(opts.transform_filter.select("ROT13", true).is_err());
+}
+
+#[test]
+fn key_sizes_can_be_overridden() {
+    let mut opts = options();
+    opts.transform_filter.key_sizes = Some(vec![64, 128]);
+    let transforms = planned_transforms(&opts);
+
+    let key_sizes = |encryption_algorithm| {
+        let mut key_sizes: Vec<_> = transforms
+            .iter()
+            .filter(|x| x.encryption_algorithm == encryption_algorithm)
+            .map(|x| x.key_size)
+            .collect();
+        key_sizes.sort();
+        key_sizes.dedup();
+        key_sizes
+    };
+    assert_eq!(key_sizes(EncryptionAlgorithm::AES_CBC), [Some(128)]);
+    assert_eq!(
+        key_sizes(EncryptionAlgorithm::CAST_CBC),
+        [Some(64), Some(128)]
+    );
+    assert_eq!(key_sizes(EncryptionAlgorithm::TrippleDES_CBC), [None]);
+}
+
+#[test]
+fn given_transforms_replace_generated_ones() {
+    let transforms: Vec<Transform> = serde_json::from_str(
+        r#"[
+            {
+                "encryption_algorithm": "AES_CBC",
+                "hash_algorithm": "SHA2_256",
+                "authentication_method": "PreSharedKey",
+                "group_description": "MODP_2048",
+                "key_size": 256
+            },
+            {
+                "encryption_algorithm": "DES_CBC",
+                "hash_algorithm": "MD5",
+                "authentication_method": "PreSharedKey",
+                "group_description": "MODP_768",
+                "key_size": null
+            }
+        ]"#,
+    )
+    .unwrap();
+    let mut opts = ScanOptions {
+        transforms: Some(transforms.clone()),
+        ..options()
+    };
+    // The filter only applies to the generated transforms
+    opts.transform_filter.only_weak = true;
+
+    assert_eq!(planned_transforms(&opts), transforms);
+}
+
+#[test]
+fn planned_proposals_are_split_by_message_size() {
+    let opts = ScanOptions {
+        max_transforms: Some(20),
+        transform_no: 20,
+        ..options()
+    };
+    assert_eq!(planned_proposals(&opts).len(), 1);
+
+    // 20 transforms don't fit into 500 bytes
+    let opts = ScanOptions {
+        max_message_size: Some(500),
+        ..opts
+    };
+    let proposals = planned_proposals(&opts);
+    assert!(proposals.len() > 1);
+    assert_eq!(proposals.concat(), planned_transforms(&opts));
+    assert!(planned_messages(&opts)
+        .iter()
+        .all(|(_, msg)| msg.len() <= 500));
+}
+
+#[test]
+fn planned_messages_carry_planned_transforms() {
+    let opts = ScanOptions {
+        max_transforms: Some(20),
+        transform_no: 8,
+        ..options()
+    };
+    let messages = planned_messages(&opts);
+
+    assert_eq!(messages.len(), 3);
+    for (transforms, msg) in &messages {
+        assert_eq!(&message_transforms(msg).unwrap(), transforms);
+    }
+    let transforms: Vec<_> = messages.into_iter().flat_map(|(x, _)| x).collect();
+    assert_eq!(transforms, planned_transforms(&opts));
+}
+
+#[test]
+fn planned_messages_with_nat_t_carry_non_esp_marker() {
+    let opts = ScanOptions {
+        nat_t: true,
+        max_transforms: Some(8),
+        ..options()
+    };
+    let messages = planned_messages(&opts);
+
+    assert_eq!(messages.len(), 1);
+    let (transforms, msg) = &messages[0];
+    assert_eq!(msg[..4], [0; 4]);
+    assert_eq!(&message_transforms(&msg[4..]).unwrap(), transforms);
+}
@@ -0,0 +1,81 @@
+use ikebuster::utils::progress::ScanProgress;
+use ikebuster::ScanEvent;
+use ikebuster::ServerQuirk;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+
+fn transform(encryption_algorithm: EncryptionAlgorithm) -> Transform {
+    Transform {
+        encryption_algorithm,
+        hash_algorithm: HashAlgorithm::SHA,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_1024,
+        key_size: None,
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn progress_from_events() {
+    let des = transform(EncryptionAlgorithm::DES_CBC);
+    let idea = transform(EncryptionAlgorithm::IDEA_CBC);
+
+    let mut progress = ScanProgress::default();
+    for event in [
+        ScanEvent::ProposalSent {
+            transforms: vec![des.clone(), idea.clone()],
+            remaining: 3,
+        },
+        ScanEvent::TransformAccepted(des.clone()),
+        ScanEvent::QuirkDetected(ServerQuirk::MultipleTransformsReturned),
+        ScanEvent::ProposalSent {
+            transforms: vec![idea.clone()],
+            remaining: 3,
+        },
+        ScanEvent::ProposalRejected(vec![idea]),
+        ScanEvent::TransformAccepted(des.clone()),
+    ] {
+        progress.apply(event);
+    }
+
+    assert_eq!(progress.sent, 2);
+    assert_eq!(progress.remaining, 3);
+    assert_eq!(progress.rejected, 1);
+    assert_eq!(progress.accepted, [des]);
+    assert_eq!(progress.quirks, [ServerQuirk::MultipleTransformsReturned]);
+    assert_eq!(progress.ratio(), 0.4);
+}
+
+#[cfg(feature = "test-util")]
+#[tokio::test]
+async fn progress_of_scan() {
+    use std::sync::Arc;
+
+    use ikebuster::planned_transforms;
+    use ikebuster::scan_with_transport;
+    use ikebuster::testing::scan_options;
+    use ikebuster::testing::Behavior;
+    use ikebuster::testing::ScriptedResponder;
+    use ikebuster::utils::progress::watch_progress;
+
+    let supported: Vec<Transform> = planned_transforms(&scan_options())
+        .into_iter()
+        .step_by(9)
+        .collect();
+    let responder = Arc::new(ScriptedResponder::new(Behavior::PickOne, supported.clone()));
+    let mut opts = scan_options();
+    let mut progress = watch_progress(&mut opts);
+
+    let result = scan_with_transport(opts, responder.clone()).await.unwrap();
+    // The receiver is closed once all events are applied
+    while progress.changed().await.is_ok() {}
+
+    let progress = progress.borrow();
+    assert_eq!(progress.sent, responder.proposals().len());
+    assert_eq!(progress.remaining, 0);
+    assert_eq!(progress.accepted.len(), result.valid_transforms.len());
+    assert_eq!(progress.accepted.len(), supported.len());
+}
@@ -0,0 +1,76 @@
+//! Attributing acceptances to proposals by their proposal number
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::ScanResult;
+use ikebuster::ServerQuirk;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::PROPOSAL_NO;
+use tokio::time::timeout;
+
+/// Scan a server accepting proposals with the given proposal number,
+/// returning the result and the number of sent proposals
+async fn run(proposal_no: u8) -> (ScanResult, usize) {
+    let responder = Arc::new(
+        ScriptedResponder::new(Behavior::PickOne, supported_every(5)).proposal_no(proposal_no),
+    );
+    let result = timeout(
+        Duration::from_secs(10),
+        scan_with_transport(scan_options(), responder.clone()),
+    )
+    .await
+    .expect("The scan didn't terminate")
+    .unwrap();
+    (result, responder.proposals().len())
+}
+
+fn expected() -> Vec<Transform> {
+    let mut expected = supported_every(5);
+    expected.sort();
+    expected
+}
+
+#[tokio::test]
+async fn offered_proposal_number() {
+    let (result, _) = run(PROPOSAL_NO).await;
+
+    assert_eq!(result.valid_transforms, expected());
+    assert!(result.quirks.is_empty());
+}
+
+#[tokio::test]
+async fn other_proposal_number() {
+    let (reference, sent) = run(PROPOSAL_NO).await;
+    let (result, other_sent) = run(PROPOSAL_NO + 1).await;
+
+    // The only offered proposal is the accepted one, nothing is sent again
+    assert_eq!(result.valid_transforms, expected());
+    assert_eq!(result.rejected_transforms, reference.rejected_transforms);
+    assert_eq!(other_sent, sent);
+    assert_eq!(
+        result.quirks,
+        [ServerQuirk::UnknownProposalNumber {
+            proposal_no: PROPOSAL_NO + 1
+        }]
+    );
+}
+
+#[tokio::test]
+async fn unknown_proposal_number() {
+    let (result, _) = run(0).await;
+
+    assert_eq!(result.valid_transforms, expected());
+    assert!(result.unanswered_transforms.is_empty());
+    assert_eq!(
+        result.quirks,
+        [ServerQuirk::UnknownProposalNumber { proposal_no: 0 }]
+    );
+}
@@ -0,0 +1,56 @@
+//! Backing off when the server stops responding
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::RateLimitOptions;
+use ikebuster::ServerQuirk;
+
+#[tokio::test]
+async fn backs_off_and_recovers() {
+    let responder = Arc::new(
+        ScriptedResponder::new(Behavior::PickOne, supported_every(7))
+            .rate_limit(4, Duration::from_millis(30)),
+    );
+    let mut opts = scan_options();
+    opts.rate_limit = Some(RateLimitOptions {
+        max_unanswered: 2,
+        backoff: Duration::from_millis(20),
+        max_backoff: Duration::from_secs(5),
+    });
+
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    let mut expected = supported_every(7);
+    expected.sort();
+    assert_eq!(result.valid_transforms, expected);
+    assert!(result.quirks.iter().any(
+        |quirk| matches!(quirk, ServerQuirk::RateLimited { backoff_ms } if *backoff_ms >= 20)
+    ));
+}
+
+#[tokio::test]
+async fn no_quirk_without_gaps() {
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::PickOne,
+        supported_every(7),
+    ));
+    let mut opts = scan_options();
+    opts.rate_limit = Some(RateLimitOptions {
+        max_unanswered: 4,
+        backoff: Duration::from_millis(20),
+        max_backoff: Duration::from_secs(5),
+    });
+
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    assert_eq!(result.valid_transforms.len(), supported_every(7).len());
+    assert!(result.quirks.is_empty());
+}
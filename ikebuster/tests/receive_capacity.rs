@@ -0,0 +1,35 @@
+//! Bounding the queue of received messages
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::ScanOptions;
+
+#[tokio::test]
+async fn scan_completes_with_minimal_capacity() {
+    for receive_capacity in [0, 1] {
+        let responder = Arc::new(ScriptedResponder::new(
+            Behavior::Reorders,
+            supported_every(6),
+        ));
+        let opts = ScanOptions {
+            receive_capacity,
+            ..scan_options()
+        };
+
+        let result = scan_with_transport(opts, responder).await.unwrap();
+
+        let mut valid = result.valid_transforms;
+        valid.sort();
+        let mut expected = supported_every(6);
+        expected.sort();
+        assert_eq!(valid, expected);
+        assert!(result.unanswered_transforms.is_empty());
+    }
+}
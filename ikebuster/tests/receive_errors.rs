@@ -0,0 +1,97 @@
+//! Continuing on transient errors of the socket and aborting on fatal ones
+
+#![cfg(feature = "test-util")]
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::transport::IkeTransport;
+use ikebuster::utils::socket_errors::is_transient;
+use ikebuster::ScanError;
+
+/// A [ScriptedResponder] whose first receives fail with the given errors
+struct FailingTransport {
+    responder: ScriptedResponder,
+    errors: Mutex<VecDeque<io::ErrorKind>>,
+}
+
+impl FailingTransport {
+    fn new(errors: impl IntoIterator<Item = io::ErrorKind>) -> Self {
+        Self {
+            responder: ScriptedResponder::new(Behavior::PickOne, supported_every(7)),
+            errors: Mutex::new(errors.into_iter().collect()),
+        }
+    }
+}
+
+impl IkeTransport for FailingTransport {
+    async fn send(&self, datagram: &[u8]) -> io::Result<()> {
+        self.responder.send(datagram).await
+    }
+
+    async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        let error = self.errors.lock().unwrap().pop_front();
+        match error {
+            Some(kind) => Err(io::Error::from(kind)),
+            None => self.responder.recv(buf).await,
+        }
+    }
+}
+
+#[tokio::test]
+async fn transient_error_continues_scan() {
+    let transport = Arc::new(FailingTransport::new([
+        io::ErrorKind::WouldBlock,
+        io::ErrorKind::Interrupted,
+    ]));
+
+    let result = scan_with_transport(scan_options(), transport.clone())
+        .await
+        .unwrap();
+
+    assert!(transport.errors.lock().unwrap().is_empty());
+    let mut expected = supported_every(7);
+    expected.sort();
+    assert_eq!(result.valid_transforms, expected);
+}
+
+#[tokio::test]
+async fn fatal_error_aborts_scan() {
+    let transport = Arc::new(FailingTransport::new([io::ErrorKind::ConnectionReset]));
+
+    let result = scan_with_transport(scan_options(), transport).await;
+
+    match result {
+        Err(ScanError::Receive(err)) => assert_eq!(err.kind(), io::ErrorKind::ConnectionReset),
+        other => panic!("Expected a receive error, got {other:?}"),
+    }
+}
+
+#[test]
+fn transient_errors() {
+    for kind in [
+        io::ErrorKind::WouldBlock,
+        io::ErrorKind::Interrupted,
+        io::ErrorKind::TimedOut,
+    ] {
+        assert!(is_transient(&io::Error::from(kind)), "{kind:?}");
+    }
+}
+
+#[test]
+fn fatal_errors() {
+    for kind in [
+        io::ErrorKind::ConnectionReset,
+        io::ErrorKind::ConnectionRefused,
+        io::ErrorKind::PermissionDenied,
+    ] {
+        assert!(!is_transient(&io::Error::from(kind)), "{kind:?}");
+    }
+}
@@ -0,0 +1,47 @@
+use ikebuster::utils::gen_transforms::gen_v1_transforms;
+use ikebuster::utils::gen_transforms::TransformFilter;
+use ikebuster::utils::record::message_transforms;
+use ikebuster::utils::record::read_messages;
+use ikebuster::utils::record::set_initiator_cookie;
+use ikebuster::utils::record::write_messages;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+
+fn build(transforms: &[Transform]) -> Vec<u8> {
+    let mut mb = MessageBuilder::new();
+    for transform in transforms {
+        mb = mb.add_transform(transform.clone());
+    }
+    mb.build().0
+}
+
+#[test]
+fn replayed_messages_match_recorded_messages() {
+    let proposals: Vec<_> = gen_v1_transforms(&TransformFilter::default(), 5)
+        .into_iter()
+        .take(2)
+        .collect();
+    let recorded: Vec<_> = proposals.iter().map(|x| build(x)).collect();
+
+    let mut record = vec![];
+    write_messages(&mut record, &recorded).unwrap();
+    let replayed = read_messages(record.as_slice()).unwrap();
+    assert_eq!(replayed, recorded);
+
+    for ((mut msg, recorded), transforms) in replayed.into_iter().zip(&recorded).zip(&proposals) {
+        assert_eq!(&message_transforms(&msg).unwrap(), transforms);
+
+        set_initiator_cookie(&mut msg, 0x0102_0304_0506_0708);
+        assert_eq!(msg[..8], [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(msg[8..], recorded[8..]);
+    }
+}
+
+#[test]
+fn truncated_record_is_rejected() {
+    let mut record = vec![];
+    write_messages(&mut record, &[vec![0; 16]]).unwrap();
+    record.pop();
+
+    assert!(read_messages(record.as_slice()).is_err());
+}
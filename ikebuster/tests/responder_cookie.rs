@@ -0,0 +1,37 @@
+//! Reporting the responder cookie of the server
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use ikebuster::planned_transforms;
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::testing::RESPONDER_COOKIE;
+
+#[tokio::test]
+async fn responder_cookie_of_accepting_response() {
+    let supported = planned_transforms(&scan_options())[..2].to_vec();
+    let responder = Arc::new(ScriptedResponder::new(Behavior::PickOne, supported));
+
+    let result = scan_with_transport(scan_options(), responder)
+        .await
+        .unwrap();
+
+    assert!(!result.valid_transforms.is_empty());
+    assert_eq!(result.responder_cookie, Some(RESPONDER_COOKIE));
+}
+
+#[tokio::test]
+async fn no_responder_cookie_without_acceptance() {
+    let responder = Arc::new(ScriptedResponder::new(Behavior::RejectsAll, vec![]));
+
+    let result = scan_with_transport(scan_options(), responder)
+        .await
+        .unwrap();
+
+    assert!(result.valid_transforms.is_empty());
+    assert_eq!(result.responder_cookie, None);
+}
@@ -0,0 +1,45 @@
+//! Giving up proposals the server never responds to
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::IncompleteReason;
+
+#[tokio::test]
+async fn silent_server_does_not_stall_the_scan() {
+    // The server stops answering for good after the first proposals
+    let responder = Arc::new(
+        ScriptedResponder::new(Behavior::PickOne, supported_every(7))
+            .rate_limit(2, Duration::from_secs(3600)),
+    );
+    let mut opts = scan_options();
+    opts.response_timeout = Duration::from_millis(100);
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        scan_with_transport(opts, responder.clone()),
+    )
+    .await
+    .expect("scan stalled")
+    .unwrap();
+
+    assert!(result.responded);
+    assert!(!result.completed);
+    assert!(result
+        .incomplete_reasons
+        .contains(&IncompleteReason::Unanswered));
+    assert!(result.valid_transforms.len() < supported_every(7).len());
+    assert!(!result.unanswered_transforms.is_empty());
+    for transform in &result.unanswered_transforms {
+        assert!(!result.valid_transforms.contains(transform));
+        assert!(!result.rejected_transforms.contains(transform));
+    }
+    assert!(responder.proposals().len() > 2);
+}
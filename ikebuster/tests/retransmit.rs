@@ -0,0 +1,59 @@
+//! Sending unanswered proposals again
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::ScanOptions;
+
+fn options(max_retries: usize) -> ScanOptions {
+    let mut opts = scan_options();
+    opts.max_retries = max_retries;
+    opts.retransmit_interval = Duration::from_millis(30);
+    opts.response_timeout = Duration::from_millis(200);
+    opts
+}
+
+#[tokio::test]
+async fn lost_responses_are_recovered() {
+    let responder =
+        Arc::new(ScriptedResponder::new(Behavior::PickOne, supported_every(5)).drop_every(3));
+
+    let result = scan_with_transport(options(2), responder).await.unwrap();
+
+    let mut expected = supported_every(5);
+    expected.sort();
+    assert_eq!(result.valid_transforms, expected);
+}
+
+#[tokio::test]
+async fn without_retries_lost_responses_are_given_up() {
+    let responder =
+        Arc::new(ScriptedResponder::new(Behavior::PickOne, supported_every(5)).drop_every(3));
+
+    let result = scan_with_transport(options(0), responder.clone())
+        .await
+        .unwrap();
+
+    assert!(result.valid_transforms.len() < supported_every(5).len());
+    assert!(!result.unanswered_transforms.is_empty());
+}
+
+#[tokio::test]
+async fn retries_are_limited() {
+    let responder =
+        Arc::new(ScriptedResponder::new(Behavior::PickOne, supported_every(5)).drop_every(1));
+    let mut opts = options(2);
+    opts.max_transforms = Some(8);
+
+    scan_with_transport(opts, responder.clone()).await.unwrap();
+
+    // The only proposal of 8 transforms is sent three times
+    assert_eq!(responder.proposals().len(), 3);
+}
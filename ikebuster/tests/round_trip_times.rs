@@ -0,0 +1,74 @@
+//! Measuring the round-trip times of the proposals
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::RoundTripTimes;
+
+#[test]
+fn summary_of_samples() {
+    let samples = [
+        Duration::from_millis(10),
+        Duration::from_millis(30),
+        Duration::from_millis(20),
+    ];
+    assert_eq!(
+        RoundTripTimes::from_samples(&samples),
+        Some(RoundTripTimes {
+            min: Duration::from_millis(10),
+            max: Duration::from_millis(30),
+            avg: Duration::from_millis(20),
+            count: 3,
+        })
+    );
+    assert_eq!(RoundTripTimes::from_samples(&[]), None);
+}
+
+#[tokio::test]
+async fn every_response_is_measured() {
+    let responder = Arc::new(ScriptedResponder::new(Behavior::RejectsAll, vec![]));
+    let opts = scan_options();
+    let proposals = opts.max_transforms.unwrap().div_ceil(opts.transform_no);
+
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    let rtt = result.round_trip_times.unwrap();
+    assert_eq!(rtt.count, proposals);
+    assert!(rtt.min <= rtt.avg && rtt.avg <= rtt.max);
+}
+
+#[tokio::test]
+async fn accepted_transforms_carry_round_trip_time() {
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::PickOne,
+        supported_every(9),
+    ));
+
+    let result = scan_with_transport(scan_options(), responder)
+        .await
+        .unwrap();
+
+    assert!(!result.choices.is_empty());
+    assert!(result.choices.iter().all(|x| x.rtt.is_some()));
+}
+
+#[tokio::test]
+async fn unanswered_proposals_are_not_measured() {
+    let responder =
+        Arc::new(ScriptedResponder::new(Behavior::PickOne, supported_every(9)).drop_every(1));
+    let opts = ikebuster::ScanOptions {
+        response_timeout: Duration::from_millis(100),
+        ..scan_options()
+    };
+
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    assert_eq!(result.round_trip_times, None);
+}
@@ -0,0 +1,29 @@
+use ikebuster::utils::gen_transforms::gen_v1_transform_list;
+use ikebuster::utils::gen_transforms::truncate_transforms;
+use ikebuster::utils::gen_transforms::TransformFilter;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+
+#[test]
+fn truncation_marks_scan_incomplete() {
+    let mut transforms = gen_v1_transform_list(&TransformFilter::default());
+    let total = transforms.len();
+
+    assert!(truncate_transforms(&mut transforms, 10));
+    assert_eq!(transforms.len(), 10);
+
+    let mut transforms = gen_v1_transform_list(&TransformFilter::default());
+    assert!(!truncate_transforms(&mut transforms, total));
+    assert_eq!(transforms.len(), total);
+}
+
+#[test]
+fn truncation_prefers_weak_transforms() {
+    let mut transforms = gen_v1_transform_list(&TransformFilter::default());
+    truncate_transforms(&mut transforms, 10);
+
+    for transform in &transforms {
+        assert_ne!(transform.hash_algorithm, HashAlgorithm::SHA2_512);
+        assert_ne!(transform.group_description, GroupDescription::MODP_8192);
+    }
+}
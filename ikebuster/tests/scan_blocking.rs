@@ -0,0 +1,40 @@
+//! Scanning without an async runtime
+
+#![cfg(feature = "test-util")]
+
+use std::net::UdpSocket;
+use std::thread;
+use std::time::Duration;
+
+use ikebuster::scan_blocking;
+use ikebuster::testing::scan_options;
+use ikebuster::ScanOptions;
+
+#[test]
+fn scan_without_runtime() {
+    // A server that receives the proposals, but never answers
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    let receiver = thread::spawn(move || {
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut buf = [0; 4096];
+        server.recv(&mut buf).is_ok()
+    });
+
+    let opts = ScanOptions {
+        ip: addr.ip(),
+        port: addr.port(),
+        source_port: 0,
+        max_transforms: Some(8),
+        response_timeout: Duration::from_millis(100),
+        ..scan_options()
+    };
+    let result = scan_blocking(opts).unwrap();
+
+    assert!(receiver.join().unwrap());
+    assert_eq!(result.target, Some(addr));
+    assert!(!result.completed);
+    assert_eq!(result.unanswered_transforms.len(), 8);
+}
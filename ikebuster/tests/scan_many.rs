@@ -0,0 +1,124 @@
+//! Scanning multiple targets with limits
+
+#![cfg(feature = "test-util")]
+
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ikebuster::many::scan_many_with;
+use ikebuster::many::HostOutcome;
+use ikebuster::many::ScanManyOptions;
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::supported_every;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::ScanOptions;
+
+/// The target which never finishes its scan
+const SLOW: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+
+fn targets() -> Vec<SocketAddr> {
+    (1..=3)
+        .map(|x| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 0, 2, x)), 500))
+        .collect()
+}
+
+async fn scan(opts: ScanOptions) -> Result<ikebuster::ScanResult, ikebuster::ScanError> {
+    if opts.ip == SLOW {
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+    }
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::PickOne,
+        supported_every(9),
+    ));
+    scan_with_transport(opts, responder).await
+}
+
+#[tokio::test]
+async fn one_host_times_out() {
+    let limits = ScanManyOptions {
+        timeout_per_host: Some(Duration::from_secs(1)),
+        budget: None,
+        concurrency: 1,
+    };
+    let results = scan_many_with(scan_options(), targets(), limits, scan).await;
+
+    assert_eq!(
+        results.iter().map(|x| x.target).collect::<Vec<_>>(),
+        targets()
+    );
+    for result in &results {
+        match &result.outcome {
+            HostOutcome::TimedOut => assert_eq!(result.target.ip(), SLOW),
+            HostOutcome::Completed(res) => {
+                assert_ne!(result.target.ip(), SLOW);
+                assert_eq!(res.target, Some(result.target));
+                assert_eq!(res.valid_transforms.len(), supported_every(9).len());
+            }
+            outcome => panic!("Unexpected outcome of {}: {outcome:?}", result.target),
+        }
+    }
+}
+
+#[tokio::test]
+async fn exhausted_budget_skips_remaining_hosts() {
+    let limits = ScanManyOptions {
+        timeout_per_host: Some(Duration::from_millis(200)),
+        budget: Some(Duration::from_millis(100)),
+        concurrency: 1,
+    };
+    let mut targets = targets();
+    targets.sort_by_key(|target| target.ip() != SLOW);
+    let results = scan_many_with(scan_options(), targets, limits, scan).await;
+
+    assert_eq!(results[0].target.ip(), SLOW);
+    assert!(matches!(results[0].outcome, HostOutcome::TimedOut));
+    assert!(matches!(results[1].outcome, HostOutcome::Skipped));
+    assert!(matches!(results[2].outcome, HostOutcome::Skipped));
+}
+
+#[tokio::test]
+async fn concurrent_scans_start_before_budget_is_exhausted() {
+    let limits = ScanManyOptions {
+        timeout_per_host: Some(Duration::from_secs(2)),
+        budget: Some(Duration::from_millis(100)),
+        concurrency: 3,
+    };
+    let mut targets = targets();
+    targets.sort_by_key(|target| target.ip() != SLOW);
+    let results = scan_many_with(scan_options(), targets.clone(), limits, scan).await;
+
+    assert_eq!(
+        results.iter().map(|x| x.target).collect::<Vec<_>>(),
+        targets
+    );
+    assert!(matches!(results[0].outcome, HostOutcome::TimedOut));
+    assert!(matches!(results[1].outcome, HostOutcome::Completed(_)));
+    assert!(matches!(results[2].outcome, HostOutcome::Completed(_)));
+}
+
+#[tokio::test]
+async fn each_target_is_scanned_on_its_port() {
+    let targets = vec![
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)), 500),
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 3)), 4501),
+    ];
+    let results = scan_many_with(
+        scan_options(),
+        targets.clone(),
+        ScanManyOptions::default(),
+        scan,
+    )
+    .await;
+
+    for (result, target) in results.iter().zip(targets) {
+        let HostOutcome::Completed(res) = &result.outcome else {
+            panic!("Unexpected outcome of {target}: {:?}", result.outcome);
+        };
+        assert_eq!(res.target, Some(target));
+    }
+}
@@ -0,0 +1,39 @@
+//! Scanning link-local IPv6 targets
+
+use ikebuster::socket::scope_id;
+
+#[test]
+fn numeric_zone() {
+    assert_eq!(scope_id("3").unwrap(), 3);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn interface_name_zone() {
+    assert!(scope_id("lo").unwrap() > 0);
+    assert!(scope_id("does-not-exist0").is_err());
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn scope_id_is_applied_to_ipv6_targets() {
+    use std::net::IpAddr;
+    use std::net::Ipv4Addr;
+    use std::net::Ipv6Addr;
+    use std::net::SocketAddr;
+    use std::net::SocketAddrV6;
+
+    use ikebuster::testing::scan_options;
+
+    let link_local = Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
+    let mut opts = scan_options();
+    opts.ip = IpAddr::V6(link_local);
+    opts.scope_id = 4;
+    assert_eq!(
+        opts.target(),
+        SocketAddr::V6(SocketAddrV6::new(link_local, 500, 0, 4))
+    );
+
+    opts.ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+    assert_eq!(opts.target(), "192.0.2.1:500".parse().unwrap());
+}
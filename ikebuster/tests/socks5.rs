@@ -0,0 +1,113 @@
+//! Relaying datagrams through a SOCKS5 proxy
+
+#![cfg(feature = "socks5")]
+
+use std::net::SocketAddr;
+
+use ikebuster::proxy::decode_udp_header;
+use ikebuster::proxy::encode_udp_header;
+use ikebuster::proxy::udp_associate;
+use ikebuster::proxy::ProxyConfig;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::UdpSocket;
+
+#[test]
+fn udp_header_roundtrip() {
+    let target: SocketAddr = "192.0.2.1:500".parse().unwrap();
+    let datagram = encode_udp_header(target, b"ike");
+    assert_eq!(
+        datagram,
+        [0, 0, 0, 1, 192, 0, 2, 1, 0x01, 0xf4, b'i', b'k', b'e']
+    );
+    assert_eq!(
+        decode_udp_header(&datagram),
+        Some((target, b"ike".as_slice()))
+    );
+
+    let target: SocketAddr = "[2001:db8::1]:4500".parse().unwrap();
+    let datagram = encode_udp_header(target, b"ike");
+    assert_eq!(datagram.len(), 3 + 1 + 16 + 2 + 3);
+    assert_eq!(
+        decode_udp_header(&datagram),
+        Some((target, b"ike".as_slice()))
+    );
+}
+
+#[test]
+fn fragments_are_rejected() {
+    let mut datagram = encode_udp_header("192.0.2.1:500".parse().unwrap(), b"ike");
+    datagram[2] = 1;
+    assert_eq!(decode_udp_header(&datagram), None);
+    assert_eq!(decode_udp_header(&[0, 0, 0, 1, 192, 0]), None);
+}
+
+#[tokio::test]
+async fn relay_through_stub_proxy() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let relay = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let relay_port = relay.local_addr().unwrap().port();
+    let config = ProxyConfig {
+        addr: listener.local_addr().unwrap(),
+        credentials: Some(("user".to_string(), "secret".to_string())),
+    };
+
+    let proxy = tokio::spawn(async move {
+        let (mut control, _) = listener.accept().await.unwrap();
+
+        let mut greeting = [0; 3];
+        control.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(greeting, [5, 1, 2]);
+        control.write_all(&[5, 2]).await.unwrap();
+
+        let mut auth = [0; 1 + 1 + 4 + 1 + 6];
+        control.read_exact(&mut auth).await.unwrap();
+        assert_eq!(&auth, b"\x01\x04user\x06secret");
+        control.write_all(&[1, 0]).await.unwrap();
+
+        let mut request = [0; 10];
+        control.read_exact(&mut request).await.unwrap();
+        assert_eq!(request[..4], [5, 3, 0, 1]);
+        // Answer with an unspecified address, so the address of the proxy is used
+        let mut reply = vec![5, 0, 0, 1, 0, 0, 0, 0];
+        reply.extend_from_slice(&relay_port.to_be_bytes());
+        control.write_all(&reply).await.unwrap();
+
+        let mut buf = [0; 64];
+        let (len, client) = relay.recv_from(&mut buf).await.unwrap();
+        let (target, payload) = decode_udp_header(&buf[..len]).unwrap();
+        assert_eq!(payload, b"request");
+        relay
+            .send_to(&encode_udp_header(target, b"response"), client)
+            .await
+            .unwrap();
+
+        // Keep the association alive until the client is done
+        let _res = control.read(&mut buf).await;
+    });
+
+    let association = udp_associate(&config).await.unwrap();
+    assert_eq!(
+        association.relay,
+        SocketAddr::new(config.addr.ip(), relay_port)
+    );
+
+    let target: SocketAddr = "192.0.2.1:500".parse().unwrap();
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    socket.connect(association.relay).await.unwrap();
+    socket
+        .send(&encode_udp_header(target, b"request"))
+        .await
+        .unwrap();
+
+    let mut buf = [0; 64];
+    let len = socket.recv(&mut buf).await.unwrap();
+    assert_eq!(
+        decode_udp_header(&buf[..len]),
+        Some((target, b"response".as_slice()))
+    );
+
+    drop(association);
+    proxy.await.unwrap();
+}
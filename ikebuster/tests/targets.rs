@@ -0,0 +1,154 @@
+//! Parsing the targets given on the command line
+
+use std::net::IpAddr;
+use std::net::SocketAddr;
+
+use ikebuster::utils::targets::parse_targets;
+use ikebuster::utils::targets::parse_targets_file;
+use ikebuster::utils::targets::TargetError;
+use ikebuster::utils::targets::Targets;
+
+fn ips(ips: &[&str]) -> Vec<IpAddr> {
+    ips.iter().map(|x| x.parse().unwrap()).collect()
+}
+
+#[test]
+fn single_and_list() {
+    assert_eq!(parse_targets("192.0.2.1"), Ok(ips(&["192.0.2.1"])));
+    assert_eq!(
+        parse_targets("192.0.2.1, 2001:db8::1"),
+        Ok(ips(&["192.0.2.1", "2001:db8::1"]))
+    );
+    assert_eq!(
+        "192.0.2.1".parse::<Targets>(),
+        Ok(Targets {
+            ips: ips(&["192.0.2.1"]),
+            zone: None,
+        })
+    );
+}
+
+#[test]
+fn cidr_ranges() {
+    assert_eq!(
+        parse_targets("10.0.0.5/30,192.0.2.1"),
+        Ok(ips(&[
+            "10.0.0.4",
+            "10.0.0.5",
+            "10.0.0.6",
+            "10.0.0.7",
+            "192.0.2.1"
+        ]))
+    );
+    assert_eq!(parse_targets("10.0.0.0/24").unwrap().len(), 256);
+    assert_eq!(parse_targets("10.0.0.1/32"), Ok(ips(&["10.0.0.1"])));
+    assert_eq!(
+        parse_targets("2001:db8::/127"),
+        Ok(ips(&["2001:db8::", "2001:db8::1"]))
+    );
+}
+
+#[test]
+fn ranges_at_the_end_of_the_address_space() {
+    assert_eq!(
+        parse_targets("255.255.255.254/31"),
+        Ok(ips(&["255.255.255.254", "255.255.255.255"]))
+    );
+    assert_eq!(
+        parse_targets("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128"),
+        Ok(ips(&["ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"]))
+    );
+    let top = parse_targets("ffff:ffff:ffff:ffff:ffff:ffff:ffff:0/112").unwrap();
+    assert_eq!(top.len(), 1 << 16);
+    assert_eq!(
+        top.last(),
+        Some(&"ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff".parse().unwrap())
+    );
+    assert_eq!(parse_targets("ffff::/16"), Err(TargetError::TooManyTargets));
+}
+
+#[test]
+fn invalid_targets() {
+    assert_eq!(
+        parse_targets("192.0.2"),
+        Err(TargetError::InvalidAddress("192.0.2".to_string()))
+    );
+    assert_eq!(
+        parse_targets("10.0.0.0/33"),
+        Err(TargetError::InvalidPrefix("10.0.0.0/33".to_string()))
+    );
+    assert_eq!(parse_targets("192.0.2.1,"), Err(TargetError::Empty));
+    assert_eq!(
+        parse_targets("10.0.0.0/8"),
+        Err(TargetError::TooManyTargets)
+    );
+    assert_eq!(
+        parse_targets("2001:db8::/0"),
+        Err(TargetError::TooManyTargets)
+    );
+}
+
+#[test]
+fn zones() {
+    assert_eq!(
+        "fe80::1%eth0".parse::<Targets>(),
+        Ok(Targets {
+            ips: ips(&["fe80::1"]),
+            zone: Some("eth0".to_string()),
+        })
+    );
+    assert_eq!(
+        "fe80::%3/127, fe80::5%3".parse::<Targets>(),
+        Ok(Targets {
+            ips: ips(&["fe80::", "fe80::1", "fe80::5"]),
+            zone: Some("3".to_string()),
+        })
+    );
+}
+
+#[test]
+fn invalid_zones() {
+    assert_eq!(
+        "192.0.2.1%eth0".parse::<Targets>(),
+        Err(TargetError::InvalidZone("192.0.2.1%eth0".to_string()))
+    );
+    assert_eq!(
+        "fe80::1%".parse::<Targets>(),
+        Err(TargetError::InvalidZone("fe80::1%".to_string()))
+    );
+    assert_eq!(
+        "fe80::1%eth0,fe80::2%eth1".parse::<Targets>(),
+        Err(TargetError::MixedZones)
+    );
+    assert_eq!(
+        "fe80::1%eth0,192.0.2.1".parse::<Targets>(),
+        Err(TargetError::MixedZones)
+    );
+}
+
+#[test]
+fn targets_file() {
+    let file = "# Gateways\n192.0.2.1\n\n  192.0.2.2:4500\n[2001:db8::1]:501\n2001:db8::2\n";
+    let expected: Vec<SocketAddr> = [
+        "192.0.2.1:500",
+        "192.0.2.2:4500",
+        "[2001:db8::1]:501",
+        "[2001:db8::2]:500",
+    ]
+    .iter()
+    .map(|target| target.parse().unwrap())
+    .collect();
+    assert_eq!(parse_targets_file(file, 500), Ok(expected));
+}
+
+#[test]
+fn invalid_targets_file() {
+    assert_eq!(
+        parse_targets_file("192.0.2.1\n192.0.2.2:foo\n", 500),
+        Err(TargetError::InvalidLine(2, "192.0.2.2:foo".to_string()))
+    );
+    assert_eq!(
+        parse_targets_file("# Nothing to scan\n\n", 500),
+        Err(TargetError::Empty)
+    );
+}
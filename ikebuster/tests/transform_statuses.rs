@@ -0,0 +1,53 @@
+use ikebuster::ScanResult;
+use ikebuster::TransformStatus;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::Transform;
+
+fn transform(encryption_algorithm: EncryptionAlgorithm) -> Transform {
+    Transform {
+        encryption_algorithm,
+        hash_algorithm: HashAlgorithm::SHA,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_1024,
+        key_size: None,
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn statuses_of_tested_transforms() {
+    let result = ScanResult {
+        valid_transforms: vec![transform(EncryptionAlgorithm::DES_CBC)],
+        rejected_transforms: vec![transform(EncryptionAlgorithm::IDEA_CBC)],
+        unanswered_transforms: vec![transform(EncryptionAlgorithm::CAST_CBC)],
+        tested_transforms: vec![
+            transform(EncryptionAlgorithm::DES_CBC),
+            transform(EncryptionAlgorithm::IDEA_CBC),
+            transform(EncryptionAlgorithm::CAST_CBC),
+            transform(EncryptionAlgorithm::BlowfishCBC),
+        ],
+        ..Default::default()
+    };
+
+    let statuses: Vec<_> = result
+        .transform_statuses()
+        .into_iter()
+        .map(|x| (x.transform.encryption_algorithm, x.status))
+        .collect();
+
+    assert_eq!(
+        statuses,
+        [
+            (EncryptionAlgorithm::DES_CBC, TransformStatus::Accepted),
+            (EncryptionAlgorithm::IDEA_CBC, TransformStatus::Rejected),
+            (EncryptionAlgorithm::CAST_CBC, TransformStatus::Unanswered),
+            (
+                EncryptionAlgorithm::BlowfishCBC,
+                TransformStatus::Indeterminate
+            ),
+        ]
+    );
+}
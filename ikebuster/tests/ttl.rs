@@ -0,0 +1,24 @@
+//! Setting the TTL of outgoing packets
+
+use ikebuster::socket::set_ttl;
+use tokio::net::UdpSocket;
+
+#[tokio::test]
+async fn ipv4_ttl() {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    set_ttl(&socket, 3).unwrap();
+    assert_eq!(socket.ttl().unwrap(), 3);
+}
+
+#[tokio::test]
+async fn ipv6_hop_limit() {
+    // IPv6 may be disabled in the test environment
+    let Ok(socket) = UdpSocket::bind("[::1]:0").await else {
+        return;
+    };
+    set_ttl(&socket, 5).unwrap();
+    assert_eq!(
+        socket2::SockRef::from(&socket).unicast_hops_v6().unwrap(),
+        5
+    );
+}
@@ -0,0 +1,71 @@
+use ikebuster::utils::formatting::format_raw_transform;
+use ikebuster::utils::payload_to_transforms::payload_to_raw_transforms;
+use ikebuster::utils::payload_to_transforms::payload_to_transforms;
+use ikebuster::utils::payload_to_transforms::RawTransform;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::parser::parse_packet;
+
+/// Header (28) + SA payload (8) + situation (4)
+const PROPOSAL_OFFSET: usize = 40;
+
+/// Offset of the value of the encryption algorithm attribute
+const ENCRYPTION_ALGORITHM_OFFSET: usize = PROPOSAL_OFFSET + 8 + 8 + 2;
+
+/// A message with an AES-256 transform, whose encryption algorithm is of the private use range
+fn private_use_message() -> Vec<u8> {
+    let (mut msg, _) = MessageBuilder::new()
+        .add_transform(Transform {
+            encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+            hash_algorithm: HashAlgorithm::SHA2_256,
+            authentication_method: AuthenticationMethod::PreSharedKey,
+            group_description: GroupDescription::MODP_2048,
+            key_size: Some(256),
+            extra_attributes: vec![],
+        })
+        .build();
+    // Use an encryption algorithm of the private use range
+    msg[ENCRYPTION_ALGORITHM_OFFSET..ENCRYPTION_ALGORITHM_OFFSET + 2]
+        .copy_from_slice(&0xfff0u16.to_be_bytes());
+    msg
+}
+
+#[test]
+fn unknown_attribute_keeps_raw_proposal() {
+    let msg = private_use_message();
+    let packet = parse_packet(&msg).unwrap();
+    let proposal = &packet.security_associations[0].proposal_payload[0];
+
+    assert!(payload_to_transforms(proposal).is_err());
+    assert_eq!(
+        proposal.raw,
+        msg[PROPOSAL_OFFSET..PROPOSAL_OFFSET + proposal.length as usize]
+    );
+}
+
+#[test]
+fn unknown_attribute_keeps_raw_values() {
+    let packet = parse_packet(&private_use_message()).unwrap();
+    let proposal = &packet.security_associations[0].proposal_payload[0];
+
+    let raw = payload_to_raw_transforms(proposal);
+    assert_eq!(
+        raw,
+        [RawTransform {
+            transform_no: 0,
+            encryption_algorithm: Some(0xfff0),
+            hash_algorithm: Some(HashAlgorithm::SHA2_256 as u16),
+            authentication_method: Some(AuthenticationMethod::PreSharedKey as u16),
+            group_description: Some(GroupDescription::MODP_2048 as u16),
+            key_size: Some(256),
+        }]
+    );
+    assert_eq!(
+        format_raw_transform(&raw[0]),
+        "ENC=0xfff0 (unknown)/256 HASH=SHA2_256 AUTH=PreSharedKey GROUP=MODP_2048"
+    );
+}
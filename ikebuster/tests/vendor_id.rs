@@ -0,0 +1,64 @@
+use ikebuster::utils::vendor_id::describe_vendor_id;
+use ikebuster::utils::vendor_id::identify_vendor_id;
+use ikebuster::utils::vendor_id::KNOWN_VENDOR_IDS;
+use ikebuster::NAT_T_VENDOR_IDS;
+
+#[test]
+fn known_vendor_ids_are_valid_hex() {
+    for (name, prefix) in KNOWN_VENDOR_IDS {
+        assert!(prefix.len().is_multiple_of(2), "{name}");
+        assert!(
+            prefix.chars().all(|x| matches!(x, '0'..='9' | 'a'..='f')),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn nat_t_vendor_ids_are_known() {
+    for vendor_id in NAT_T_VENDOR_IDS {
+        assert!(identify_vendor_id(&vendor_id).is_some());
+    }
+}
+
+#[test]
+fn vendor_ids_match_by_prefix() {
+    // Cisco Unity, followed by its version
+    let vendor_id = [
+        0x12, 0xf5, 0xf2, 0x8c, 0x45, 0x71, 0x68, 0xa9, 0x70, 0x2d, 0x9f, 0xe2, 0x74, 0xcc, 0x01,
+        0x00,
+    ];
+    assert_eq!(identify_vendor_id(&vendor_id), Some("Cisco Unity"));
+    assert_eq!(identify_vendor_id(&vendor_id[..8]), None);
+}
+
+#[test]
+fn unknown_vendor_ids_are_hex_encoded() {
+    assert_eq!(describe_vendor_id(&[0x00, 0xab, 0x10]), "00ab10");
+}
+
+#[cfg(feature = "test-util")]
+#[tokio::test]
+async fn vendor_ids_of_scan() {
+    use std::sync::Arc;
+
+    use ikebuster::planned_transforms;
+    use ikebuster::scan_with_transport;
+    use ikebuster::testing::scan_options;
+    use ikebuster::testing::Behavior;
+    use ikebuster::testing::ScriptedResponder;
+
+    let supported = planned_transforms(&scan_options())[..3].to_vec();
+    let responder = Arc::new(
+        ScriptedResponder::new(Behavior::PickOne, supported)
+            .vendor_id(NAT_T_VENDOR_IDS[0])
+            .vendor_id([0xde, 0xad, 0xbe, 0xef]),
+    );
+
+    let result = scan_with_transport(scan_options(), responder)
+        .await
+        .unwrap();
+
+    // Every response carries the same vendor IDs, they are only listed once
+    assert_eq!(result.vendor_ids, ["RFC 3947 NAT-T", "deadbeef"]);
+}
@@ -0,0 +1,64 @@
+//! Grading custom groups the server accepts
+
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use ikebuster::planned_transforms;
+use ikebuster::scan_with_transport;
+use ikebuster::testing::scan_options;
+use ikebuster::testing::Behavior;
+use ikebuster::testing::ScriptedResponder;
+use ikebuster::utils::grading::Grade;
+use ikebuster::ScanOptions;
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::AttributeValue;
+use isakmp::v1::generator::Transform;
+
+/// A transform which is acceptable on its own, advertising a custom group with a prime
+/// of the given size
+fn transform(prime_bits: usize) -> Transform {
+    let mut prime = vec![0xff; prime_bits / 8];
+    prime[prime_bits / 8 - 1] = 0xfd;
+
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_2048,
+        key_size: Some(256),
+        extra_attributes: vec![(AttributeType::GroupPrime, AttributeValue::Long(prime))],
+    }
+}
+
+async fn scan(prime_bits: usize) -> (Vec<usize>, Option<Grade>) {
+    let mut transforms = planned_transforms(&scan_options());
+    transforms.truncate(8);
+    transforms.push(transform(prime_bits));
+    let opts = ScanOptions {
+        transforms: Some(transforms),
+        ..scan_options()
+    };
+    let responder = Arc::new(ScriptedResponder::new(
+        Behavior::PickOne,
+        vec![transform(prime_bits)],
+    ));
+    let result = scan_with_transport(opts, responder).await.unwrap();
+
+    assert_eq!(result.valid_transforms, [transform(prime_bits)]);
+    (result.custom_group_prime_bits, result.grade)
+}
+
+#[tokio::test]
+async fn custom_1024_bit_group_is_weak() {
+    assert_eq!(scan(1024).await, (vec![1024], Some(Grade::Weak)));
+}
+
+#[tokio::test]
+async fn custom_2048_bit_group_is_acceptable() {
+    assert_eq!(scan(2048).await, (vec![2048], Some(Grade::Acceptable)));
+}
@@ -0,0 +1,81 @@
+//! Benchmarks of building and parsing messages
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use isakmp::strum::IntoEnumIterator;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::parser::parse_packet;
+
+/// Numbers of transforms in a proposal
+const TRANSFORM_COUNTS: [usize; 4] = [1, 10, 20, 100];
+
+/// Generate distinct transforms
+fn transforms(count: usize) -> Vec<Transform> {
+    EncryptionAlgorithm::iter()
+        .flat_map(|encryption_algorithm| {
+            GroupDescription::iter().map(move |group_description| Transform {
+                encryption_algorithm,
+                hash_algorithm: HashAlgorithm::SHA2_256,
+                authentication_method: AuthenticationMethod::PreSharedKey,
+                group_description,
+                key_size: Some(256),
+                extra_attributes: vec![],
+            })
+        })
+        .cycle()
+        .take(count)
+        .collect()
+}
+
+fn message(count: usize) -> Vec<u8> {
+    let mut mb = MessageBuilder::new().initiator_cookie(1);
+    for transform in transforms(count) {
+        mb = mb.add_transform(transform);
+    }
+    mb.build().0
+}
+
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    for count in TRANSFORM_COUNTS {
+        let transforms = transforms(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &transforms, |b, t| {
+            b.iter(|| {
+                let mut mb = MessageBuilder::new().initiator_cookie(1);
+                for transform in t {
+                    mb = mb.add_transform(transform.clone());
+                }
+                mb.build()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for count in TRANSFORM_COUNTS {
+        let msg = message(count);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &msg, |b, msg| {
+            b.iter(|| parse_packet(msg))
+        });
+    }
+    group.finish();
+}
+
+fn attribute_bytes(c: &mut Criterion) {
+    let transform = transforms(1).remove(0);
+    c.bench_function("attribute_bytes", |b| {
+        b.iter(|| transform.to_attribute_bytes())
+    });
+}
+
+criterion_group!(benches, build, parse, attribute_bytes);
+criterion_main!(benches);
@@ -82,6 +82,12 @@ pub enum PayloadType {
     Notification = 11,
     Delete = 12,
     VendorID = 13,
+    /// NAT discovery, see [StaticNatDiscoveryPayload]
+    NatDiscovery = 20,
+    /// NAT discovery as assigned by the drafts preceding RFC 3947
+    NatDiscoveryDraft = 130,
+    /// Fragment of a message split by IKEv1 fragmentation, see [StaticFragmentPayload]
+    Fragment = 132,
 }
 
 /// Other payload types of [PayloadType] that can't be defined by Rusts enum
@@ -113,6 +119,9 @@ impl TryFrom<u8> for PayloadType {
             11 => PayloadType::Notification,
             12 => PayloadType::Delete,
             13 => PayloadType::VendorID,
+            20 => PayloadType::NatDiscovery,
+            130 => PayloadType::NatDiscoveryDraft,
+            132 => PayloadType::Fragment,
             14..128 => return Err(PayloadTypeOther::Reserved),
             _ => return Err(PayloadTypeOther::PrivateUse),
         })
@@ -123,7 +132,7 @@ impl TryFrom<u8> for PayloadType {
 ///
 /// For more information, take a look at:
 /// https://datatracker.ietf.org/doc/html/rfc2408#section-3.1
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)] // Serialization
 #[repr(u8)]
 #[allow(missing_docs)]
 pub enum ExchangeType {
@@ -356,8 +365,14 @@ pub struct VariableKeyExchangePayload {
 pub struct StaticIdentificationPayload {
     /// Generic header
     pub generic_payload_header: GenericPayloadHeader,
-    /// Specifies the type of Identification being used.
+    /// Specifies the type of Identification being used, see [IdentificationType].
     pub id_type: u8,
+    /// IPsec DOI: The IP protocol (e.g. UDP) of the identity, 0 if it is ignored
+    ///
+    /// https://datatracker.ietf.org/doc/html/rfc2407#section-4.6.2
+    pub protocol_id: u8,
+    /// IPsec DOI: The port of the identity, 0 if it is ignored
+    pub port: U16,
 }
 
 /// The variable part of the [StaticIdentificationPayload]
@@ -368,6 +383,55 @@ pub struct VariableIdentificationPayload {
     pub identification_data: Vec<u8>,
 }
 
+/// The type of the identification data of the IPsec DOI
+///
+/// For more information, take a look at:
+/// https://datatracker.ietf.org/doc/html/rfc2407#section-4.6.2.1
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Copy)]
+#[repr(u8)]
+#[allow(missing_docs, non_camel_case_types)]
+pub enum IdentificationType {
+    ID_IPV4_ADDR = 1,
+    ID_FQDN = 2,
+    ID_USER_FQDN = 3,
+    ID_IPV4_ADDR_SUBNET = 4,
+    ID_IPV6_ADDR = 5,
+    ID_IPV6_ADDR_SUBNET = 6,
+    ID_IPV4_ADDR_RANGE = 7,
+    ID_IPV6_ADDR_RANGE = 8,
+    ID_DER_ASN1_DN = 9,
+    ID_DER_ASN1_GN = 10,
+    ID_KEY_ID = 11,
+}
+
+/// Other uses of [IdentificationType]
+#[derive(Debug, Clone)]
+pub enum IdentificationTypeOther {
+    /// 0, 12 - 255: Reserved
+    Reserved,
+}
+
+impl TryFrom<u8> for IdentificationType {
+    type Error = IdentificationTypeOther;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => IdentificationType::ID_IPV4_ADDR,
+            2 => IdentificationType::ID_FQDN,
+            3 => IdentificationType::ID_USER_FQDN,
+            4 => IdentificationType::ID_IPV4_ADDR_SUBNET,
+            5 => IdentificationType::ID_IPV6_ADDR,
+            6 => IdentificationType::ID_IPV6_ADDR_SUBNET,
+            7 => IdentificationType::ID_IPV4_ADDR_RANGE,
+            8 => IdentificationType::ID_IPV6_ADDR_RANGE,
+            9 => IdentificationType::ID_DER_ASN1_DN,
+            10 => IdentificationType::ID_DER_ASN1_GN,
+            11 => IdentificationType::ID_KEY_ID,
+            _ => return Err(IdentificationTypeOther::Reserved),
+        })
+    }
+}
+
 ///  The Certificate Payload provides a means to transport certificates or other certificate-related
 /// information via ISAKMP and can appear in any ISAKMP message. Certificate payloads SHOULD be
 /// included in an exchange whenever an appropriate directory service (e.g.  Secure DNS
@@ -595,7 +659,9 @@ pub struct StaticNotificationPayload {
 /// their corresponding values.
 ///
 /// Values in the Private Use range are expected to be DOI-specific values.
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Copy)]
+/// The status types of the IPsec DOI (RFC 2407) are included.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Copy, strum::Display)] // Display implementation
+#[derive(Serialize, Deserialize)] // Serialization
 #[repr(u16)]
 #[allow(missing_docs)]
 pub enum NotifyMessageType {
@@ -633,6 +699,17 @@ pub enum NotifyMessageType {
     UnequalPayloadLengths = 30,
     // Notify Messages
     Connected = 16384,
+    // Status types of the IPsec DOI
+    ResponderLifetime = 24576,
+    ReplayStatus = 24577,
+    InitialContact = 24578,
+}
+
+impl NotifyMessageType {
+    /// Check whether the type is an error, the remaining types are status notifications
+    pub fn is_error(&self) -> bool {
+        (*self as u16) < NotifyMessageType::Connected as u16
+    }
 }
 
 /// Other types of [NotifyMessageType]
@@ -692,7 +769,11 @@ impl TryFrom<u16> for NotifyMessageType {
             8192..16384 => return Err(NotifyMessageTypeOther::PrivateUse),
             16384 => NotifyMessageType::Connected,
             16385..24576 => return Err(NotifyMessageTypeOther::Reserved2),
-            24576..32768 => return Err(NotifyMessageTypeOther::PrivateUse2),
+            24576 => NotifyMessageType::ResponderLifetime,
+            24577 => NotifyMessageType::ReplayStatus,
+            24578 => NotifyMessageType::InitialContact,
+            24579..32768 => return Err(NotifyMessageTypeOther::DOISpecific),
+            32768..40960 => return Err(NotifyMessageTypeOther::PrivateUse2),
             _ => return Err(NotifyMessageTypeOther::Reserved3),
         })
     }
@@ -808,6 +889,50 @@ pub struct VariableVendorIDPayload {
     pub vendor_id: Vec<u8>,
 }
 
+/// The NAT discovery payload contains the hash of an IP address and port of either peer.
+/// If the hashes don't match the addresses a peer observes, there is a NAT in between.
+///
+/// For more information, take a look at:
+/// https://datatracker.ietf.org/doc/html/rfc3947#section-3.2
+#[derive(Debug, FromBytes, FromZeroes, AsBytes, Unaligned, Copy, Clone)]
+#[repr(C, packed)]
+pub struct StaticNatDiscoveryPayload {
+    /// Generic payload
+    pub generic_payload_header: GenericPayloadHeader,
+}
+
+/// The variable part of the [StaticNatDiscoveryPayload]
+#[derive(Debug, Clone)]
+pub struct VariableNatDiscoveryPayload {
+    /// Hash of the cookies, IP address and port, using the negotiated hash algorithm
+    pub hash_of_address_and_port: Vec<u8>,
+}
+
+/// Flag of [StaticFragmentPayload::flags] that marks the last fragment of a message
+pub const LAST_FRAGMENT: u8 = 0x01;
+
+/// The fragment payload carries a part of a message that was too large for a single datagram.
+///
+/// The message is split after its header and every fragment is sent as the only payload
+/// of a message on its own. Once all fragments were received, their data is concatenated
+/// to the original message. This is the proprietary fragmentation of IKEv1 implemented by
+/// Cisco and strongSwan, announced by a vendor id.
+///
+/// For more information, take a look at:
+/// https://docs.strongswan.org/docs/latest/features/ikev1Fragmentation.html
+#[derive(Debug, FromBytes, FromZeroes, AsBytes, Unaligned, Copy, Clone)]
+#[repr(C, packed)]
+pub struct StaticFragmentPayload {
+    /// Generic payload
+    pub generic_payload_header: GenericPayloadHeader,
+    /// Identifies the message the fragment belongs to
+    pub fragment_id: U16,
+    /// Position of the fragment in the message, starting at 1
+    pub fragment_number: u8,
+    /// Flags of the fragment, see [LAST_FRAGMENT]
+    pub flags: u8,
+}
+
 /// The Domain of Interpretation is a 32-bit value which identifies the
 /// context in which the Security Association payload is to be evaluated.
 /// Requests for assignments of new domain of interpretation identifiers
@@ -1035,6 +1160,82 @@ pub enum GroupDescription {
     ECP_Brainpool_512 = 30,
 }
 
+/// Groups providing less bits of security are considered weak, see [GroupDescription::is_weak]
+pub const MIN_GROUP_SECURITY_BITS: u16 = 112;
+
+impl GroupDescription {
+    /// The approximate strength of the group in bits of security
+    ///
+    /// The estimates follow NIST SP 800-57 and RFC 3526, returns `None` for
+    /// [GroupDescription::Reserved]
+    pub fn approx_security_bits(&self) -> Option<u16> {
+        Some(match self {
+            GroupDescription::Reserved => return None,
+            GroupDescription::MODP_768 => 64,
+            GroupDescription::MODP_1024 => 80,
+            GroupDescription::EC2N_GF2_155 => 77,
+            GroupDescription::EC2N_GF2_185 => 92,
+            GroupDescription::MODP_1536 => 96,
+            GroupDescription::MODP_2048 => 112,
+            GroupDescription::MODP_3072 => 128,
+            GroupDescription::MODP_4096 => 152,
+            GroupDescription::MODP_6144 => 176,
+            GroupDescription::MODP_8192 => 200,
+            GroupDescription::ECP_Random_256 => 128,
+            GroupDescription::ECP_Random_384 => 192,
+            GroupDescription::ECP_Random_521 => 256,
+            GroupDescription::MODP_1024_160_PrimeOrderSubgroup => 80,
+            GroupDescription::MODP_2048_224_PrimeOrderSubgroup => 112,
+            GroupDescription::MODP_2048_256_PrimeOrderSubgroup => 112,
+            GroupDescription::ECP_Random_192 => 96,
+            GroupDescription::ECP_Random_224 => 112,
+            GroupDescription::ECP_Brainpool_224 => 112,
+            GroupDescription::ECP_Brainpool_256 => 128,
+            GroupDescription::ECP_Brainpool_384 => 192,
+            GroupDescription::ECP_Brainpool_512 => 256,
+        })
+    }
+
+    /// The length of the public value sent in a key exchange payload in bytes
+    ///
+    /// This is the size of the prime of MODP groups, the size of the x-coordinate of EC2N
+    /// groups and the size of both coordinates of ECP groups (RFC 5903).
+    /// Returns `None` for [GroupDescription::Reserved]
+    pub fn key_exchange_length(&self) -> Option<usize> {
+        Some(match self {
+            GroupDescription::Reserved => return None,
+            GroupDescription::MODP_768 => 96,
+            GroupDescription::MODP_1024 => 128,
+            GroupDescription::EC2N_GF2_155 => 20,
+            GroupDescription::EC2N_GF2_185 => 24,
+            GroupDescription::MODP_1536 => 192,
+            GroupDescription::MODP_2048 => 256,
+            GroupDescription::MODP_3072 => 384,
+            GroupDescription::MODP_4096 => 512,
+            GroupDescription::MODP_6144 => 768,
+            GroupDescription::MODP_8192 => 1024,
+            GroupDescription::ECP_Random_256 => 64,
+            GroupDescription::ECP_Random_384 => 96,
+            GroupDescription::ECP_Random_521 => 132,
+            GroupDescription::MODP_1024_160_PrimeOrderSubgroup => 128,
+            GroupDescription::MODP_2048_224_PrimeOrderSubgroup => 256,
+            GroupDescription::MODP_2048_256_PrimeOrderSubgroup => 256,
+            GroupDescription::ECP_Random_192 => 48,
+            GroupDescription::ECP_Random_224 => 56,
+            GroupDescription::ECP_Brainpool_224 => 56,
+            GroupDescription::ECP_Brainpool_256 => 64,
+            GroupDescription::ECP_Brainpool_384 => 96,
+            GroupDescription::ECP_Brainpool_512 => 128,
+        })
+    }
+
+    /// Check whether the group provides less than [MIN_GROUP_SECURITY_BITS] bits of security
+    pub fn is_weak(&self) -> bool {
+        self.approx_security_bits()
+            .is_some_and(|bits| bits < MIN_GROUP_SECURITY_BITS)
+    }
+}
+
 /// Other variants of [GroupDescription]
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Copy, Error)]
 pub enum GroupDescriptionOther {
@@ -1188,7 +1389,8 @@ impl TryFrom<u16> for GroupType {
 /// For a given "Life Type" the value of the "Life Duration" attribute defines
 /// the actual length of the SA life -- either a number of seconds, or a number
 /// of kbytes protected.
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Copy)]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Copy, strum::Display)] // Display implementation
+#[derive(Serialize, Deserialize)] // Serialization
 #[allow(missing_docs)]
 #[repr(u16)]
 pub enum LifeType {
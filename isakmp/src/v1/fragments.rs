@@ -0,0 +1,215 @@
+//! Splitting and reassembly of fragmented IKEv1 messages
+//!
+//! Messages too large for a single datagram, e.g. with certificates, may be split into
+//! fragment payloads, see [StaticFragmentPayload]. The fragments of a message share the
+//! header of the original message, except for the next payload and the length.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use thiserror::Error;
+use zerocopy::AsBytes;
+use zerocopy::FromBytes;
+
+use crate::v1::definitions::GenericPayloadHeader;
+use crate::v1::definitions::Header;
+use crate::v1::definitions::PayloadType;
+use crate::v1::definitions::StaticFragmentPayload;
+use crate::v1::definitions::LAST_FRAGMENT;
+use crate::v1::parser::definitions::FragmentPayload;
+use crate::v1::parser::definitions::Packet;
+
+/// Split a message into fragments carrying at most `fragment_size` bytes of it each
+///
+/// The fragments are numbered from 1 in the order they have to be reassembled.
+/// Returns `None` if `msg` doesn't start with a header or `fragment_size` is 0.
+pub fn fragment_message(
+    msg: &[u8],
+    fragment_id: u16,
+    fragment_size: usize,
+) -> Option<Vec<Vec<u8>>> {
+    let header = Header::read_from_prefix(msg)?;
+    if fragment_size == 0 {
+        return None;
+    }
+
+    let chunks: Vec<&[u8]> = msg.chunks(fragment_size).collect();
+    let count = chunks.len();
+    if count > u8::MAX as usize {
+        return None;
+    }
+
+    let mut fragments = vec![];
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let payload_length = size_of::<StaticFragmentPayload>() + chunk.len();
+
+        let mut fragment_header = header;
+        fragment_header.next_payload = PayloadType::Fragment as u8;
+        fragment_header
+            .length
+            .set((size_of::<Header>() + payload_length) as u32);
+
+        let payload = StaticFragmentPayload {
+            generic_payload_header: GenericPayloadHeader {
+                next_payload: PayloadType::None as u8,
+                reserved: 0,
+                payload_length: (payload_length as u16).into(),
+            },
+            fragment_id: fragment_id.into(),
+            fragment_number: index as u8 + 1,
+            flags: if index + 1 == count { LAST_FRAGMENT } else { 0 },
+        };
+
+        let mut fragment = fragment_header.as_bytes().to_vec();
+        fragment.extend_from_slice(payload.as_bytes());
+        fragment.extend_from_slice(chunk);
+        fragments.push(fragment);
+    }
+    Some(fragments)
+}
+
+/// Buffer for the fragments of messages until they are complete
+///
+/// Fragments are grouped by the initiator cookie and the fragment id of their message.
+/// To bound the memory used by incomplete messages, only a limited number of messages
+/// is buffered. If another message starts, the oldest incomplete one is discarded.
+#[derive(Debug, Clone)]
+pub struct Reassembler {
+    /// Maximum number of incomplete messages
+    max_messages: usize,
+    /// Maximum number of fragments per message
+    max_fragments: u8,
+    /// The fragments of the incomplete messages
+    pending: HashMap<(u64, u16), PendingMessage>,
+    /// The incomplete messages, starting with the oldest one
+    order: VecDeque<(u64, u16)>,
+}
+
+/// The fragments received of a message
+#[derive(Debug, Clone, Default)]
+struct PendingMessage {
+    /// The data of the fragments by their number
+    fragments: BTreeMap<u8, Vec<u8>>,
+    /// The number of the last fragment, once it was received
+    last: Option<u8>,
+}
+
+impl Default for Reassembler {
+    fn default() -> Self {
+        Self::new(16, 32)
+    }
+}
+
+impl Reassembler {
+    /// Create a buffer for at most `max_messages` incomplete messages
+    /// of at most `max_fragments` fragments each
+    pub fn new(max_messages: usize, max_fragments: u8) -> Self {
+        Self {
+            max_messages: max_messages.max(1),
+            max_fragments,
+            pending: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Add the fragments of a received message
+    ///
+    /// Returns the reassembled message once all fragments were received,
+    /// it has to be parsed on its own.
+    pub fn add_packet(&mut self, packet: &Packet) -> Result<Option<Vec<u8>>, FragmentError> {
+        let mut complete = None;
+        for fragment in &packet.fragments {
+            if let Some(msg) = self.add(packet.header.initiator_cookie, fragment)? {
+                complete = Some(msg);
+            }
+        }
+        Ok(complete)
+    }
+
+    /// Add a fragment of the message with the given initiator cookie
+    ///
+    /// Returns the reassembled message once all fragments were received.
+    /// Repeated fragments replace the earlier ones, as they are retransmissions.
+    pub fn add(
+        &mut self,
+        initiator_cookie: u64,
+        fragment: &FragmentPayload,
+    ) -> Result<Option<Vec<u8>>, FragmentError> {
+        let number = fragment.fragment_number;
+        if number == 0 {
+            return Err(FragmentError::InvalidNumber(number));
+        }
+        if number > self.max_fragments {
+            return Err(FragmentError::TooManyFragments(self.max_fragments));
+        }
+
+        let key = (initiator_cookie, fragment.fragment_id);
+        if !self.pending.contains_key(&key) {
+            if self.order.len() >= self.max_messages {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.pending.remove(&oldest);
+                }
+            }
+            self.order.push_back(key);
+        }
+        let pending = self.pending.entry(key).or_default();
+
+        if fragment.last {
+            if pending.last.is_some_and(|last| last != number) {
+                self.discard(key);
+                return Err(FragmentError::InconsistentLast);
+            }
+            pending.last = Some(number);
+        }
+        if let Some(last) = pending.last {
+            let beyond_last = number > last
+                || pending
+                    .fragments
+                    .last_key_value()
+                    .is_some_and(|(x, _)| *x > last);
+            if beyond_last {
+                self.discard(key);
+                return Err(FragmentError::InconsistentLast);
+            }
+        }
+        pending.fragments.insert(number, fragment.data.clone());
+
+        match pending.last {
+            Some(last) if pending.fragments.len() == last as usize => {
+                let msg = self
+                    .discard(key)
+                    .map(|pending| pending.fragments.into_values().flatten().collect());
+                Ok(msg)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Number of incomplete messages
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn discard(&mut self, key: (u64, u16)) -> Option<PendingMessage> {
+        self.order.retain(|x| *x != key);
+        self.pending.remove(&key)
+    }
+}
+
+/// Errors that may occur while reassembling a message
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum FragmentError {
+    /// Fragments are numbered from 1
+    #[error("Invalid fragment number: {0}")]
+    InvalidNumber(u8),
+    /// The message is split into more fragments than allowed
+    #[error("The message exceeds {0} fragments")]
+    TooManyFragments(u8),
+    /// The fragments disagree on the last fragment of the message
+    #[error("The fragments disagree on the last fragment")]
+    InconsistentLast,
+    /// The reassembled message consists of fragments itself
+    #[error("The reassembled message is fragmented")]
+    Nested,
+}
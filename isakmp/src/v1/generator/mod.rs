@@ -1,5 +1,7 @@
 //! Message generation
 
+use std::fmt;
+
 use serde::Deserialize;
 use serde::Serialize;
 use zerocopy::network_endian::*;
@@ -7,16 +9,25 @@ use zerocopy::AsBytes;
 use zerocopy::U16;
 use zerocopy::U32;
 
+use crate::v1::definitions::AttributeType;
 use crate::v1::definitions::AuthenticationMethod;
 use crate::v1::definitions::DataAttributeShort;
+use crate::v1::definitions::DomainOfInterpretation;
 use crate::v1::definitions::EncryptionAlgorithm;
 use crate::v1::definitions::ExchangeType;
 use crate::v1::definitions::GenericPayloadHeader;
 use crate::v1::definitions::GroupDescription;
 use crate::v1::definitions::HashAlgorithm;
 use crate::v1::definitions::Header;
+use crate::v1::definitions::IdentificationType;
 use crate::v1::definitions::LifeType;
+use crate::v1::definitions::NotifyMessageType;
 use crate::v1::definitions::PayloadType;
+use crate::v1::definitions::StaticDataAttributeLong;
+use crate::v1::definitions::StaticIdentificationPayload;
+use crate::v1::definitions::StaticKeyExchangePayload;
+use crate::v1::definitions::StaticNoncePayload;
+use crate::v1::definitions::StaticNotificationPayload;
 use crate::v1::definitions::StaticProposalPayload;
 use crate::v1::definitions::StaticSecurityAssociationPayload;
 use crate::v1::definitions::StaticTransformPayload;
@@ -28,6 +39,12 @@ use crate::v1::definitions::VariableTransformPayload;
 ///
 /// A transform consists of multiple attributes that determine the encryption and authentication
 /// that should be used
+///
+/// Scan results are persisted as JSON, so the serialized format is kept stable: the fields keep
+/// their names and the attributes are serialized as the names of their variants, e.g.
+/// `{"encryption_algorithm":"AES_CBC","hash_algorithm":"SHA2_256","authentication_method":
+/// "PreSharedKey","group_description":"MODP_2048","key_size":256}`.
+/// `extra_attributes` is omitted if it is empty.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Transform {
     /// Encryption algorithm
@@ -40,11 +57,132 @@ pub struct Transform {
     pub group_description: GroupDescription,
     /// Optional key size
     pub key_size: Option<u16>,
+    /// Additional attributes which are emitted after the standard ones
+    ///
+    /// Attributes like [AttributeType::PRF] or [AttributeType::FieldSize] are not covered
+    /// by the fields above.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_attributes: Vec<(AttributeType, AttributeValue)>,
+}
+
+/// The value of a data attribute
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum AttributeValue {
+    /// Value of a short attribute (TV format)
+    Short(u16),
+    /// Value of a variable length attribute (TLV format)
+    Long(Vec<u8>),
+}
+
+/// The lifetime of a security association
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Lifetime {
+    /// Whether the duration is measured in seconds or kilobytes
+    pub life_type: LifeType,
+    /// Number of seconds or kilobytes the security association is valid for
+    pub duration: u32,
+}
+
+/// Formats the lifetime with its unit, e.g. `28800 seconds`
+impl fmt::Display for Lifetime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.life_type {
+            LifeType::Seconds => write!(f, "{} seconds", self.duration),
+            LifeType::Kilobytes => write!(f, "{} kilobytes", self.duration),
+            LifeType::Reserved => write!(f, "{} (reserved unit)", self.duration),
+        }
+    }
+}
+
+impl Default for Lifetime {
+    fn default() -> Self {
+        Self {
+            life_type: LifeType::Seconds,
+            duration: 7080,
+        }
+    }
+}
+
+/// The identity of the initiator, sent in the identification payload of aggressive mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    /// The type of the identification data
+    pub id_type: IdentificationType,
+    /// The identification data, e.g. the octets of an IPv4 address or a FQDN
+    pub data: Vec<u8>,
+}
+
+/// An additional proposal of the security association built by the [MessageBuilder]
+///
+/// Proposals with distinct numbers are alternatives the responder chooses from,
+/// see [MessageBuilder::add_proposal].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proposal {
+    /// The number of the proposal
+    pub proposal_no: u8,
+    /// The security parameter index, empty by default
+    pub spi: Vec<u8>,
+    /// The transforms of the proposal
+    pub transforms: Vec<Transform>,
 }
 
+/// The number of the proposal that is built by the [MessageBuilder]
+pub const PROPOSAL_NO: u8 = 1;
+
+/// The domain of interpretation of the security association built by the [MessageBuilder]
+/// by default
+pub const DOI: DomainOfInterpretation = DomainOfInterpretation::IPSEC;
+
+/// The situation of the security association built by the [MessageBuilder] by default
+/// (`SIT_IDENTITY_ONLY`)
+pub const SITUATION: [u8; 4] = [0x00, 0x00, 0x00, 0x01];
+
+/// The transform id of the ISAKMP protocol (`KEY_IKE`), used by the [MessageBuilder] by default
+pub const KEY_IKE: u8 = 1;
+
+/// The length of the nonce of aggressive mode messages built by the [MessageBuilder]
+pub const NONCE_LENGTH: usize = 32;
+
+/// The order in which the [MessageBuilder] emits the attributes of a transform by default
+pub const DEFAULT_ATTRIBUTE_ORDER: [AttributeType; 7] = [
+    AttributeType::EncryptionAlgorithm,
+    AttributeType::HashAlgorithm,
+    AttributeType::AuthenticationMethod,
+    AttributeType::GroupDescription,
+    AttributeType::LifeType,
+    AttributeType::LifeDuration,
+    AttributeType::KeyLength,
+];
+
+/// An attribute order which places the key length right after the encryption algorithm
+///
+/// Some implementations expect the key length to be adjacent to the encryption algorithm
+pub const KEY_LENGTH_FIRST_ATTRIBUTE_ORDER: [AttributeType; 7] = [
+    AttributeType::EncryptionAlgorithm,
+    AttributeType::KeyLength,
+    AttributeType::HashAlgorithm,
+    AttributeType::AuthenticationMethod,
+    AttributeType::GroupDescription,
+    AttributeType::LifeType,
+    AttributeType::LifeDuration,
+];
+
 /// Helper struct to build an crate message
 pub struct MessageBuilder {
     transforms: Vec<Transform>,
+    initiator_cookie: Option<u64>,
+    responder_cookie: u64,
+    exchange_type: ExchangeType,
+    lifetime: Lifetime,
+    attribute_order: Vec<AttributeType>,
+    transform_id: u8,
+    identity: Option<Identity>,
+    nonce: bool,
+    notification: Option<NotifyMessageType>,
+    doi: u32,
+    situation: Vec<u8>,
+    spi: Vec<u8>,
+    proposals: Vec<Proposal>,
 }
 
 impl MessageBuilder {
@@ -52,31 +190,170 @@ impl MessageBuilder {
     pub fn new() -> Self {
         Self {
             transforms: Vec::new(),
+            initiator_cookie: None,
+            responder_cookie: 0,
+            exchange_type: ExchangeType::IdentityProtection,
+            lifetime: Lifetime::default(),
+            attribute_order: DEFAULT_ATTRIBUTE_ORDER.to_vec(),
+            transform_id: KEY_IKE,
+            identity: None,
+            nonce: false,
+            notification: None,
+            doi: DOI as u32,
+            situation: SITUATION.to_vec(),
+            spi: vec![],
+            proposals: vec![],
         }
     }
 
+    /// Use a fixed initiator cookie instead of a randomly generated one
+    pub fn initiator_cookie(mut self, initiator_cookie: u64) -> Self {
+        self.initiator_cookie = Some(initiator_cookie);
+        self
+    }
+
+    /// Set the responder cookie
+    ///
+    /// The first message of an exchange has no responder cookie. Follow-up messages of the
+    /// same exchange have to echo the cookie the responder sent in its reply.
+    pub fn responder_cookie(mut self, responder_cookie: u64) -> Self {
+        self.responder_cookie = responder_cookie;
+        self
+    }
+
+    /// Set the exchange type of the message
+    ///
+    /// Defaults to [ExchangeType::IdentityProtection] (main mode). Setting
+    /// [ExchangeType::Aggressive] only changes the header, use
+    /// [MessageBuilder::aggressive_mode] to add the payloads aggressive mode requires.
+    pub fn exchange_type(mut self, exchange_type: ExchangeType) -> Self {
+        self.exchange_type = exchange_type;
+        self
+    }
+
+    /// Build an aggressive mode message, identifying the initiator as `identity`
+    ///
+    /// Aggressive mode sends the key exchange with the first message, so in addition to
+    /// the security association the message contains the mandatory
+    /// - key exchange payload with random data of the size of the public value of the
+    ///   group of the first transform, as all transforms have to use the same group
+    /// - nonce payload with [NONCE_LENGTH] random bytes
+    /// - identification payload with the `identity`
+    ///
+    /// The key exchange data is no valid public value, so the exchange can't be completed.
+    pub fn aggressive_mode(mut self, identity: Identity) -> Self {
+        self.exchange_type = ExchangeType::Aggressive;
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Add a nonce payload with [NONCE_LENGTH] random bytes after the security association
+    ///
+    /// The first message of [ExchangeType::Base] and [ExchangeType::AuthenticationOnly]
+    /// requires a nonce. Aggressive mode always contains one.
+    pub fn nonce(mut self) -> Self {
+        self.nonce = true;
+        self
+    }
+
+    /// Build an informational message with a single notification of the given type
+    ///
+    /// The message contains no security association, so the added transforms and proposals
+    /// are ignored. The notification is sent unprotected and concerns the ISAKMP security
+    /// association identified by the cookies.
+    pub fn informational(mut self, notify_message_type: NotifyMessageType) -> Self {
+        self.exchange_type = ExchangeType::Informational;
+        self.notification = Some(notify_message_type);
+        self
+    }
+
+    /// Set the lifetime that is proposed for all transforms
+    ///
+    /// Defaults to 7080 seconds
+    pub fn lifetime(mut self, lifetime: Lifetime) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Set the order in which the attributes of each transform are emitted
+    ///
+    /// Attributes missing from the order are emitted afterward in the order of
+    /// [DEFAULT_ATTRIBUTE_ORDER], attribute types which are never emitted are ignored.
+    pub fn attribute_order(mut self, attribute_order: Vec<AttributeType>) -> Self {
+        self.attribute_order = attribute_order;
+        self
+    }
+
+    /// Set the transform id of all transforms
+    ///
+    /// Defaults to [KEY_IKE]
+    pub fn transform_id(mut self, transform_id: u8) -> Self {
+        self.transform_id = transform_id;
+        self
+    }
+
+    /// Set the domain of interpretation of the security association
+    ///
+    /// Defaults to [DOI]. The value isn't checked, so servers can be probed with unassigned
+    /// domains of interpretation.
+    pub fn doi(mut self, doi: u32) -> Self {
+        self.doi = doi;
+        self
+    }
+
+    /// Set the situation of the security association
+    ///
+    /// Defaults to [SITUATION]. The situation is interpreted according to the domain of
+    /// interpretation, so it may have any length.
+    pub fn situation(mut self, situation: Vec<u8>) -> Self {
+        self.situation = situation;
+        self
+    }
+
+    /// Set the security parameter index of the proposal of the added transforms
+    ///
+    /// Defaults to none, as the cookies identify the ISAKMP security association.
+    pub fn spi(mut self, spi: Vec<u8>) -> Self {
+        self.spi = spi;
+        self
+    }
+
     /// Add transform to the message builder
+    ///
+    /// The transforms are sent in a proposal numbered [PROPOSAL_NO].
     pub fn add_transform(mut self, transform: Transform) -> Self {
         self.transforms.push(transform);
         self
     }
 
+    /// Add another proposal after the proposal of the added transforms
+    ///
+    /// If no transforms were added, the proposals are sent on their own.
+    pub fn add_proposal(mut self, proposal: Proposal) -> Self {
+        self.proposals.push(proposal);
+        self
+    }
+
     /// Create a test message
     ///
     /// # Returns
     /// - the isakmp message
     /// - the initiator cookie
     pub fn build(self) -> (Vec<u8>, u64) {
+        if let Some(notify_message_type) = self.notification {
+            return self.build_informational(notify_message_type);
+        }
+
         let mut msg = vec![];
 
         let mut overall_msg_length = size_of::<Header>();
 
         let mut header = Header {
-            initiator_cookie: U64::new(rand::random::<u64>()),
-            responder_cookie: U64::new(0),
+            initiator_cookie: U64::new(self.initiator_cookie.unwrap_or_else(rand::random::<u64>)),
+            responder_cookie: U64::new(self.responder_cookie),
             next_payload: PayloadType::SecurityAssociation as u8,
             version: 0b00010000,
-            exchange_type: ExchangeType::IdentityProtection as u8,
+            exchange_type: self.exchange_type as u8,
             flags: 0,
             message_id: Default::default(),
             length: Default::default(),
@@ -87,32 +364,188 @@ impl MessageBuilder {
                 reserved: 0,
                 payload_length: Default::default(),
             },
-            doi: U32::new(1),
+            doi: U32::new(self.doi),
         };
 
         let sa_var = VariableSecurityAssociationPayload {
-            situation: vec![0x00, 0x00, 0x00, 0x01],
+            situation: self.situation.clone(),
         };
 
-        let mut proposal = StaticProposalPayload {
+        let mut proposals: Vec<(u8, &[u8], &[Transform])> = vec![];
+        if !self.transforms.is_empty() || self.proposals.is_empty() {
+            proposals.push((PROPOSAL_NO, &self.spi, &self.transforms));
+        }
+        for proposal in &self.proposals {
+            proposals.push((proposal.proposal_no, &proposal.spi, &proposal.transforms));
+        }
+
+        let mut proposals_raw: Vec<u8> = vec![];
+        for (i, (proposal_no, spi, transforms)) in proposals.iter().enumerate() {
+            let next_payload = if i < proposals.len() - 1 {
+                PayloadType::Proposal
+            } else {
+                PayloadType::None
+            };
+            proposals_raw.extend(self.proposal_bytes(*proposal_no, spi, transforms, next_payload));
+        }
+
+        // The payloads following the security association
+        let mut trailing_raw: Vec<u8> = vec![];
+        let identity = match self.exchange_type {
+            ExchangeType::Aggressive => self.identity.as_ref(),
+            _ => None,
+        };
+        if identity.is_some() {
+            sa.generic_payload_header.next_payload = PayloadType::KeyExchange as u8;
+
+            let key_exchange_length = proposals
+                .iter()
+                .find_map(|(_, _, transforms)| transforms.first())
+                .and_then(|transform| transform.group_description.key_exchange_length())
+                .unwrap_or(0);
+            let key_exchange = StaticKeyExchangePayload {
+                generic_payload_header: GenericPayloadHeader {
+                    next_payload: PayloadType::Nonce as u8,
+                    reserved: 0,
+                    payload_length: U16::new(
+                        (size_of::<StaticKeyExchangePayload>() + key_exchange_length) as u16,
+                    ),
+                },
+            };
+            trailing_raw.extend_from_slice(key_exchange.as_bytes());
+            trailing_raw.extend((0..key_exchange_length).map(|_| rand::random::<u8>()));
+        } else if self.nonce {
+            sa.generic_payload_header.next_payload = PayloadType::Nonce as u8;
+        }
+
+        if identity.is_some() || self.nonce {
+            let nonce = StaticNoncePayload {
+                generic_payload_header: GenericPayloadHeader {
+                    next_payload: match identity {
+                        Some(_) => PayloadType::Identification as u8,
+                        None => PayloadType::None as u8,
+                    },
+                    reserved: 0,
+                    payload_length: U16::new(
+                        (size_of::<StaticNoncePayload>() + NONCE_LENGTH) as u16,
+                    ),
+                },
+            };
+            trailing_raw.extend_from_slice(nonce.as_bytes());
+            trailing_raw.extend((0..NONCE_LENGTH).map(|_| rand::random::<u8>()));
+        }
+
+        if let Some(identity) = identity {
+            let identification = StaticIdentificationPayload {
+                generic_payload_header: GenericPayloadHeader {
+                    next_payload: PayloadType::None as u8,
+                    reserved: 0,
+                    payload_length: U16::new(
+                        (size_of::<StaticIdentificationPayload>() + identity.data.len()) as u16,
+                    ),
+                },
+                id_type: identity.id_type as u8,
+                protocol_id: 0,
+                port: U16::new(0),
+            };
+            trailing_raw.extend_from_slice(identification.as_bytes());
+            trailing_raw.extend_from_slice(&identity.data);
+        }
+
+        // Set sa length
+        let mut sa_size = 0;
+        let static_sa_size = size_of::<StaticSecurityAssociationPayload>();
+
+        sa_size += static_sa_size;
+        sa_size += sa_var.situation.len();
+
+        // Add proposal sizes to sa size
+        sa_size += proposals_raw.len();
+
+        // Add sa size and the payloads following it to overall msg length
+        overall_msg_length += sa_size;
+        overall_msg_length += trailing_raw.len();
+        // Pad the message to a multiple of 4 bytes
+        overall_msg_length = overall_msg_length.next_multiple_of(4);
+
+        // Set SA payload size to message and payload header
+        sa.generic_payload_header.payload_length = U16::new(sa_size as u16);
+
+        // Set overall message length
+        header.length = U32::new(overall_msg_length as u32);
+
+        // Add data to message
+        msg.extend_from_slice(header.as_bytes());
+        msg.extend_from_slice(sa.as_bytes());
+        msg.extend_from_slice(&sa_var.situation);
+        msg.extend_from_slice(&proposals_raw);
+        msg.extend_from_slice(&trailing_raw);
+
+        // padding with 0
+        msg.resize(overall_msg_length, 0);
+
+        (msg, header.initiator_cookie.get())
+    }
+
+    /// Create an informational message with a single notification, see
+    /// [MessageBuilder::informational]
+    fn build_informational(self, notify_message_type: NotifyMessageType) -> (Vec<u8>, u64) {
+        let notification = StaticNotificationPayload {
             generic_payload_header: GenericPayloadHeader {
                 next_payload: PayloadType::None as u8,
                 reserved: 0,
-                payload_length: Default::default(),
+                payload_length: U16::new(size_of::<StaticNotificationPayload>() as u16),
             },
-            proposal_no: 1,
+            doi: U32::new(self.doi),
             protocol_id: 1,
             spi_size: 0,
-            no_of_transforms: self.transforms.len() as u8,
+            notify_message_type: U16::new(notify_message_type as u16),
+        };
+        let header = Header {
+            initiator_cookie: U64::new(self.initiator_cookie.unwrap_or_else(rand::random::<u64>)),
+            responder_cookie: U64::new(self.responder_cookie),
+            next_payload: PayloadType::Notification as u8,
+            version: 0b00010000,
+            exchange_type: ExchangeType::Informational as u8,
+            flags: 0,
+            message_id: Default::default(),
+            length: U32::new((size_of::<Header>() + size_of::<StaticNotificationPayload>()) as u32),
         };
 
-        let proposal_var = VariableProposalPayload { spi: vec![] };
+        let mut msg = vec![];
+        msg.extend_from_slice(header.as_bytes());
+        msg.extend_from_slice(notification.as_bytes());
+
+        (msg, header.initiator_cookie.get())
+    }
+
+    /// Encode a proposal payload including its transforms
+    fn proposal_bytes(
+        &self,
+        proposal_no: u8,
+        spi: &[u8],
+        transforms: &[Transform],
+        next_payload: PayloadType,
+    ) -> Vec<u8> {
+        let mut proposal = StaticProposalPayload {
+            generic_payload_header: GenericPayloadHeader {
+                next_payload: next_payload as u8,
+                reserved: 0,
+                payload_length: Default::default(),
+            },
+            proposal_no,
+            protocol_id: 1,
+            spi_size: spi.len() as u8,
+            no_of_transforms: transforms.len() as u8,
+        };
+
+        let proposal_var = VariableProposalPayload { spi: spi.to_vec() };
 
         let mut transforms_raw: Vec<u8> = vec![];
-        for (i, transform) in self.transforms.iter().enumerate() {
+        for (i, transform) in transforms.iter().enumerate() {
             let mut transform_payload = StaticTransformPayload {
                 generic_payload_header: GenericPayloadHeader {
-                    next_payload: if i < self.transforms.len() - 1 {
+                    next_payload: if i < transforms.len() - 1 {
                         PayloadType::Transform as u8
                     } else {
                         PayloadType::None as u8
@@ -121,63 +554,13 @@ impl MessageBuilder {
                     payload_length: Default::default(),
                 },
                 transform_no: i as u8,
-                transform_id: 1,
+                transform_id: self.transform_id,
                 reserved: U16::new(0),
             };
 
-            let mut sa_attributes = vec![];
-            sa_attributes.extend_from_slice(
-                DataAttributeShort {
-                    attribute_type: U16::new(0b1000_0000_0000_0001),
-                    attribute_value: U16::new(transform.encryption_algorithm as u16),
-                }
-                .as_bytes(),
-            );
-            sa_attributes.extend_from_slice(
-                DataAttributeShort {
-                    attribute_type: U16::new(0b1000_0000_0000_0010),
-                    attribute_value: U16::new(transform.hash_algorithm as u16),
-                }
-                .as_bytes(),
-            );
-            sa_attributes.extend_from_slice(
-                DataAttributeShort {
-                    attribute_type: U16::new(0b1000_0000_0000_0011),
-                    attribute_value: U16::new(transform.authentication_method as u16),
-                }
-                .as_bytes(),
-            );
-            sa_attributes.extend_from_slice(
-                DataAttributeShort {
-                    attribute_type: U16::new(0b1000_0000_0000_0100),
-                    attribute_value: U16::new(transform.group_description as u16),
-                }
-                .as_bytes(),
-            );
-            sa_attributes.extend_from_slice(
-                DataAttributeShort {
-                    attribute_type: U16::new(0b1000_0000_0000_1011),
-                    attribute_value: U16::new(LifeType::Seconds as u16),
-                }
-                .as_bytes(),
-            );
-            sa_attributes.extend_from_slice(
-                DataAttributeShort {
-                    attribute_type: U16::new(0b1000_0000_0000_1100),
-                    attribute_value: U16::new(7080),
-                }
-                .as_bytes(),
-            );
-            if let Some(key_size) = transform.key_size {
-                sa_attributes.extend_from_slice(
-                    DataAttributeShort {
-                        attribute_type: U16::new(0b1000_0000_0000_1110),
-                        attribute_value: U16::new(key_size),
-                    }
-                    .as_bytes(),
-                );
-            }
-            let transform_var = VariableTransformPayload { sa_attributes };
+            let transform_var = VariableTransformPayload {
+                sa_attributes: transform.attribute_bytes(&self.lifetime, &self.attribute_order),
+            };
 
             transform_payload.generic_payload_header.payload_length = U16::new(
                 (size_of::<StaticTransformPayload>() + transform_var.sa_attributes.len()) as u16,
@@ -187,51 +570,143 @@ impl MessageBuilder {
             transforms_raw.extend_from_slice(&transform_var.sa_attributes);
         }
 
-        // Set sa length
-        let mut sa_size = 0;
-        let mut proposal_size = 0;
-        let static_sa_size = size_of::<StaticSecurityAssociationPayload>();
-        let static_proposal_size = size_of::<StaticProposalPayload>();
-
-        sa_size += static_sa_size;
-        sa_size += sa_var.situation.len();
+        let proposal_size =
+            size_of::<StaticProposalPayload>() + proposal_var.spi.len() + transforms_raw.len();
+        proposal.generic_payload_header.payload_length = U16::new(proposal_size as u16);
 
-        proposal_size += static_proposal_size;
-        proposal_size += proposal_var.spi.len();
+        let mut proposal_raw = proposal.as_bytes().to_vec();
+        proposal_raw.extend_from_slice(&proposal_var.spi);
+        proposal_raw.extend_from_slice(&transforms_raw);
+        proposal_raw
+    }
+}
 
-        // Add transform size to proposal size
-        proposal_size += transforms_raw.len();
+/// Formats the transform in a human-readable, single line form
+///
+/// Example: `ENC=AES_CBC/256 HASH=SHA2_256 AUTH=PreSharedKey GROUP=MODP_2048`
+///
+/// The key size is appended to the encryption algorithm if it is set,
+/// [Transform::extra_attributes] are not included.
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ENC={}", self.encryption_algorithm)?;
+        if let Some(key_size) = self.key_size {
+            write!(f, "/{key_size}")?;
+        }
+        write!(
+            f,
+            " HASH={} AUTH={} GROUP={}",
+            self.hash_algorithm, self.authentication_method, self.group_description
+        )
+    }
+}
 
-        // Add proposal size to sa size
-        sa_size += proposal_size;
+impl Transform {
+    /// The SA attributes of the transform as they are put on the wire
+    ///
+    /// Uses the default [Lifetime] and [DEFAULT_ATTRIBUTE_ORDER], the payload headers
+    /// surrounding the attributes are not included.
+    pub fn to_attribute_bytes(&self) -> Vec<u8> {
+        self.attribute_bytes(&Lifetime::default(), &DEFAULT_ATTRIBUTE_ORDER)
+    }
 
-        // Add sa size to overall msg length
-        overall_msg_length += sa_size;
-        let remaining = overall_msg_length % 4;
-        if remaining != 0 {
-            overall_msg_length += remaining;
+    /// The SA attributes of the transform with the given lifetime and attribute order
+    ///
+    /// Attributes missing from the order are emitted afterward in the order of
+    /// [DEFAULT_ATTRIBUTE_ORDER], attribute types which are never emitted are ignored.
+    /// The [Transform::extra_attributes] are emitted last.
+    pub fn attribute_bytes(
+        &self,
+        lifetime: &Lifetime,
+        attribute_order: &[AttributeType],
+    ) -> Vec<u8> {
+        let mut sa_attributes = vec![];
+        for attribute_type in complete_attribute_order(attribute_order) {
+            self.build_attribute(lifetime, attribute_type, &mut sa_attributes);
         }
+        for (attribute_type, value) in &self.extra_attributes {
+            match value {
+                AttributeValue::Short(value) => {
+                    push_short_attribute(&mut sa_attributes, *attribute_type, *value)
+                }
+                AttributeValue::Long(value) => {
+                    push_long_attribute(&mut sa_attributes, *attribute_type, value)
+                }
+            }
+        }
+        sa_attributes
+    }
 
-        // Set SA payload size to message and payload header
-        proposal.generic_payload_header.payload_length = U16::new(proposal_size as u16);
-        sa.generic_payload_header.payload_length = U16::new(sa_size as u16);
+    /// Append the attribute of the given type to `sa_attributes`
+    fn build_attribute(
+        &self,
+        lifetime: &Lifetime,
+        attribute_type: AttributeType,
+        sa_attributes: &mut Vec<u8>,
+    ) {
+        let value = match attribute_type {
+            AttributeType::EncryptionAlgorithm => self.encryption_algorithm as u16,
+            AttributeType::HashAlgorithm => self.hash_algorithm as u16,
+            AttributeType::AuthenticationMethod => self.authentication_method as u16,
+            AttributeType::GroupDescription => self.group_description as u16,
+            AttributeType::LifeType => lifetime.life_type as u16,
+            AttributeType::LifeDuration => match u16::try_from(lifetime.duration) {
+                Ok(duration) => duration,
+                // Durations that don't fit into a short attribute (e.g. kilobytes)
+                // have to be sent as variable length attribute
+                Err(_) => {
+                    push_long_attribute(
+                        sa_attributes,
+                        AttributeType::LifeDuration,
+                        &lifetime.duration.to_be_bytes(),
+                    );
+                    return;
+                }
+            },
+            AttributeType::KeyLength => match self.key_size {
+                Some(key_size) => key_size,
+                None => return,
+            },
+            _ => return,
+        };
 
-        // Set overall message length
-        header.length = U32::new(overall_msg_length as u32);
+        push_short_attribute(sa_attributes, attribute_type, value);
+    }
+}
 
-        // Add data to message
-        msg.extend_from_slice(header.as_bytes());
-        msg.extend_from_slice(sa.as_bytes());
-        msg.extend_from_slice(&sa_var.situation);
-        msg.extend_from_slice(proposal.as_bytes());
-        msg.extend_from_slice(&proposal_var.spi);
-        msg.extend_from_slice(&transforms_raw);
+/// Append a short attribute to `sa_attributes`
+fn push_short_attribute(sa_attributes: &mut Vec<u8>, attribute_type: AttributeType, value: u16) {
+    sa_attributes.extend_from_slice(
+        DataAttributeShort {
+            // The most significant bit marks the attribute as short
+            attribute_type: U16::new(0b1000_0000_0000_0000 | attribute_type as u16),
+            attribute_value: U16::new(value),
+        }
+        .as_bytes(),
+    );
+}
 
-        // padding with 0
-        msg.resize(overall_msg_length, 0);
+/// Append a variable length attribute to `sa_attributes`
+fn push_long_attribute(sa_attributes: &mut Vec<u8>, attribute_type: AttributeType, value: &[u8]) {
+    sa_attributes.extend_from_slice(
+        StaticDataAttributeLong {
+            attribute_type: U16::new(attribute_type as u16),
+            attribute_length: U16::new(value.len() as u16),
+        }
+        .as_bytes(),
+    );
+    sa_attributes.extend_from_slice(value);
+}
 
-        (msg, header.initiator_cookie.get())
+/// The given attribute order, completed by the missing attributes of the default order
+fn complete_attribute_order(attribute_order: &[AttributeType]) -> Vec<AttributeType> {
+    let mut complete = vec![];
+    for attribute_type in attribute_order.iter().chain(&DEFAULT_ATTRIBUTE_ORDER) {
+        if DEFAULT_ATTRIBUTE_ORDER.contains(attribute_type) && !complete.contains(attribute_type) {
+            complete.push(*attribute_type);
+        }
     }
+    complete
 }
 
 impl Default for MessageBuilder {
@@ -1,5 +1,6 @@
 //! Implementation of parsers, definitions and message generation for IKEv1
 
 pub mod definitions;
+pub mod fragments;
 pub mod generator;
 pub mod parser;
@@ -13,6 +13,8 @@ pub struct Packet {
     pub header: Header,
     /// Notification payloads
     pub notification_payloads: Vec<NotificationPayload>,
+    /// Delete payloads
+    pub delete_payloads: Vec<DeletePayload>,
     /// Security Association payloads
     pub security_associations: Vec<SecurityAssociationPayload>,
     /// List of vendor ids
@@ -21,6 +23,21 @@ pub struct Packet {
     pub transforms: Vec<TransformPayload>,
     /// List of proposal payloads
     pub proposals: Vec<ProposalPayload>,
+    /// Key exchange payloads
+    pub key_exchange_payloads: Vec<KeyExchangePayload>,
+    /// Nonce payloads
+    pub nonce_payloads: Vec<NoncePayload>,
+    /// NAT discovery payloads
+    pub nat_discovery_payloads: Vec<NatDiscoveryPayload>,
+    /// Identification payloads
+    pub identification_payloads: Vec<IdentificationPayload>,
+    /// Hash payloads
+    pub hash_payloads: Vec<HashPayload>,
+    /// Fragment payloads
+    ///
+    /// A fragmented message carries a single fragment, see [crate::v1::fragments] to
+    /// reassemble the original message.
+    pub fragments: Vec<FragmentPayload>,
 }
 
 /// High level presentation of an ISAKMP header
@@ -65,6 +82,21 @@ pub struct NotificationPayload {
     pub notification: Vec<u8>,
 }
 
+/// High-level representation of a delete payload
+#[derive(Debug, Clone)]
+pub struct DeletePayload {
+    /// The type of the next payload
+    pub next_payload: PayloadType,
+    /// Length of this payload including header
+    pub length: u16,
+    /// The domain of interpretation
+    pub domain_of_interpretation: DomainOfInterpretation,
+    /// Identifies the security association database the SPIs belong to
+    pub protocol_id: u8,
+    /// The SPIs of the deleted security associations
+    pub spis: Vec<Vec<u8>>,
+}
+
 /// High-level representation of a security association payload
 #[derive(Debug, Clone)]
 pub struct SecurityAssociationPayload {
@@ -106,6 +138,8 @@ pub struct ProposalPayload {
     pub spi: Vec<u8>,
     /// Transform payloads
     pub transforms: Vec<TransformPayload>,
+    /// The raw bytes of the whole payload
+    pub raw: Vec<u8>,
 }
 
 /// High-level representation of a transform payload
@@ -139,6 +173,84 @@ pub struct VendorIDPayload {
     pub vendor_id: Vec<u8>,
 }
 
+/// High-level representation of a key exchange payload
+#[derive(Debug, Clone)]
+pub struct KeyExchangePayload {
+    /// The type of the next payload
+    pub next_payload: PayloadType,
+    /// Length of this payload including header
+    pub length: u16,
+    /// Data required to generate a session key, e.g. the public Diffie-Hellman value
+    pub key_exchange_data: Vec<u8>,
+}
+
+/// High-level representation of a nonce payload
+#[derive(Debug, Clone)]
+pub struct NoncePayload {
+    /// The type of the next payload
+    pub next_payload: PayloadType,
+    /// Length of this payload including header
+    pub length: u16,
+    /// Random data generated by the sender
+    pub nonce_data: Vec<u8>,
+}
+
+/// High-level representation of an identification payload
+#[derive(Debug, Clone)]
+pub struct IdentificationPayload {
+    /// The type of the next payload
+    pub next_payload: PayloadType,
+    /// Length of this payload including header
+    pub length: u16,
+    /// The type of the identification data, see [crate::v1::definitions::IdentificationType]
+    pub id_type: u8,
+    /// The IP protocol of the identity, 0 if it is ignored
+    pub protocol_id: u8,
+    /// The port of the identity, 0 if it is ignored
+    pub port: u16,
+    /// The identity, e.g. an IP address or a FQDN
+    pub identification_data: Vec<u8>,
+}
+
+/// High-level representation of a hash payload
+#[derive(Debug, Clone)]
+pub struct HashPayload {
+    /// The type of the next payload
+    pub next_payload: PayloadType,
+    /// Length of this payload including header
+    pub length: u16,
+    /// The result of the negotiated hash function
+    pub hash_data: Vec<u8>,
+}
+
+/// High-level representation of a NAT discovery payload
+#[derive(Debug, Clone)]
+pub struct NatDiscoveryPayload {
+    /// The type of the next payload
+    pub next_payload: PayloadType,
+    /// Length of this payload including header
+    pub length: u16,
+    /// Hash of the cookies, IP address and port of one of the peers
+    pub hash_of_address_and_port: Vec<u8>,
+}
+
+/// High-level representation of a fragment payload
+#[derive(Debug, Clone)]
+pub struct FragmentPayload {
+    /// The type of the next payload
+    pub next_payload: PayloadType,
+    /// Length of this payload including header
+    pub length: u16,
+    /// Identifies the message the fragment belongs to
+    pub fragment_id: u16,
+    /// Position of the fragment in the message, starting at 1
+    pub fragment_number: u8,
+    /// Whether this is the last fragment of the message
+    pub last: bool,
+    /// The part of the message carried by the fragment
+    pub data: Vec<u8>,
+}
+
 /// Possible data attributes
 #[derive(Debug, Clone)]
 pub enum DataAttribute {
@@ -7,6 +7,7 @@ use crate::v1::definitions::CertificateEncodingOther;
 use crate::v1::definitions::ExchangeTypeOther;
 use crate::v1::definitions::InvalidDomainOfInterpretation;
 use crate::v1::definitions::NotifyMessageTypeOther;
+use crate::v1::definitions::PayloadType;
 use crate::v1::definitions::PayloadTypeOther;
 
 /// The errors that can occur while parsing an ISAKMP message
@@ -24,6 +25,59 @@ pub enum IsakmpParseError {
 
     #[error("Informational payload")]
     Informational,
+
+    #[error("Notification data of {length} bytes exceeds the maximum of {max} bytes")]
+    NotificationTooLarge { length: usize, max: usize },
+
+    /// An error occurred while parsing a nested payload
+    ///
+    /// The `offset` is relative to the start of the enclosing buffer
+    #[error("Could not parse {payload_type:?} payload at offset {offset}: {source}")]
+    InPayload {
+        payload_type: PayloadType,
+        offset: usize,
+        #[source]
+        source: Box<IsakmpParseError>,
+    },
+}
+
+impl IsakmpParseError {
+    /// Wrap the error with the payload that was parsed at `offset`
+    pub fn in_payload(self, payload_type: PayloadType, offset: usize) -> Self {
+        Self::InPayload {
+            payload_type,
+            offset,
+            source: Box::new(self),
+        }
+    }
+
+    /// The offset of the innermost payload the error occurred in, relative to the parsed buffer
+    pub fn offset(&self) -> usize {
+        match self {
+            Self::InPayload { offset, source, .. } => offset + source.offset(),
+            _ => 0,
+        }
+    }
+
+    /// The type of the innermost payload the error occurred in
+    pub fn payload_type(&self) -> Option<PayloadType> {
+        match self {
+            Self::InPayload {
+                payload_type,
+                source,
+                ..
+            } => source.payload_type().or(Some(*payload_type)),
+            _ => None,
+        }
+    }
+
+    /// The error without any payload context
+    pub fn root_cause(&self) -> &Self {
+        match self {
+            Self::InPayload { source, .. } => source.root_cause(),
+            _ => self,
+        }
+    }
 }
 
 impl From<PayloadTypeOther> for IsakmpParseError {
@@ -14,14 +14,51 @@ pub mod definitions;
 pub mod errors;
 pub mod header;
 pub mod payload;
+pub mod payload_delete;
+pub mod payload_fragment;
+pub mod payload_hash;
+pub mod payload_identification;
+pub mod payload_key_exchange;
+pub mod payload_nat_discovery;
+pub mod payload_nonce;
 pub mod payload_notification;
 pub mod payload_proposal;
 pub mod payload_sa;
 pub mod payload_transform;
 pub mod payload_vendor_id;
 
-/// Parse an ISAKMP message
+/// Options to limit the parsing of untrusted messages
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Maximum number of bytes of data a notification payload may contain
+    pub max_notification_data: usize,
+    /// Reject proposals and transforms whose `next_payload` doesn't match their position
+    ///
+    /// Every but the last proposal of a security association must point to another proposal,
+    /// and every but the last transform of a proposal must point to another transform.
+    /// Disabled by default, as real-world servers aren't always consistent.
+    pub strict_chaining: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_notification_data: 4096,
+            strict_chaining: false,
+        }
+    }
+}
+
+/// Parse an ISAKMP message with the default [ParseOptions]
 pub fn parse_packet(buf: &[u8]) -> Result<Packet, IsakmpParseError> {
+    parse_packet_with_options(buf, &ParseOptions::default())
+}
+
+/// Parse an ISAKMP message
+pub fn parse_packet_with_options(
+    buf: &[u8],
+    options: &ParseOptions,
+) -> Result<Packet, IsakmpParseError> {
     // Parse header
     let header = header::parse_header(buf)?;
 
@@ -33,10 +70,17 @@ pub fn parse_packet(buf: &[u8]) -> Result<Packet, IsakmpParseError> {
     let mut packet = Packet {
         header,
         notification_payloads: vec![],
+        delete_payloads: vec![],
         security_associations: vec![],
         proposals: vec![],
         transforms: vec![],
         vendor_ids: vec![],
+        key_exchange_payloads: vec![],
+        nonce_payloads: vec![],
+        nat_discovery_payloads: vec![],
+        identification_payloads: vec![],
+        hash_payloads: vec![],
+        fragments: vec![],
     };
 
     let mut next_payload = packet.header.next_payload;
@@ -51,18 +95,30 @@ pub fn parse_packet(buf: &[u8]) -> Result<Packet, IsakmpParseError> {
             payload_size,
             next_payload_type,
             payload,
-        } = parse_next_payload(&buf[curr_offset..], next_payload)?;
+        } = parse_next_payload(&buf[curr_offset..], next_payload, options)
+            .map_err(|err| err.in_payload(next_payload, curr_offset))?;
         curr_offset += payload_size;
         next_payload = next_payload_type;
 
         match payload {
             Payload::Notification(notification) => packet.notification_payloads.push(notification),
+            Payload::Delete(delete) => packet.delete_payloads.push(delete),
             Payload::SecurityAssociation(security_association) => {
                 packet.security_associations.push(security_association)
             }
             Payload::VendorID(vendor_id) => packet.vendor_ids.push(vendor_id),
             Payload::Proposal(proposal) => packet.proposals.push(proposal),
             Payload::Transform(transform) => packet.transforms.push(transform),
+            Payload::KeyExchange(key_exchange) => packet.key_exchange_payloads.push(key_exchange),
+            Payload::Nonce(nonce) => packet.nonce_payloads.push(nonce),
+            Payload::NatDiscovery(nat_discovery) => {
+                packet.nat_discovery_payloads.push(nat_discovery)
+            }
+            Payload::Identification(identification) => {
+                packet.identification_payloads.push(identification)
+            }
+            Payload::Hash(hash) => packet.hash_payloads.push(hash),
+            Payload::Fragment(fragment) => packet.fragments.push(fragment),
         }
     }
 
@@ -1,26 +1,48 @@
 //! Parser for all payloads
 
+use crate::v1::parser::definitions::DeletePayload;
+use crate::v1::parser::definitions::FragmentPayload;
+use crate::v1::parser::definitions::HashPayload;
+use crate::v1::parser::definitions::IdentificationPayload;
+use crate::v1::parser::definitions::KeyExchangePayload;
+use crate::v1::parser::definitions::NatDiscoveryPayload;
+use crate::v1::parser::definitions::NoncePayload;
 use crate::v1::parser::definitions::NotificationPayload;
 use crate::v1::parser::definitions::ProposalPayload;
 use crate::v1::parser::definitions::SecurityAssociationPayload;
 use crate::v1::parser::definitions::TransformPayload;
 use crate::v1::parser::definitions::VendorIDPayload;
 use crate::v1::parser::errors::IsakmpParseError;
+use crate::v1::parser::payload_delete::parse_delete;
+use crate::v1::parser::payload_fragment::parse_fragment;
+use crate::v1::parser::payload_hash::parse_hash;
+use crate::v1::parser::payload_identification::parse_identification;
+use crate::v1::parser::payload_key_exchange::parse_key_exchange;
+use crate::v1::parser::payload_nat_discovery::parse_nat_discovery;
+use crate::v1::parser::payload_nonce::parse_nonce;
 use crate::v1::parser::payload_notification::parse_notification;
 use crate::v1::parser::payload_proposal::parse_proposal;
 use crate::v1::parser::payload_sa::parse_security_association;
 use crate::v1::parser::payload_transform::parse_transform;
 use crate::v1::parser::payload_vendor_id::parse_vendor_id;
+use crate::v1::parser::ParseOptions;
 
 /// All parsable payload types
 #[derive(Debug, Clone)]
 #[allow(missing_docs)]
 pub enum Payload {
     Notification(NotificationPayload),
+    Delete(DeletePayload),
     SecurityAssociation(SecurityAssociationPayload),
     VendorID(VendorIDPayload),
     Proposal(ProposalPayload),
     Transform(TransformPayload),
+    KeyExchange(KeyExchangePayload),
+    Nonce(NoncePayload),
+    NatDiscovery(NatDiscoveryPayload),
+    Identification(IdentificationPayload),
+    Hash(HashPayload),
+    Fragment(FragmentPayload),
 }
 
 /// Representation of a generic payload
@@ -38,11 +60,12 @@ pub struct GenericPayload {
 pub fn parse_next_payload(
     buf: &[u8],
     payload_type: crate::v1::definitions::PayloadType,
+    options: &ParseOptions,
 ) -> Result<GenericPayload, IsakmpParseError> {
     match payload_type {
         crate::v1::definitions::PayloadType::None => Err(IsakmpParseError::UnexpectedPayload),
         crate::v1::definitions::PayloadType::Notification => {
-            let notification = parse_notification(buf)?;
+            let notification = parse_notification(buf, options)?;
 
             Ok(GenericPayload {
                 payload_size: notification.length as usize,
@@ -50,8 +73,17 @@ pub fn parse_next_payload(
                 payload: Payload::Notification(notification),
             })
         }
+        crate::v1::definitions::PayloadType::Delete => {
+            let delete = parse_delete(buf)?;
+
+            Ok(GenericPayload {
+                payload_size: delete.length as usize,
+                next_payload_type: delete.next_payload,
+                payload: Payload::Delete(delete),
+            })
+        }
         crate::v1::definitions::PayloadType::SecurityAssociation => {
-            let sa = parse_security_association(buf)?;
+            let sa = parse_security_association(buf, options)?;
 
             Ok(GenericPayload {
                 payload_size: sa.length as usize,
@@ -69,7 +101,7 @@ pub fn parse_next_payload(
             })
         }
         crate::v1::definitions::PayloadType::Proposal => {
-            let proposal = parse_proposal(buf)?;
+            let proposal = parse_proposal(buf, options)?;
 
             Ok(GenericPayload {
                 payload_size: proposal.length as usize,
@@ -86,6 +118,61 @@ pub fn parse_next_payload(
                 payload: Payload::Transform(transform),
             })
         }
+        crate::v1::definitions::PayloadType::KeyExchange => {
+            let key_exchange = parse_key_exchange(buf)?;
+
+            Ok(GenericPayload {
+                payload_size: key_exchange.length as usize,
+                next_payload_type: key_exchange.next_payload,
+                payload: Payload::KeyExchange(key_exchange),
+            })
+        }
+        crate::v1::definitions::PayloadType::Nonce => {
+            let nonce = parse_nonce(buf)?;
+
+            Ok(GenericPayload {
+                payload_size: nonce.length as usize,
+                next_payload_type: nonce.next_payload,
+                payload: Payload::Nonce(nonce),
+            })
+        }
+        crate::v1::definitions::PayloadType::NatDiscovery
+        | crate::v1::definitions::PayloadType::NatDiscoveryDraft => {
+            let nat_discovery = parse_nat_discovery(buf)?;
+
+            Ok(GenericPayload {
+                payload_size: nat_discovery.length as usize,
+                next_payload_type: nat_discovery.next_payload,
+                payload: Payload::NatDiscovery(nat_discovery),
+            })
+        }
+        crate::v1::definitions::PayloadType::Identification => {
+            let identification = parse_identification(buf)?;
+
+            Ok(GenericPayload {
+                payload_size: identification.length as usize,
+                next_payload_type: identification.next_payload,
+                payload: Payload::Identification(identification),
+            })
+        }
+        crate::v1::definitions::PayloadType::Hash => {
+            let hash = parse_hash(buf)?;
+
+            Ok(GenericPayload {
+                payload_size: hash.length as usize,
+                next_payload_type: hash.next_payload,
+                payload: Payload::Hash(hash),
+            })
+        }
+        crate::v1::definitions::PayloadType::Fragment => {
+            let fragment = parse_fragment(buf)?;
+
+            Ok(GenericPayload {
+                payload_size: fragment.length as usize,
+                next_payload_type: fragment.next_payload,
+                payload: Payload::Fragment(fragment),
+            })
+        }
         _ => {
             todo!("Payload type {payload_type:?} not implemented yet");
         }
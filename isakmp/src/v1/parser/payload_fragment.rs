@@ -0,0 +1,38 @@
+//! Parser of the fragment payload
+
+use zerocopy::FromBytes;
+
+use crate::v1::definitions::PayloadType;
+use crate::v1::definitions::StaticFragmentPayload;
+use crate::v1::definitions::LAST_FRAGMENT;
+use crate::v1::parser::definitions::FragmentPayload;
+use crate::v1::parser::errors::IsakmpParseError;
+
+/// Parse a fragment payload
+pub fn parse_fragment(buf: &[u8]) -> Result<FragmentPayload, IsakmpParseError> {
+    let static_part =
+        StaticFragmentPayload::ref_from_prefix(buf).ok_or(IsakmpParseError::BufferTooSmall)?;
+
+    if static_part.generic_payload_header.reserved != 0 {
+        return Err(IsakmpParseError::UnexpectedPayload);
+    }
+
+    let static_size = size_of::<StaticFragmentPayload>();
+    let length = static_part.generic_payload_header.payload_length.get();
+    if (length as usize) < static_size {
+        return Err(IsakmpParseError::UnexpectedPayload);
+    }
+    let data = buf
+        .get(static_size..length as usize)
+        .ok_or(IsakmpParseError::BufferTooSmall)?
+        .to_vec();
+
+    Ok(FragmentPayload {
+        next_payload: PayloadType::try_from(static_part.generic_payload_header.next_payload)?,
+        length,
+        fragment_id: static_part.fragment_id.get(),
+        fragment_number: static_part.fragment_number,
+        last: static_part.flags & LAST_FRAGMENT != 0,
+        data,
+    })
+}
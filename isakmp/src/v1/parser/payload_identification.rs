@@ -0,0 +1,37 @@
+//! Parser of the identification payload
+
+use zerocopy::FromBytes;
+
+use crate::v1::definitions::PayloadType;
+use crate::v1::definitions::StaticIdentificationPayload;
+use crate::v1::parser::definitions::IdentificationPayload;
+use crate::v1::parser::errors::IsakmpParseError;
+
+/// Parse an identification payload
+pub fn parse_identification(buf: &[u8]) -> Result<IdentificationPayload, IsakmpParseError> {
+    let static_part = StaticIdentificationPayload::ref_from_prefix(buf)
+        .ok_or(IsakmpParseError::BufferTooSmall)?;
+
+    if static_part.generic_payload_header.reserved != 0 {
+        return Err(IsakmpParseError::UnexpectedPayload);
+    }
+
+    let static_size = size_of::<StaticIdentificationPayload>();
+    let length = static_part.generic_payload_header.payload_length.get();
+    if (length as usize) < static_size {
+        return Err(IsakmpParseError::UnexpectedPayload);
+    }
+    let identification_data = buf
+        .get(static_size..length as usize)
+        .ok_or(IsakmpParseError::BufferTooSmall)?
+        .to_vec();
+
+    Ok(IdentificationPayload {
+        next_payload: PayloadType::try_from(static_part.generic_payload_header.next_payload)?,
+        length,
+        id_type: static_part.id_type,
+        protocol_id: static_part.protocol_id,
+        port: static_part.port.get(),
+        identification_data,
+    })
+}
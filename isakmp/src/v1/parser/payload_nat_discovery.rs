@@ -0,0 +1,34 @@
+//! Parser of the NAT discovery payload
+
+use zerocopy::FromBytes;
+
+use crate::v1::definitions::PayloadType;
+use crate::v1::definitions::StaticNatDiscoveryPayload;
+use crate::v1::parser::definitions::NatDiscoveryPayload;
+use crate::v1::parser::errors::IsakmpParseError;
+
+/// Parse a NAT discovery payload
+pub fn parse_nat_discovery(buf: &[u8]) -> Result<NatDiscoveryPayload, IsakmpParseError> {
+    let static_part =
+        StaticNatDiscoveryPayload::ref_from_prefix(buf).ok_or(IsakmpParseError::BufferTooSmall)?;
+
+    if static_part.generic_payload_header.reserved != 0 {
+        return Err(IsakmpParseError::UnexpectedPayload);
+    }
+
+    let static_size = size_of::<StaticNatDiscoveryPayload>();
+    let length = static_part.generic_payload_header.payload_length.get();
+    if (length as usize) < static_size {
+        return Err(IsakmpParseError::UnexpectedPayload);
+    }
+    let hash_of_address_and_port = buf
+        .get(static_size..length as usize)
+        .ok_or(IsakmpParseError::BufferTooSmall)?
+        .to_vec();
+
+    Ok(NatDiscoveryPayload {
+        next_payload: PayloadType::try_from(static_part.generic_payload_header.next_payload)?,
+        length,
+        hash_of_address_and_port,
+    })
+}
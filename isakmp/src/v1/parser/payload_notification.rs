@@ -7,9 +7,15 @@ use crate::v1::definitions::PayloadType;
 use crate::v1::definitions::StaticNotificationPayload;
 use crate::v1::parser::definitions::NotificationPayload;
 use crate::v1::parser::errors::IsakmpParseError;
+use crate::v1::parser::ParseOptions;
 
 /// Parse a notification payload
-pub fn parse_notification(buf: &[u8]) -> Result<NotificationPayload, IsakmpParseError> {
+///
+/// Notifications with more data than [ParseOptions::max_notification_data] are rejected
+pub fn parse_notification(
+    buf: &[u8],
+    options: &ParseOptions,
+) -> Result<NotificationPayload, IsakmpParseError> {
     let static_part =
         StaticNotificationPayload::ref_from_prefix(buf).ok_or(IsakmpParseError::BufferTooSmall)?;
 
@@ -17,12 +23,28 @@ pub fn parse_notification(buf: &[u8]) -> Result<NotificationPayload, IsakmpParse
         return Err(IsakmpParseError::UnexpectedPayload);
     }
 
+    // The notification data follows the SPI and ends with the payload
+    let data_start = size_of::<StaticNotificationPayload>() + static_part.spi_size as usize;
+    let data_end = static_part.generic_payload_header.payload_length.get() as usize;
+    if data_end < data_start {
+        return Err(IsakmpParseError::UnexpectedPayload);
+    }
+    if data_end - data_start > options.max_notification_data {
+        return Err(IsakmpParseError::NotificationTooLarge {
+            length: data_end - data_start,
+            max: options.max_notification_data,
+        });
+    }
+    let data = buf
+        .get(data_start..data_end)
+        .ok_or(IsakmpParseError::BufferTooSmall)?;
+
     let notification = NotificationPayload {
         next_payload: PayloadType::try_from(static_part.generic_payload_header.next_payload)?,
         length: static_part.generic_payload_header.payload_length.get(),
         protocol_id: static_part.protocol_id,
         notify_message_type: NotifyMessageType::try_from(static_part.notify_message_type.get())?,
-        notification: buf[size_of::<StaticNotificationPayload>()..].to_vec(),
+        notification: data.to_vec(),
     };
 
     Ok(notification)
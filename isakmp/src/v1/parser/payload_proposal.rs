@@ -6,9 +6,15 @@ use crate::v1::definitions::PayloadType;
 use crate::v1::parser::definitions::ProposalPayload;
 use crate::v1::parser::errors::IsakmpParseError;
 use crate::v1::parser::payload_transform::parse_transform;
+use crate::v1::parser::ParseOptions;
 
 /// Parse a proposal payload
-pub fn parse_proposal(buf: &[u8]) -> Result<ProposalPayload, IsakmpParseError> {
+///
+/// If [ParseOptions::strict_chaining] is set, the `next_payload` of the transforms is validated
+pub fn parse_proposal(
+    buf: &[u8],
+    options: &ParseOptions,
+) -> Result<ProposalPayload, IsakmpParseError> {
     let static_part = crate::v1::definitions::StaticProposalPayload::ref_from_prefix(buf)
         .ok_or(IsakmpParseError::BufferTooSmall)?;
 
@@ -23,6 +29,11 @@ pub fn parse_proposal(buf: &[u8]) -> Result<ProposalPayload, IsakmpParseError> {
         .ok_or(IsakmpParseError::BufferTooSmall)?
         .to_vec();
 
+    let raw = buf
+        .get(..static_part.generic_payload_header.payload_length.get() as usize)
+        .ok_or(IsakmpParseError::BufferTooSmall)?
+        .to_vec();
+
     let mut proposal = ProposalPayload {
         next_payload: PayloadType::try_from(static_part.generic_payload_header.next_payload)?,
         length: static_part.generic_payload_header.payload_length.get(),
@@ -32,14 +43,32 @@ pub fn parse_proposal(buf: &[u8]) -> Result<ProposalPayload, IsakmpParseError> {
         no_of_transforms: static_part.no_of_transforms,
         spi,
         transforms: vec![],
+        raw,
     };
 
-    let remaining = &buf[static_size + static_part.spi_size as usize..];
+    let transforms_offset = static_size + static_part.spi_size as usize;
+    let remaining = buf
+        .get(transforms_offset..proposal.length as usize)
+        .ok_or(IsakmpParseError::BufferTooSmall)?;
 
     let mut start = 0;
     while start < remaining.len() {
-        let transform = parse_transform(&remaining[start..])?;
+        let transform = parse_transform(&remaining[start..])
+            .map_err(|err| err.in_payload(PayloadType::Transform, transforms_offset + start))?;
+        let offset = transforms_offset + start;
         start += transform.length as usize;
+
+        let expected_next_payload = if start < remaining.len() {
+            PayloadType::Transform
+        } else {
+            PayloadType::None
+        };
+        if options.strict_chaining && transform.next_payload != expected_next_payload {
+            return Err(
+                IsakmpParseError::UnexpectedPayload.in_payload(PayloadType::Transform, offset)
+            );
+        }
+
         proposal.transforms.push(transform);
     }
 
@@ -8,10 +8,12 @@ use crate::v1::definitions::StaticSecurityAssociationPayload;
 use crate::v1::parser::definitions::SecurityAssociationPayload;
 use crate::v1::parser::errors::IsakmpParseError;
 use crate::v1::parser::payload_proposal::parse_proposal;
+use crate::v1::parser::ParseOptions;
 
 /// Parse a security association payload
 pub fn parse_security_association(
     buf: &[u8],
+    options: &ParseOptions,
 ) -> Result<SecurityAssociationPayload, IsakmpParseError> {
     let static_part = StaticSecurityAssociationPayload::ref_from_prefix(buf)
         .ok_or(IsakmpParseError::BufferTooSmall)?;
@@ -42,8 +44,22 @@ pub fn parse_security_association(
     let remaining = &buf[static_size + 4..security_association.length as usize];
     let mut start = 0;
     while start < remaining.len() {
-        let payload = parse_proposal(&remaining[start..])?;
+        let payload = parse_proposal(&remaining[start..], options)
+            .map_err(|err| err.in_payload(PayloadType::Proposal, static_size + 4 + start))?;
+        let offset = static_size + 4 + start;
         start += payload.length as usize;
+
+        let expected_next_payload = if start < remaining.len() {
+            PayloadType::Proposal
+        } else {
+            PayloadType::None
+        };
+        if options.strict_chaining && payload.next_payload != expected_next_payload {
+            return Err(
+                IsakmpParseError::UnexpectedPayload.in_payload(PayloadType::Proposal, offset)
+            );
+        }
+
         security_association.proposal_payload.push(payload);
     }
 
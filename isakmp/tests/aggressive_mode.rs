@@ -0,0 +1,99 @@
+//! Building aggressive mode messages
+
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::ExchangeType;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::definitions::IdentificationType;
+use isakmp::v1::generator::Identity;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::NONCE_LENGTH;
+use isakmp::v1::parser::parse_packet;
+
+fn transform(group_description: GroupDescription) -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description,
+        key_size: Some(256),
+        extra_attributes: vec![],
+    }
+}
+
+fn identity() -> Identity {
+    Identity {
+        id_type: IdentificationType::ID_FQDN,
+        data: b"vpn.example.com".to_vec(),
+    }
+}
+
+#[test]
+fn mandatory_payloads() {
+    let (msg, initiator_cookie) = MessageBuilder::new()
+        .aggressive_mode(identity())
+        .add_transform(transform(GroupDescription::MODP_2048))
+        .add_transform(transform(GroupDescription::MODP_2048))
+        .build();
+
+    let packet = parse_packet(&msg).unwrap();
+    assert_eq!(packet.header.initiator_cookie, initiator_cookie);
+    assert_eq!(packet.header.exchange_mode, ExchangeType::Aggressive);
+    assert_eq!(packet.security_associations.len(), 1);
+
+    assert_eq!(packet.key_exchange_payloads.len(), 1);
+    assert_eq!(packet.key_exchange_payloads[0].key_exchange_data.len(), 256);
+    assert_eq!(packet.nonce_payloads.len(), 1);
+    assert_eq!(packet.nonce_payloads[0].nonce_data.len(), NONCE_LENGTH);
+    assert_eq!(packet.identification_payloads.len(), 1);
+    let identification = &packet.identification_payloads[0];
+    assert_eq!(identification.id_type, IdentificationType::ID_FQDN as u8);
+    assert_eq!(identification.protocol_id, 0);
+    assert_eq!(identification.port, 0);
+    assert_eq!(identification.identification_data, b"vpn.example.com");
+}
+
+#[test]
+fn key_exchange_matches_group() {
+    let (msg, _) = MessageBuilder::new()
+        .aggressive_mode(identity())
+        .add_transform(transform(GroupDescription::ECP_Random_256))
+        .build();
+
+    let packet = parse_packet(&msg).unwrap();
+    assert_eq!(packet.key_exchange_payloads[0].key_exchange_data.len(), 64);
+}
+
+#[test]
+fn exchange_type_only_changes_header() {
+    let (msg, _) = MessageBuilder::new()
+        .exchange_type(ExchangeType::Aggressive)
+        .add_transform(transform(GroupDescription::MODP_2048))
+        .build();
+
+    let packet = parse_packet(&msg).unwrap();
+    assert_eq!(packet.header.exchange_mode, ExchangeType::Aggressive);
+    assert!(packet.key_exchange_payloads.is_empty());
+    assert!(packet.identification_payloads.is_empty());
+}
+
+#[test]
+fn message_is_padded_to_multiple_of_four() {
+    for len in 1..=8 {
+        let data = vec![b'a'; len];
+        let (msg, _) = MessageBuilder::new()
+            .aggressive_mode(Identity {
+                id_type: IdentificationType::ID_FQDN,
+                data: data.clone(),
+            })
+            .add_transform(transform(GroupDescription::MODP_2048))
+            .build();
+
+        assert_eq!(msg.len() % 4, 0, "identity of {len} bytes");
+        let packet = parse_packet(&msg).unwrap();
+        assert_eq!(packet.header.length as usize, msg.len());
+        assert_eq!(packet.identification_payloads[0].identification_data, data);
+    }
+}
@@ -0,0 +1,98 @@
+//! Serialization of the SA attributes of a single transform
+
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::AttributeValue;
+use isakmp::v1::generator::Lifetime;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::parser::definitions::DataAttribute;
+use isakmp::v1::parser::parse_packet;
+
+fn transform_aes_256() -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::RSASignatures,
+        group_description: GroupDescription::MODP_2048,
+        key_size: Some(256),
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn aes_256_attribute_bytes() {
+    assert_eq!(
+        transform_aes_256().to_attribute_bytes(),
+        [
+            0x80, 0x01, 0x00, 0x07, // Encryption algorithm: AES-CBC
+            0x80, 0x02, 0x00, 0x04, // Hash algorithm: SHA2-256
+            0x80, 0x03, 0x00, 0x03, // Authentication method: RSA signatures
+            0x80, 0x04, 0x00, 0x0e, // Group description: MODP 2048
+            0x80, 0x0b, 0x00, 0x01, // Life type: seconds
+            0x80, 0x0c, 0x1b, 0xa8, // Life duration: 7080
+            0x80, 0x0e, 0x01, 0x00, // Key length: 256
+        ]
+    );
+}
+
+#[test]
+fn attribute_bytes_match_built_message() {
+    let lifetime = Lifetime::default();
+    let order = [AttributeType::EncryptionAlgorithm, AttributeType::KeyLength];
+    let attributes = transform_aes_256().attribute_bytes(&lifetime, &order);
+    assert_eq!(&attributes[4..8], &[0x80, 0x0e, 0x01, 0x00]);
+
+    let (msg, _) = MessageBuilder::new()
+        .attribute_order(order.to_vec())
+        .add_transform(transform_aes_256())
+        .build();
+    assert!(msg
+        .windows(attributes.len())
+        .any(|window| window == attributes.as_slice()));
+}
+
+#[test]
+fn extra_attributes_roundtrip() {
+    let transform = Transform {
+        extra_attributes: vec![
+            (AttributeType::PRF, AttributeValue::Short(1)),
+            (
+                AttributeType::GroupOrder,
+                AttributeValue::Long(vec![0xff; 6]),
+            ),
+        ],
+        ..transform_aes_256()
+    };
+    let attributes = transform.to_attribute_bytes();
+    assert_eq!(
+        attributes[transform_aes_256().to_attribute_bytes().len()..],
+        [
+            0x80, 0x0d, 0x00, 0x01, // PRF: 1
+            0x00, 0x10, 0x00, 0x06, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // Group order
+        ]
+    );
+
+    let (msg, _) = MessageBuilder::new().add_transform(transform).build();
+    let packet = parse_packet(&msg).unwrap();
+    let sa_attributes =
+        &packet.security_associations[0].proposal_payload[0].transforms[0].sa_attributes;
+    assert_eq!(sa_attributes.len(), 9);
+    match &sa_attributes[7] {
+        DataAttribute::DataAttributeShort(attr) => {
+            assert_eq!(attr.attribute_type, AttributeType::PRF);
+            assert_eq!(attr.attribute_value, 1);
+        }
+        attr => panic!("Expected a short attribute, got {attr:?}"),
+    }
+    match &sa_attributes[8] {
+        DataAttribute::DataAttributeLong(attr) => {
+            assert_eq!(attr.attribute_type, AttributeType::GroupOrder);
+            assert_eq!(attr.attribute_value, [0xff; 6]);
+        }
+        attr => panic!("Expected a variable length attribute, got {attr:?}"),
+    }
+}
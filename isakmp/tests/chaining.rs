@@ -0,0 +1,72 @@
+//! Strict validation of the chaining of proposals and transforms
+
+use isakmp::strum::IntoEnumIterator;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::definitions::PayloadType;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::parser::errors::IsakmpParseError;
+use isakmp::v1::parser::parse_packet;
+use isakmp::v1::parser::parse_packet_with_options;
+use isakmp::v1::parser::ParseOptions;
+
+/// Header (28) + SA payload (8) + situation (4) + proposal payload (8)
+const TRANSFORM_OFFSET: usize = 48;
+
+/// Size of a transform without key length attribute
+const TRANSFORM_SIZE: usize = 32;
+
+fn message() -> Vec<u8> {
+    let mut mb = MessageBuilder::new();
+    for group_description in GroupDescription::iter().skip(1).take(3) {
+        mb = mb.add_transform(Transform {
+            encryption_algorithm: EncryptionAlgorithm::TrippleDES_CBC,
+            hash_algorithm: HashAlgorithm::SHA,
+            authentication_method: AuthenticationMethod::PreSharedKey,
+            group_description,
+            key_size: None,
+            extra_attributes: vec![],
+        });
+    }
+    mb.build().0
+}
+
+fn strict() -> ParseOptions {
+    ParseOptions {
+        strict_chaining: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn consistent_chaining_is_accepted() {
+    assert!(parse_packet_with_options(&message(), &strict()).is_ok());
+}
+
+#[test]
+fn middle_transform_without_next_payload() {
+    let mut msg = message();
+    let middle = TRANSFORM_OFFSET + TRANSFORM_SIZE;
+    assert_eq!(msg[middle], PayloadType::Transform as u8);
+    msg[middle] = PayloadType::None as u8;
+
+    // Lenient by default
+    let packet = parse_packet(&msg).unwrap();
+    assert_eq!(
+        packet.security_associations[0].proposal_payload[0]
+            .transforms
+            .len(),
+        3
+    );
+
+    let err = parse_packet_with_options(&msg, &strict()).unwrap_err();
+    assert_eq!(err.offset(), middle);
+    assert_eq!(err.payload_type(), Some(PayloadType::Transform));
+    assert!(matches!(
+        err.root_cause(),
+        IsakmpParseError::UnexpectedPayload
+    ));
+}
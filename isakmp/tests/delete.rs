@@ -0,0 +1,39 @@
+//! Parsing of delete payloads
+
+use isakmp::v1::definitions::DomainOfInterpretation;
+use isakmp::v1::definitions::PayloadType;
+use isakmp::v1::parser::parse_packet;
+
+#[test]
+fn delete_with_two_spis() {
+    #[rustfmt::skip]
+    let msg = [
+        // Header
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // initiator cookie
+        0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, // responder cookie
+        0x0c, 0x10, 0x05, 0x00, // next payload, version, exchange type, flags
+        0x00, 0x00, 0x00, 0x00, // message id
+        0x00, 0x00, 0x00, 0x30, // length
+        // Delete
+        0x00, 0x00, 0x00, 0x14, // next payload, reserved, length
+        0x00, 0x00, 0x00, 0x01, // doi
+        0x03, 0x04, 0x00, 0x02, // protocol id, spi size, number of spis
+        0xaa, 0xbb, 0xcc, 0xdd, // first spi
+        0x11, 0x22, 0x33, 0x44, // second spi
+    ];
+
+    let packet = parse_packet(&msg).unwrap();
+
+    assert_eq!(packet.delete_payloads.len(), 1);
+    let delete = &packet.delete_payloads[0];
+    assert_eq!(delete.next_payload, PayloadType::None);
+    assert_eq!(
+        delete.domain_of_interpretation,
+        DomainOfInterpretation::IPSEC
+    );
+    assert_eq!(delete.protocol_id, 3);
+    assert_eq!(
+        delete.spis,
+        [vec![0xaa, 0xbb, 0xcc, 0xdd], vec![0x11, 0x22, 0x33, 0x44]]
+    );
+}
@@ -0,0 +1,47 @@
+//! The human-readable form of transforms
+
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::AttributeValue;
+use isakmp::v1::generator::Transform;
+
+fn transform(key_size: Option<u16>) -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_2048,
+        key_size,
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn key_size_is_appended_to_encryption_algorithm() {
+    assert_eq!(
+        transform(Some(256)).to_string(),
+        "ENC=AES_CBC/256 HASH=SHA2_256 AUTH=PreSharedKey GROUP=MODP_2048"
+    );
+}
+
+#[test]
+fn without_key_size() {
+    assert_eq!(
+        transform(None).to_string(),
+        "ENC=AES_CBC HASH=SHA2_256 AUTH=PreSharedKey GROUP=MODP_2048"
+    );
+}
+
+#[test]
+fn extra_attributes_are_omitted() {
+    let mut transform = transform(Some(128));
+    transform.extra_attributes = vec![(AttributeType::PRF, AttributeValue::Short(1))];
+
+    assert_eq!(
+        transform.to_string(),
+        "ENC=AES_CBC/128 HASH=SHA2_256 AUTH=PreSharedKey GROUP=MODP_2048"
+    );
+}
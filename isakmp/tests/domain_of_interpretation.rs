@@ -0,0 +1,77 @@
+//! Building security associations with other domains of interpretation and situations
+
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::DomainOfInterpretation;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::DOI;
+use isakmp::v1::generator::SITUATION;
+use isakmp::v1::parser::parse_packet;
+
+/// Offset of the domain of interpretation, following the header and the generic payload header
+const DOI_OFFSET: usize = 28 + 4;
+
+fn message(mb: MessageBuilder) -> Vec<u8> {
+    mb.add_transform(Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_2048,
+        key_size: Some(128),
+        extra_attributes: vec![],
+    })
+    .build()
+    .0
+}
+
+#[test]
+fn ipsec_doi_by_default() {
+    let packet = parse_packet(&message(MessageBuilder::new())).unwrap();
+    let sa = &packet.security_associations[0];
+    assert_eq!(sa.domain_of_interpretation, DOI);
+    assert_eq!(sa.situation, SITUATION);
+}
+
+#[test]
+fn other_doi_and_situation() {
+    let msg = message(
+        MessageBuilder::new()
+            .doi(DomainOfInterpretation::GDOI as u32)
+            .situation(vec![0, 0, 0, 4]),
+    );
+
+    let packet = parse_packet(&msg).unwrap();
+    let sa = &packet.security_associations[0];
+    assert_eq!(sa.domain_of_interpretation, DomainOfInterpretation::GDOI);
+    assert_eq!(sa.situation, [0, 0, 0, 4]);
+    assert_eq!(sa.proposal_payload.len(), 1);
+}
+
+#[test]
+fn unassigned_doi() {
+    let msg = message(MessageBuilder::new().doi(0xdead_beef));
+    assert_eq!(
+        msg[DOI_OFFSET..DOI_OFFSET + 4],
+        0xdead_beef_u32.to_be_bytes()
+    );
+    assert!(parse_packet(&msg).is_err());
+}
+
+#[test]
+fn longer_situation_is_included_in_lengths() {
+    let default = message(MessageBuilder::new());
+    let msg = message(MessageBuilder::new().situation(vec![0, 0, 0, 1, 0, 0, 0, 0]));
+
+    assert_eq!(msg.len(), default.len() + 4);
+    assert_eq!(
+        msg[DOI_OFFSET + 4..DOI_OFFSET + 12],
+        [0, 0, 0, 1, 0, 0, 0, 0]
+    );
+    let sa_length = u16::from_be_bytes([msg[30], msg[31]]) as usize;
+    assert_eq!(sa_length, msg.len() - 28);
+    let length = u32::from_be_bytes([msg[24], msg[25], msg[26], msg[27]]) as usize;
+    assert_eq!(length, msg.len());
+}
@@ -0,0 +1,78 @@
+//! Building messages of other exchange types than main mode
+
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::ExchangeType;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::definitions::NotifyMessageType;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::NONCE_LENGTH;
+use isakmp::v1::parser::parse_packet;
+
+/// Offset of the exchange type in the header
+const EXCHANGE_TYPE_OFFSET: usize = 18;
+
+fn message(mb: MessageBuilder) -> Vec<u8> {
+    mb.add_transform(Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_2048,
+        key_size: Some(128),
+        extra_attributes: vec![],
+    })
+    .build()
+    .0
+}
+
+#[test]
+fn main_mode_by_default() {
+    let msg = message(MessageBuilder::new());
+    assert_eq!(
+        msg[EXCHANGE_TYPE_OFFSET],
+        ExchangeType::IdentityProtection as u8
+    );
+}
+
+#[test]
+fn base_mode_header() {
+    let msg = message(MessageBuilder::new().exchange_type(ExchangeType::Base));
+    assert_eq!(msg[EXCHANGE_TYPE_OFFSET], ExchangeType::Base as u8);
+
+    let packet = parse_packet(&msg).unwrap();
+    assert_eq!(packet.header.exchange_mode, ExchangeType::Base);
+    assert_eq!(packet.security_associations.len(), 1);
+}
+
+#[test]
+fn base_mode_nonce() {
+    let msg = message(
+        MessageBuilder::new()
+            .exchange_type(ExchangeType::Base)
+            .nonce(),
+    );
+
+    let packet = parse_packet(&msg).unwrap();
+    assert_eq!(packet.header.exchange_mode, ExchangeType::Base);
+    assert_eq!(packet.security_associations.len(), 1);
+    assert_eq!(packet.nonce_payloads.len(), 1);
+    assert_eq!(packet.nonce_payloads[0].nonce_data.len(), NONCE_LENGTH);
+    assert!(packet.key_exchange_payloads.is_empty());
+    assert!(packet.identification_payloads.is_empty());
+}
+
+#[test]
+fn informational_notification() {
+    let msg = message(MessageBuilder::new().informational(NotifyMessageType::InitialContact));
+    assert_eq!(msg[EXCHANGE_TYPE_OFFSET], ExchangeType::Informational as u8);
+
+    let packet = parse_packet(&msg).unwrap();
+    assert!(packet.security_associations.is_empty());
+    assert_eq!(packet.notification_payloads.len(), 1);
+    assert_eq!(
+        packet.notification_payloads[0].notify_message_type,
+        NotifyMessageType::InitialContact
+    );
+}
@@ -0,0 +1,146 @@
+//! Splitting and reassembly of fragmented messages
+
+use isakmp::v1::definitions::ExchangeType;
+use isakmp::v1::definitions::PayloadType;
+use isakmp::v1::fragments::fragment_message;
+use isakmp::v1::fragments::FragmentError;
+use isakmp::v1::fragments::Reassembler;
+use isakmp::v1::parser::definitions::FragmentPayload;
+use isakmp::v1::parser::parse_packet;
+
+/// A message with a single large nonce payload
+fn message(initiator_cookie: u64) -> Vec<u8> {
+    let mut msg = vec![];
+    msg.extend_from_slice(&initiator_cookie.to_be_bytes());
+    msg.extend_from_slice(&2u64.to_be_bytes());
+    msg.extend_from_slice(&[
+        PayloadType::Nonce as u8,
+        0x10,
+        ExchangeType::IdentityProtection as u8,
+        0,
+    ]);
+    msg.extend_from_slice(&0u32.to_be_bytes());
+    msg.extend_from_slice(&0u32.to_be_bytes());
+
+    let nonce: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    msg.extend_from_slice(&[PayloadType::None as u8, 0]);
+    msg.extend_from_slice(&(4 + nonce.len() as u16).to_be_bytes());
+    msg.extend_from_slice(&nonce);
+
+    let length = msg.len() as u32;
+    msg[24..28].copy_from_slice(&length.to_be_bytes());
+    msg
+}
+
+fn fragment(fragment_id: u16, fragment_number: u8, last: bool) -> FragmentPayload {
+    FragmentPayload {
+        next_payload: PayloadType::None,
+        length: 8 + 1,
+        fragment_id,
+        fragment_number,
+        last,
+        data: vec![fragment_number],
+    }
+}
+
+#[test]
+fn fragments_are_parsed() {
+    let msg = message(1);
+    let fragments = fragment_message(&msg, 7, 400).unwrap();
+    assert_eq!(fragments.len(), 3);
+
+    for (index, datagram) in fragments.iter().enumerate() {
+        let packet = parse_packet(datagram).unwrap();
+        assert_eq!(packet.header.initiator_cookie, 1);
+        assert_eq!(packet.header.next_payload, PayloadType::Fragment);
+        assert_eq!(packet.header.length as usize, datagram.len());
+        assert!(packet.nonce_payloads.is_empty());
+
+        let [fragment] = packet.fragments.as_slice() else {
+            panic!("Expected a single fragment, got {:?}", packet.fragments);
+        };
+        assert_eq!(fragment.fragment_id, 7);
+        assert_eq!(fragment.fragment_number as usize, index + 1);
+        assert_eq!(fragment.last, index == 2);
+    }
+}
+
+#[test]
+fn out_of_order_fragments_are_reassembled() {
+    let msg = message(1);
+    let mut reassembler = Reassembler::default();
+
+    let mut fragments = fragment_message(&msg, 7, 400).unwrap();
+    fragments.reverse();
+    let (first, rest) = fragments.split_last().unwrap();
+    for datagram in rest {
+        let packet = parse_packet(datagram).unwrap();
+        assert_eq!(reassembler.add_packet(&packet), Ok(None));
+    }
+    assert_eq!(reassembler.pending(), 1);
+
+    let packet = parse_packet(first).unwrap();
+    let reassembled = reassembler.add_packet(&packet).unwrap().unwrap();
+    assert_eq!(reassembled, msg);
+    assert_eq!(reassembler.pending(), 0);
+
+    let packet = parse_packet(&reassembled).unwrap();
+    assert_eq!(packet.nonce_payloads[0].nonce_data.len(), 1000);
+}
+
+#[test]
+fn messages_are_reassembled_separately() {
+    let mut reassembler = Reassembler::default();
+    assert_eq!(reassembler.add(1, &fragment(1, 1, false)), Ok(None));
+    assert_eq!(reassembler.add(2, &fragment(1, 2, true)), Ok(None));
+    assert_eq!(reassembler.add(1, &fragment(2, 2, true)), Ok(None));
+    assert_eq!(reassembler.pending(), 3);
+
+    assert_eq!(
+        reassembler.add(1, &fragment(1, 2, true)),
+        Ok(Some(vec![1, 2]))
+    );
+    assert_eq!(reassembler.pending(), 2);
+}
+
+#[test]
+fn oldest_message_is_discarded() {
+    let mut reassembler = Reassembler::new(2, 32);
+    assert_eq!(reassembler.add(1, &fragment(1, 1, false)), Ok(None));
+    assert_eq!(reassembler.add(2, &fragment(1, 1, false)), Ok(None));
+    assert_eq!(reassembler.add(3, &fragment(1, 1, false)), Ok(None));
+    assert_eq!(reassembler.pending(), 2);
+
+    // The first fragment of the oldest message is gone
+    assert_eq!(reassembler.add(1, &fragment(1, 2, true)), Ok(None));
+    assert_eq!(
+        reassembler.add(3, &fragment(1, 2, true)),
+        Ok(Some(vec![1, 2]))
+    );
+}
+
+#[test]
+fn invalid_fragments() {
+    let mut reassembler = Reassembler::new(16, 4);
+    assert_eq!(
+        reassembler.add(1, &fragment(1, 0, false)),
+        Err(FragmentError::InvalidNumber(0))
+    );
+    assert_eq!(
+        reassembler.add(1, &fragment(1, 5, true)),
+        Err(FragmentError::TooManyFragments(4))
+    );
+
+    assert_eq!(reassembler.add(1, &fragment(1, 3, true)), Ok(None));
+    assert_eq!(
+        reassembler.add(1, &fragment(1, 2, true)),
+        Err(FragmentError::InconsistentLast)
+    );
+    assert_eq!(reassembler.pending(), 0);
+
+    assert_eq!(reassembler.add(1, &fragment(1, 3, false)), Ok(None));
+    assert_eq!(
+        reassembler.add(1, &fragment(1, 2, true)),
+        Err(FragmentError::InconsistentLast)
+    );
+}
@@ -0,0 +1,29 @@
+//! Strength of the key exchange groups
+
+use isakmp::strum::IntoEnumIterator;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::MIN_GROUP_SECURITY_BITS;
+
+#[test]
+fn weak_groups() {
+    assert!(GroupDescription::MODP_768.is_weak());
+    assert!(GroupDescription::MODP_1024.is_weak());
+    assert!(!GroupDescription::MODP_2048.is_weak());
+    assert!(!GroupDescription::ECP_Random_256.is_weak());
+}
+
+#[test]
+fn security_bits() {
+    assert_eq!(GroupDescription::MODP_1024.approx_security_bits(), Some(80));
+    assert_eq!(
+        GroupDescription::MODP_2048.approx_security_bits(),
+        Some(112)
+    );
+    assert_eq!(GroupDescription::Reserved.approx_security_bits(), None);
+    assert!(!GroupDescription::Reserved.is_weak());
+
+    for group in GroupDescription::iter().skip(1) {
+        let bits = group.approx_security_bits().unwrap();
+        assert_eq!(group.is_weak(), bits < MIN_GROUP_SECURITY_BITS, "{group}");
+    }
+}
@@ -0,0 +1,70 @@
+//! Parsing of the payloads of a key exchange with NAT discovery
+
+use isakmp::v1::definitions::ExchangeType;
+use isakmp::v1::definitions::PayloadType;
+use isakmp::v1::parser::parse_packet;
+
+/// Append a payload with the given data
+fn push_payload(msg: &mut Vec<u8>, next_payload: PayloadType, data: &[u8]) {
+    msg.extend_from_slice(&[next_payload as u8, 0]);
+    msg.extend_from_slice(&(4 + data.len() as u16).to_be_bytes());
+    msg.extend_from_slice(data);
+}
+
+/// A message with a key exchange, a nonce and two NAT discovery payloads
+fn message(nat_discovery: PayloadType) -> Vec<u8> {
+    let mut msg = vec![];
+    msg.extend_from_slice(&1u64.to_be_bytes());
+    msg.extend_from_slice(&2u64.to_be_bytes());
+    msg.extend_from_slice(&[
+        PayloadType::KeyExchange as u8,
+        0x10,
+        ExchangeType::IdentityProtection as u8,
+        0,
+    ]);
+    msg.extend_from_slice(&0u32.to_be_bytes());
+    msg.extend_from_slice(&0u32.to_be_bytes());
+
+    push_payload(&mut msg, PayloadType::Nonce, &[0xab; 128]);
+    push_payload(&mut msg, nat_discovery, &[0xcd; 16]);
+    push_payload(&mut msg, nat_discovery, &[0x01; 20]);
+    push_payload(&mut msg, PayloadType::None, &[0x02; 20]);
+
+    let length = msg.len() as u32;
+    msg[24..28].copy_from_slice(&length.to_be_bytes());
+    msg
+}
+
+#[test]
+fn two_nat_discovery_payloads() {
+    let packet = parse_packet(&message(PayloadType::NatDiscovery)).unwrap();
+
+    assert_eq!(packet.key_exchange_payloads.len(), 1);
+    assert_eq!(
+        packet.key_exchange_payloads[0].key_exchange_data,
+        [0xab; 128]
+    );
+    assert_eq!(packet.nonce_payloads.len(), 1);
+    assert_eq!(packet.nonce_payloads[0].nonce_data, [0xcd; 16]);
+
+    let hashes: Vec<&[u8]> = packet
+        .nat_discovery_payloads
+        .iter()
+        .map(|x| x.hash_of_address_and_port.as_slice())
+        .collect();
+    assert_eq!(hashes, [[0x01; 20].as_slice(), [0x02; 20].as_slice()]);
+}
+
+#[test]
+fn draft_payload_type() {
+    let packet = parse_packet(&message(PayloadType::NatDiscoveryDraft)).unwrap();
+    assert_eq!(packet.nat_discovery_payloads.len(), 2);
+}
+
+#[test]
+fn truncated_payload() {
+    let mut msg = message(PayloadType::NatDiscovery);
+    msg.truncate(msg.len() - 10);
+    let err = parse_packet(&msg).unwrap_err();
+    assert_eq!(err.payload_type(), Some(PayloadType::NatDiscovery));
+}
@@ -0,0 +1,68 @@
+//! Notifications are bounded by their payload length and the configured maximum
+
+use isakmp::v1::definitions::NotifyMessageType;
+use isakmp::v1::parser::errors::IsakmpParseError;
+use isakmp::v1::parser::parse_packet;
+use isakmp::v1::parser::parse_packet_with_options;
+use isakmp::v1::parser::ParseOptions;
+
+/// Build an informational message with a single NO-PROPOSAL-CHOSEN notification
+fn informational(data: &[u8]) -> Vec<u8> {
+    let payload_length = 12 + data.len();
+    let length = 28 + payload_length;
+
+    let mut msg = vec![];
+    msg.extend_from_slice(&0x0102_0304_0506_0708u64.to_be_bytes());
+    msg.extend_from_slice(&[0; 8]);
+    // next payload, version, exchange type, flags
+    msg.extend_from_slice(&[0x0b, 0x10, 0x05, 0x00]);
+    msg.extend_from_slice(&[0; 4]);
+    msg.extend_from_slice(&(length as u32).to_be_bytes());
+
+    // next payload, reserved, length
+    msg.extend_from_slice(&[0x00, 0x00]);
+    msg.extend_from_slice(&(payload_length as u16).to_be_bytes());
+    // doi
+    msg.extend_from_slice(&[0x00, 0x00, 0x00, 0x01]);
+    // protocol id, spi size, notify message type
+    msg.extend_from_slice(&[0x01, 0x00, 0x00, 0x0e]);
+    msg.extend_from_slice(data);
+
+    msg
+}
+
+#[test]
+fn notification_data_ends_with_payload() {
+    let mut msg = informational(b"no proposal");
+    // Trailing bytes are not part of the notification
+    msg.extend_from_slice(&[0; 4]);
+
+    let packet = parse_packet(&msg).unwrap();
+    assert_eq!(packet.notification_payloads.len(), 1);
+    let notification = &packet.notification_payloads[0];
+    assert_eq!(
+        notification.notify_message_type,
+        NotifyMessageType::NoProposalChosen
+    );
+    assert_eq!(notification.notification, b"no proposal");
+}
+
+#[test]
+fn oversized_notification_is_rejected() {
+    let msg = informational(&[0x41; 8192]);
+
+    let err = parse_packet(&msg).unwrap_err();
+    assert!(matches!(
+        err.root_cause(),
+        IsakmpParseError::NotificationTooLarge {
+            length: 8192,
+            max: 4096
+        }
+    ));
+
+    let options = ParseOptions {
+        max_notification_data: 8192,
+        ..Default::default()
+    };
+    assert!(parse_packet_with_options(&msg, &options).is_ok());
+}
@@ -0,0 +1,48 @@
+//! Errors of the parser point at the payload that couldn't be parsed
+
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::definitions::PayloadType;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::parser::errors::IsakmpParseError;
+use isakmp::v1::parser::parse_packet;
+
+/// Header (28) + SA payload (8) + situation (4) + proposal payload (8)
+const TRANSFORM_OFFSET: usize = 48;
+
+fn message() -> Vec<u8> {
+    let (msg, _) = MessageBuilder::new()
+        .add_transform(Transform {
+            encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+            hash_algorithm: HashAlgorithm::SHA2_256,
+            authentication_method: AuthenticationMethod::PreSharedKey,
+            group_description: GroupDescription::MODP_2048,
+            key_size: Some(256),
+            extra_attributes: vec![],
+        })
+        .build();
+    msg
+}
+
+#[test]
+fn valid_message_parses() {
+    assert!(parse_packet(&message()).is_ok());
+}
+
+#[test]
+fn offset_points_at_bad_transform() {
+    let mut msg = message();
+    // Set the reserved byte of the transform's generic payload header
+    msg[TRANSFORM_OFFSET + 1] = 1;
+
+    let err = parse_packet(&msg).unwrap_err();
+    assert_eq!(err.offset(), TRANSFORM_OFFSET);
+    assert_eq!(err.payload_type(), Some(PayloadType::Transform));
+    assert!(matches!(
+        err.root_cause(),
+        IsakmpParseError::UnexpectedPayload
+    ));
+}
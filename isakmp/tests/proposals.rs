@@ -0,0 +1,130 @@
+//! Building messages with a security parameter index and multiple proposals
+
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::definitions::IdentificationType;
+use isakmp::v1::definitions::PayloadType;
+use isakmp::v1::generator::Identity;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Proposal;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::PROPOSAL_NO;
+use isakmp::v1::parser::parse_packet_with_options;
+use isakmp::v1::parser::ParseOptions;
+
+fn transform(group_description: GroupDescription) -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description,
+        key_size: Some(128),
+        extra_attributes: vec![],
+    }
+}
+
+fn strict() -> ParseOptions {
+    ParseOptions {
+        strict_chaining: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn no_spi_by_default() {
+    let (msg, _) = MessageBuilder::new()
+        .add_transform(transform(GroupDescription::MODP_2048))
+        .build();
+
+    let packet = parse_packet_with_options(&msg, &strict()).unwrap();
+    let proposal = &packet.security_associations[0].proposal_payload[0];
+    assert_eq!(proposal.proposal_no, PROPOSAL_NO);
+    assert_eq!(proposal.spi_size, 0);
+    assert!(proposal.spi.is_empty());
+}
+
+#[test]
+fn non_zero_spi() {
+    let spi = vec![0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0];
+    let (msg, _) = MessageBuilder::new()
+        .spi(spi.clone())
+        .add_transform(transform(GroupDescription::MODP_2048))
+        .add_transform(transform(GroupDescription::MODP_1024))
+        .build();
+
+    let packet = parse_packet_with_options(&msg, &strict()).unwrap();
+    assert_eq!(packet.header.length as usize, msg.len());
+    let proposal = &packet.security_associations[0].proposal_payload[0];
+    assert_eq!(proposal.spi_size as usize, spi.len());
+    assert_eq!(proposal.spi, spi);
+    assert_eq!(proposal.transforms.len(), 2);
+}
+
+#[test]
+fn multiple_proposals() {
+    let (msg, _) = MessageBuilder::new()
+        .add_transform(transform(GroupDescription::MODP_2048))
+        .add_proposal(Proposal {
+            proposal_no: 2,
+            spi: vec![0xaa; 4],
+            transforms: vec![
+                transform(GroupDescription::MODP_1024),
+                transform(GroupDescription::MODP_1536),
+            ],
+        })
+        .add_proposal(Proposal {
+            proposal_no: 3,
+            spi: vec![],
+            transforms: vec![transform(GroupDescription::MODP_768)],
+        })
+        .build();
+
+    let packet = parse_packet_with_options(&msg, &strict()).unwrap();
+    assert_eq!(packet.header.length as usize, msg.len());
+    let proposals = &packet.security_associations[0].proposal_payload;
+    assert_eq!(
+        proposals.iter().map(|x| x.proposal_no).collect::<Vec<_>>(),
+        [PROPOSAL_NO, 2, 3]
+    );
+    assert_eq!(
+        proposals.iter().map(|x| x.next_payload).collect::<Vec<_>>(),
+        [
+            PayloadType::Proposal,
+            PayloadType::Proposal,
+            PayloadType::None
+        ]
+    );
+    assert_eq!(
+        proposals
+            .iter()
+            .map(|x| x.transforms.len())
+            .collect::<Vec<_>>(),
+        [1, 2, 1]
+    );
+    assert_eq!(proposals[1].spi, [0xaa; 4]);
+}
+
+#[test]
+fn proposals_without_added_transforms() {
+    let (msg, _) = MessageBuilder::new()
+        .aggressive_mode(Identity {
+            id_type: IdentificationType::ID_FQDN,
+            data: b"vpn.example.com".to_vec(),
+        })
+        .add_proposal(Proposal {
+            proposal_no: 5,
+            spi: vec![],
+            transforms: vec![transform(GroupDescription::MODP_1024)],
+        })
+        .build();
+
+    let packet = parse_packet_with_options(&msg, &strict()).unwrap();
+    let proposals = &packet.security_associations[0].proposal_payload;
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].proposal_no, 5);
+
+    // The key exchange matches the group of the first transform of the proposals
+    assert_eq!(packet.key_exchange_payloads[0].key_exchange_data.len(), 128);
+}
@@ -0,0 +1,55 @@
+//! The serialized format of transforms, which has to stay stable
+
+use isakmp::v1::definitions::AttributeType;
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::AttributeValue;
+use isakmp::v1::generator::Transform;
+
+fn transform() -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_2048,
+        key_size: Some(256),
+        extra_attributes: vec![],
+    }
+}
+
+#[test]
+fn field_and_variant_names() {
+    assert_eq!(
+        serde_json::to_string(&transform()).unwrap(),
+        r#"{"encryption_algorithm":"AES_CBC","hash_algorithm":"SHA2_256","authentication_method":"PreSharedKey","group_description":"MODP_2048","key_size":256}"#
+    );
+}
+
+#[test]
+fn roundtrip_with_extra_attributes() {
+    let transform = Transform {
+        key_size: None,
+        extra_attributes: vec![
+            (AttributeType::PRF, AttributeValue::Short(1)),
+            (AttributeType::FieldSize, AttributeValue::Long(vec![1, 2])),
+        ],
+        ..transform()
+    };
+
+    let json = serde_json::to_string(&transform).unwrap();
+    assert!(json.contains(
+        r#""key_size":null,"extra_attributes":[["PRF",{"Short":1}],["FieldSize",{"Long":[1,2]}]]"#
+    ));
+    assert_eq!(serde_json::from_str::<Transform>(&json).unwrap(), transform);
+}
+
+#[test]
+fn missing_extra_attributes() {
+    let json = r#"{"encryption_algorithm":"AES_CBC","hash_algorithm":"SHA2_256","authentication_method":"PreSharedKey","group_description":"MODP_2048","key_size":256}"#;
+    assert_eq!(
+        serde_json::from_str::<Transform>(json).unwrap(),
+        transform()
+    );
+}
@@ -0,0 +1,50 @@
+//! Setting the transform id of the built transforms
+
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+use isakmp::v1::generator::KEY_IKE;
+use isakmp::v1::parser::parse_packet;
+
+/// Header (28) + SA payload (8) + situation (4) + proposal payload (8)
+const TRANSFORM_OFFSET: usize = 48;
+
+/// Offset of the transform id within the transform payload
+const TRANSFORM_ID_OFFSET: usize = TRANSFORM_OFFSET + 5;
+
+fn builder() -> MessageBuilder {
+    MessageBuilder::new().add_transform(Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_2048,
+        key_size: Some(256),
+        extra_attributes: vec![],
+    })
+}
+
+fn transform_ids(msg: &[u8]) -> Vec<u8> {
+    let packet = parse_packet(msg).unwrap();
+    packet.security_associations[0].proposal_payload[0]
+        .transforms
+        .iter()
+        .map(|x| x.transform_id)
+        .collect()
+}
+
+#[test]
+fn default_transform_id() {
+    let (msg, _) = builder().build();
+    assert_eq!(msg[TRANSFORM_ID_OFFSET], KEY_IKE);
+    assert_eq!(transform_ids(&msg), [KEY_IKE]);
+}
+
+#[test]
+fn custom_transform_id() {
+    let (msg, _) = builder().transform_id(0xf0).build();
+    assert_eq!(msg[TRANSFORM_ID_OFFSET], 0xf0);
+    assert_eq!(transform_ids(&msg), [0xf0]);
+}
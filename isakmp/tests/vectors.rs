@@ -0,0 +1,148 @@
+//! Known-good serialized IKEv1 messages
+//!
+//! The vectors pin the exact bytes produced by [MessageBuilder] for a fixed initiator cookie.
+
+use isakmp::v1::definitions::AuthenticationMethod;
+use isakmp::v1::definitions::EncryptionAlgorithm;
+use isakmp::v1::definitions::GroupDescription;
+use isakmp::v1::definitions::HashAlgorithm;
+use isakmp::v1::generator::MessageBuilder;
+use isakmp::v1::generator::Transform;
+
+const INITIATOR_COOKIE: u64 = 0x0102_0304_0506_0708;
+
+fn transform_3des() -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::TrippleDES_CBC,
+        hash_algorithm: HashAlgorithm::SHA,
+        authentication_method: AuthenticationMethod::PreSharedKey,
+        group_description: GroupDescription::MODP_1024,
+        key_size: None,
+        extra_attributes: vec![],
+    }
+}
+
+fn transform_aes_256() -> Transform {
+    Transform {
+        encryption_algorithm: EncryptionAlgorithm::AES_CBC,
+        hash_algorithm: HashAlgorithm::SHA2_256,
+        authentication_method: AuthenticationMethod::RSASignatures,
+        group_description: GroupDescription::MODP_2048,
+        key_size: Some(256),
+        extra_attributes: vec![],
+    }
+}
+
+fn build(transforms: Vec<Transform>) -> Vec<u8> {
+    let mut mb = MessageBuilder::new().initiator_cookie(INITIATOR_COOKIE);
+    for transform in transforms {
+        mb = mb.add_transform(transform);
+    }
+    let (msg, initiator_cookie) = mb.build();
+    assert_eq!(initiator_cookie, INITIATOR_COOKIE);
+    msg
+}
+
+#[test]
+fn single_transform_without_key_size() {
+    #[rustfmt::skip]
+    let expected = [
+        // Header
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // initiator cookie
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // responder cookie
+        0x01, 0x10, 0x02, 0x00, // next payload, version, exchange type, flags
+        0x00, 0x00, 0x00, 0x00, // message id
+        0x00, 0x00, 0x00, 0x50, // length
+        // Security association
+        0x00, 0x00, 0x00, 0x34, // next payload, reserved, length
+        0x00, 0x00, 0x00, 0x01, // doi
+        0x00, 0x00, 0x00, 0x01, // situation
+        // Proposal
+        0x00, 0x00, 0x00, 0x28, // next payload, reserved, length
+        0x01, 0x01, 0x00, 0x01, // proposal no, protocol id, spi size, no of transforms
+        // Transform
+        0x00, 0x00, 0x00, 0x20, // next payload, reserved, length
+        0x00, 0x01, 0x00, 0x00, // transform no, transform id, reserved
+        0x80, 0x01, 0x00, 0x05, // encryption algorithm
+        0x80, 0x02, 0x00, 0x02, // hash algorithm
+        0x80, 0x03, 0x00, 0x01, // authentication method
+        0x80, 0x04, 0x00, 0x02, // group description
+        0x80, 0x0b, 0x00, 0x01, // life type
+        0x80, 0x0c, 0x1b, 0xa8, // life duration
+    ];
+
+    assert_eq!(build(vec![transform_3des()]), expected);
+}
+
+#[test]
+fn single_transform_with_key_size() {
+    #[rustfmt::skip]
+    let expected = [
+        // Header
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // initiator cookie
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // responder cookie
+        0x01, 0x10, 0x02, 0x00, // next payload, version, exchange type, flags
+        0x00, 0x00, 0x00, 0x00, // message id
+        0x00, 0x00, 0x00, 0x54, // length
+        // Security association
+        0x00, 0x00, 0x00, 0x38, // next payload, reserved, length
+        0x00, 0x00, 0x00, 0x01, // doi
+        0x00, 0x00, 0x00, 0x01, // situation
+        // Proposal
+        0x00, 0x00, 0x00, 0x2c, // next payload, reserved, length
+        0x01, 0x01, 0x00, 0x01, // proposal no, protocol id, spi size, no of transforms
+        // Transform
+        0x00, 0x00, 0x00, 0x24, // next payload, reserved, length
+        0x00, 0x01, 0x00, 0x00, // transform no, transform id, reserved
+        0x80, 0x01, 0x00, 0x07, // encryption algorithm
+        0x80, 0x02, 0x00, 0x04, // hash algorithm
+        0x80, 0x03, 0x00, 0x03, // authentication method
+        0x80, 0x04, 0x00, 0x0e, // group description
+        0x80, 0x0b, 0x00, 0x01, // life type
+        0x80, 0x0c, 0x1b, 0xa8, // life duration
+        0x80, 0x0e, 0x01, 0x00, // key length
+    ];
+
+    assert_eq!(build(vec![transform_aes_256()]), expected);
+}
+
+#[test]
+fn multiple_transforms() {
+    #[rustfmt::skip]
+    let expected = [
+        // Header
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // initiator cookie
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // responder cookie
+        0x01, 0x10, 0x02, 0x00, // next payload, version, exchange type, flags
+        0x00, 0x00, 0x00, 0x00, // message id
+        0x00, 0x00, 0x00, 0x74, // length
+        // Security association
+        0x00, 0x00, 0x00, 0x58, // next payload, reserved, length
+        0x00, 0x00, 0x00, 0x01, // doi
+        0x00, 0x00, 0x00, 0x01, // situation
+        // Proposal
+        0x00, 0x00, 0x00, 0x4c, // next payload, reserved, length
+        0x01, 0x01, 0x00, 0x02, // proposal no, protocol id, spi size, no of transforms
+        // First transform
+        0x03, 0x00, 0x00, 0x20, // next payload, reserved, length
+        0x00, 0x01, 0x00, 0x00, // transform no, transform id, reserved
+        0x80, 0x01, 0x00, 0x05, // encryption algorithm
+        0x80, 0x02, 0x00, 0x02, // hash algorithm
+        0x80, 0x03, 0x00, 0x01, // authentication method
+        0x80, 0x04, 0x00, 0x02, // group description
+        0x80, 0x0b, 0x00, 0x01, // life type
+        0x80, 0x0c, 0x1b, 0xa8, // life duration
+        // Second transform
+        0x00, 0x00, 0x00, 0x24, // next payload, reserved, length
+        0x01, 0x01, 0x00, 0x00, // transform no, transform id, reserved
+        0x80, 0x01, 0x00, 0x07, // encryption algorithm
+        0x80, 0x02, 0x00, 0x04, // hash algorithm
+        0x80, 0x03, 0x00, 0x03, // authentication method
+        0x80, 0x04, 0x00, 0x0e, // group description
+        0x80, 0x0b, 0x00, 0x01, // life type
+        0x80, 0x0c, 0x1b, 0xa8, // life duration
+        0x80, 0x0e, 0x01, 0x00, // key length
+    ];
+
+    assert_eq!(build(vec![transform_3des(), transform_aes_256()]), expected);
+}